@@ -0,0 +1,163 @@
+use crate::any::Any;
+use ratatui::{
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+};
+use std::ops::Range;
+
+// NOTE: converts the SGR escape sequences that e.g. `jq --color-output` writes into ratatui styles so that colored
+// output is rendered as such rather than as raw escapes; any other escape sequence is dropped
+pub struct Ansi;
+
+impl Ansi {
+    const ESCAPE: char = '\x1b';
+    const CSI_PREFIX: char = '[';
+    const SGR_FINAL: char = 'm';
+    const NUM_BASIC_COLORS: u8 = 8;
+
+    pub fn contains(line: &str) -> bool {
+        line.contains(Self::ESCAPE)
+    }
+
+    fn is_final_byte(chr: char) -> bool {
+        ('@'..='~').contains(&chr)
+    }
+
+    fn extended_color(params: &mut impl Iterator<Item = u8>) -> Option<Color> {
+        match params.next()? {
+            5 => Color::Indexed(params.next()?).some(),
+            2 => Color::Rgb(params.next()?, params.next()?, params.next()?).some(),
+            _mode => None,
+        }
+    }
+
+    fn apply(mut style: Style, params: &str) -> Style {
+        // NOTE: an empty parameter, e.g. in `ESC[m`, means reset
+        let mut params = params.split(';').map(|param| param.parse::<u8>().unwrap_or(0));
+
+        while let Some(param) = params.next() {
+            style = match param {
+                0 => Style::new(),
+                1 => style.add_modifier(Modifier::BOLD),
+                2 => style.add_modifier(Modifier::DIM),
+                3 => style.add_modifier(Modifier::ITALIC),
+                4 => style.add_modifier(Modifier::UNDERLINED),
+                7 => style.add_modifier(Modifier::REVERSED),
+                22 => style.remove_modifier(Modifier::BOLD | Modifier::DIM),
+                23 => style.remove_modifier(Modifier::ITALIC),
+                24 => style.remove_modifier(Modifier::UNDERLINED),
+                27 => style.remove_modifier(Modifier::REVERSED),
+                30..=37 => style.fg(Color::Indexed(param - 30)),
+                39 => style.fg(Color::Reset),
+                40..=47 => style.bg(Color::Indexed(param - 40)),
+                49 => style.bg(Color::Reset),
+                90..=97 => style.fg(Color::Indexed(param - 90 + Self::NUM_BASIC_COLORS)),
+                100..=107 => style.bg(Color::Indexed(param - 100 + Self::NUM_BASIC_COLORS)),
+                38 => Self::extended_color(&mut params).map_or(style, |color| style.fg(color)),
+                48 => Self::extended_color(&mut params).map_or(style, |color| style.bg(color)),
+                _param => style,
+            };
+        }
+
+        style
+    }
+
+    fn segments(line: &str) -> Vec<(Style, &str)> {
+        let mut segments = Vec::new();
+        let mut style = Style::new();
+        let mut rest = line;
+
+        while let Some(index) = rest.find(Self::ESCAPE) {
+            if 0 < index {
+                segments.push((style, &rest[..index]));
+            }
+
+            rest = &rest[index + Self::ESCAPE.len_utf8()..];
+
+            let Some(sequence) = rest.strip_prefix(Self::CSI_PREFIX) else {
+                continue;
+            };
+            let Some(end) = sequence.find(Self::is_final_byte) else {
+                return segments;
+            };
+
+            if sequence[end..].starts_with(Self::SGR_FINAL) {
+                style = Self::apply(style, &sequence[..end]);
+            }
+
+            // NOTE: the final byte is ascii, so it is a single byte long
+            rest = &sequence[end + 1..];
+        }
+
+        if !rest.is_empty() {
+            segments.push((style, rest));
+        }
+
+        segments
+    }
+
+    pub fn strip(line: &str) -> String {
+        Self::segments(line).into_iter().map(|(_style, text)| text).collect()
+    }
+
+    // NOTE: range is in graphemes of the visible text, i.e. escape sequences do not count towards it
+    pub fn line(line: &str, range: Range<usize>) -> Line<'static> {
+        let mut spans = Vec::new();
+        let mut column = 0;
+
+        for (style, text) in Self::segments(line) {
+            let len = text.len_graphemes();
+            let begin = range.start.saturating_sub(column).min(len);
+            let end = range.end.saturating_sub(column).min(len);
+
+            if begin < end {
+                Span::styled(text.substring(begin..end).to_owned(), style).push_to(&mut spans);
+            }
+
+            column += len;
+        }
+
+        spans.into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Ansi;
+    use ratatui::style::{Color, Modifier, Style};
+
+    #[test]
+    fn strip_drops_escape_sequences() {
+        assert_eq!(Ansi::strip("\x1b[1;31mred\x1b[0m plain"), "red plain");
+        assert_eq!(Ansi::strip("\x1b[2Kcleared"), "cleared");
+        assert_eq!(Ansi::strip("cut off\x1b[1;3"), "cut off");
+        assert_eq!(Ansi::strip("no escapes"), "no escapes");
+    }
+
+    #[test]
+    fn line_applies_sgr_styles() {
+        let line = Ansi::line("\x1b[1;31mkey\x1b[0m: \x1b[38;5;42mvalue\x1b[m", 0..usize::MAX);
+        let spans = line
+            .spans
+            .iter()
+            .map(|span| (span.content.as_ref(), span.style))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            spans,
+            [
+                ("key", Style::new().fg(Color::Indexed(1)).add_modifier(Modifier::BOLD)),
+                (": ", Style::new()),
+                ("value", Style::new().fg(Color::Indexed(42))),
+            ]
+        );
+    }
+
+    #[test]
+    fn line_range_counts_visible_text_only() {
+        let line = Ansi::line("\x1b[32mabc\x1b[0mdef", 2..4);
+        let spans = line.spans.iter().map(|span| span.content.as_ref()).collect::<Vec<_>>();
+
+        assert_eq!(spans, ["c", "d"]);
+    }
+}
@@ -95,6 +95,28 @@ pub trait Any {
         .some()
     }
 
+    fn human_bytes(self) -> String
+    where
+        Self: Sized + ToPrimitive,
+    {
+        const UNITS: [&str; 5] = ["B", "KiB", "MiB", "GiB", "TiB"];
+        const BASE: f64 = 1024.0;
+
+        let mut size = self.cast::<f64>();
+        let mut unit_idx = 0;
+
+        while BASE <= size && unit_idx < UNITS.len() - 1 {
+            size /= BASE;
+            unit_idx += 1;
+        }
+
+        if unit_idx == 0 {
+            format!("{size} {unit}", unit = UNITS[unit_idx])
+        } else {
+            format!("{size:.1} {unit}", unit = UNITS[unit_idx])
+        }
+    }
+
     fn indices(&self, text: &str) -> (usize, usize)
     where
         Self: RangeBounds<usize>,
@@ -1,111 +1,770 @@
 use crate::{
     any::Any,
-    channel::Channel,
-    cli_args::JqCliArgs,
-    input::Input,
-    jq_process::{JqOutput, JqProcessBuilder},
+    channel::{BroadcastSender, Channel},
+    cli_args::{
+        AppCliArgs, EmptyOutput, ErrorBorder, ErrorNotification, JqCliArgs, JsonVar, LayoutProfile, LayoutProfileEntry,
+    },
+    cli_flags::CliFlags,
+    command_palette::{CommandPalette, CommandPaletteEvent, PaletteEntry},
+    decoder::Decoder,
+    filter_history::FilterHistory,
+    flag_variant::FlagVariant,
+    flash::Flash,
+    global_search::{GlobalSearch, GlobalSearchEvent, Pane},
+    goto_line::{GotoLineEvent, GotoLinePrompt},
+    history_search::{HistorySearch, HistorySearchEvent},
+    input::{Input, InputConfig, InputFormat, InputSource},
+    input_matches::InputMatches,
+    input_set::{InputSet, InputTab},
+    jq_error::JqError,
+    jq_outline::{Definition, JqOutline, Outline, OutlineEvent},
+    jq_process::{JqConfig, JqOutput, JqProcess, JqProcessBuilder, JqProcessQueue, JsonArg},
+    jq_syntax::JqSyntax,
+    key_index::KeyIndex,
+    keymap::{Action, Keymap},
     line_editor_set::LineEditorSet,
+    navigation::{Navigation, NavigationEvent},
+    output_status::OutputStatus,
+    path_prompt::{PathPrompt, PathPromptEvent},
+    popup::Popup,
+    precision::Precision,
     rect_set::RectSet,
-    scroll::ScrollView,
+    scratch::ScratchEditor,
+    scroll::{ScrollSpeed, ScrollView},
+    scroll_memory::ScrollMemory,
+    search_prompt::{SearchPrompt, SearchPromptEvent},
+    shutdown_signal::ShutdownSignal,
     terminal::Terminal,
+    theme::Theme,
+    time_window::{TimeWindow, TimeWindowEditor},
+    transcript::Transcript,
 };
 use anyhow::Error;
-use crossterm::event::{Event, EventStream, KeyCode, KeyEvent, KeyModifiers, MouseEvent};
+use crossterm::event::{Event, EventStream, KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
 use futures::StreamExt;
-use ratatui::{layout::Rect, style::Color, Frame};
-use std::{io::Error as IoError, path::Path, time::Duration};
+use ratatui::{
+    layout::{Constraint, Layout, Position, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, BorderType, Tabs},
+    Frame,
+};
+use serde_json::Value;
+use std::{
+    collections::VecDeque,
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
 use tokio::time::Interval;
+use tokio_util::sync::CancellationToken;
+
+pub enum AppExit {
+    Accepted(Box<JqOutput>),
+    Signaled(&'static str),
+}
+
+// NOTE: how the panes are titled and scrolled and how outputs and errors are presented, which stays as the flags
+// set it for the whole session
+pub struct ViewConfig {
+    pub empty_output: EmptyOutput,
+    pub error_border: ErrorBorder,
+    pub error_notification: ErrorNotification,
+    pub input_scroll_speed: ScrollSpeed,
+    pub input_title: String,
+    pub output_preview_bytes: Option<usize>,
+    pub output_scroll_speed: ScrollSpeed,
+    pub output_title: String,
+    pub scroll_off: u16,
+}
+
+// NOTE: the second run of the filter with a flag toggled, which is shown next to the output while a flag variant is
+// selected
+struct VariantRun {
+    flag_variant: Option<FlagVariant>,
+    cancellation_token: CancellationToken,
+    output: Option<JqOutput>,
+    outputs: Channel<JqOutput>,
+}
+
+impl VariantRun {
+    fn new(cancellation_token: &CancellationToken) -> Self {
+        Self {
+            flag_variant: None,
+            cancellation_token: cancellation_token.child_token(),
+            output: None,
+            outputs: Channel::new(),
+        }
+    }
+}
+
+// NOTE: the modes that actions switch on and off while the app runs
+struct Toggles {
+    input_paused: bool,
+    line_numbers: bool,
+    skip_record_errors: bool,
+}
+
+// NOTE: the hints and warnings that the status line shows once they apply
+#[derive(Default)]
+struct StatusNotices {
+    keyboard_only: bool,
+    precision: bool,
+    slurp: bool,
+}
+
+// NOTE: how a changed output and an error get noticed, see --flash-on-output-update and --error-notification
+struct Notifications {
+    bell_pending: bool,
+    output_updated: bool,
+    flash_on_output_update: bool,
+}
+
+// NOTE: the popups and prompts drawn over the panes, each of which is open while it is set
+#[derive(Default)]
+struct Overlays {
+    command_palette: Option<CommandPalette<Action>>,
+    error_popup: bool,
+    global_search: Option<GlobalSearch>,
+    goto_line_prompt: Option<GotoLinePrompt>,
+    history_search: Option<HistorySearch>,
+    open_input_prompt: Option<PathPrompt>,
+    outline: Option<Outline>,
+    popup: Option<Popup>,
+    save_output_prompt: Option<PathPrompt>,
+    scratch_editor: Option<ScratchEditor>,
+    search_prompt: Option<SearchPrompt>,
+    time_window_editor: Option<TimeWindowEditor>,
+}
 
 pub struct App {
+    clipboard_pending: Option<String>,
+    cancellation_token: CancellationToken,
     event_stream: EventStream,
-    input: Input,
-    input_scroll_view: ScrollView,
+    filter_history: FilterHistory,
+    full_output_key: Option<(String, String)>,
+    input_config: InputConfig,
+    input_match_outputs: Channel<JqOutput>,
+    input_matches: InputMatches,
+    input_set: InputSet,
     interval: Interval,
+    jq_config: JqConfig,
     jq_output: JqOutput,
-    jq_outputs: Channel<Result<JqOutput, Error>>,
+    jq_outputs: Channel<JqOutput>,
+    jq_process_queue: JqProcessQueue,
+    key_index: KeyIndex,
+    key_index_source: Option<(usize, usize)>,
+    keymap: Keymap,
+    last_click: Option<(Instant, Position)>,
+    last_input_instant: Instant,
+    last_run_id: Option<u64>,
+    layout_profiles: Vec<(LayoutProfile, Theme)>,
+    layout_profile_idx: usize,
+    json_args: Vec<JsonArg>,
     line_editor_set: LineEditorSet,
-    output_block_color: Color,
+    navigation: Option<Navigation>,
+    notifications: Notifications,
+    output_block_flash: Flash,
+    output_focused: bool,
+    output_status: OutputStatus,
+    overlays: Overlays,
     rect_set: RectSet,
+    scroll_memory: ScrollMemory<String>,
+    selected_word: Option<String>,
+    status_notices: StatusNotices,
+    successful_jq_output: Option<JqOutput>,
+    theme: Theme,
+    time_field: String,
+    time_window: Option<TimeWindow>,
+    toggles: Toggles,
+    transcript: Transcript,
+    transcript_filepath: Option<PathBuf>,
+    variant_run: VariantRun,
+    view_config: ViewConfig,
 }
 
 impl App {
-    const COLOR_SUCCESS: Color = Color::Reset;
-    const COLOR_ERROR: Color = Color::Red;
-    const INPUT_BLOCK_TITLE: &'static str = "INPUT";
+    // NOTE: every action that the command palette offers, listed with the keys bound to it in the keymap
+    const ACTIONS: &'static [(Action, &'static str)] = &[
+        (Action::ToggleFlag("--slurp", 's'), "toggle --slurp"),
+        (Action::ToggleFlag("--raw-input", 'R'), "toggle --raw-input"),
+        (Action::ToggleFlag("--raw-output", 'r'), "toggle --raw-output"),
+        (Action::ToggleFlag("--compact-output", 'c'), "toggle --compact-output"),
+        (Action::ToggleFlag("--sort-keys", 'S'), "toggle --sort-keys"),
+        (Action::CopyOutput, "copy output"),
+        (Action::CopyFilter, "copy filter"),
+        (Action::ClearInput, "clear selected input"),
+        (Action::ToggleSkipRecordErrors, "skip records that raise errors"),
+        (Action::ToggleLineNumbers, "show line numbers"),
+        (Action::CycleLayout, "switch layout"),
+        (Action::ExpandFilter, "expand or collapse filter editor"),
+        (Action::SearchHistory, "search filter history"),
+        (Action::SearchPanes, "search input and output"),
+        (Action::Search, "search focused pane"),
+        (Action::NextMatch, "jump to next match"),
+        (Action::PreviousMatch, "jump to previous match"),
+        (Action::GotoLine, "jump focused pane to a line number"),
+        (Action::CopyLine, "copy output line under the cursor"),
+        (Action::CopyScreen, "copy visible output lines"),
+        (Action::CursorDown, "move output cursor down"),
+        (Action::CursorUp, "move output cursor up"),
+        (Action::ScrollOutputToLeft, "scroll output to the first column"),
+        (Action::ScrollOutputToRight, "scroll output to the last column"),
+        (Action::UnfocusOutput, "return from output pane to the editors"),
+        (Action::SetTimeWindow, "set time window"),
+        (Action::ToggleInputMatches, "highlight matching input records"),
+        (Action::PauseInput, "pause or resume input"),
+        (Action::FocusOutput, "focus output pane"),
+        (Action::ToggleFocus, "switch between filter and cli flags"),
+        (Action::ToggleFeedAll, "feed all inputs"),
+        (Action::TogglePretty, "pretty-print input"),
+        (Action::ToggleEditing, "edit input"),
+        (Action::SetVariable, "set session variable"),
+        (Action::LoadFullOutput, "load full output"),
+        (Action::CycleFlagVariant, "cycle flag variant"),
+        (Action::ShowError, "show error"),
+        (
+            Action::GotoDefinition,
+            "go to the definition of the function under the cursor",
+        ),
+        (Action::ShowOutline, "list the functions defined by the filter"),
+        (Action::OpenInput, "open input"),
+        (Action::SaveOutput, "save output"),
+        (Action::NextInput, "next input tab"),
+        (Action::PreviousInput, "previous input tab"),
+        (Action::ScrollUp, "scroll focused pane up"),
+        (Action::ScrollDown, "scroll focused pane down"),
+        (Action::ScrollLeft, "scroll focused pane left"),
+        (Action::ScrollRight, "scroll focused pane right"),
+        (Action::PageUp, "scroll focused pane a page up"),
+        (Action::PageDown, "scroll focused pane a page down"),
+        (Action::ScrollToTop, "scroll focused pane to the first line"),
+        (Action::ScrollToBottom, "scroll focused pane to the last line"),
+        (Action::ScrollToLeft, "scroll focused pane to the first column"),
+        (Action::ScrollToRight, "scroll focused pane to the last column"),
+    ];
+    const DOUBLE_CLICK_DURATION: Duration = Duration::from_millis(400);
+    const EMPTY_INPUT_MESSAGE: &'static str = "input is empty";
+    const EMPTY_OUTPUT_PLACEHOLDER: &'static str = "∅ no results";
+    const ERROR_ACTIONS: &'static str = "press enter to jump to the error in the filter or any other key to close";
+    const ERROR_POPUP_TITLE: &'static str = "JQ ERROR";
     const INTERVAL_DURATION: Duration = Duration::from_millis(50);
-    const OUTPUT_BLOCK_TITLE: &'static str = "OUTPUT";
+    const KEYBOARD_ONLY_NOTICE: &'static str = "mouse unavailable, running keyboard-only";
+    const NORMAL_MODE_BADGE: &'static str = "NORMAL (i to type)";
+    const OPEN_INPUT_TITLE: &'static str = "OPEN INPUT path, url, or !command";
+    const OUTPUT_ERROR_BADGE: &'static str = "[ERROR]";
+    const OUTPUT_UPDATED_BADGE: &'static str = "[updated]";
+    const PARTIAL_INPUT_BADGE: &'static str = "[partial input]";
+    const MAX_JSON_VAR_BYTES: usize = 128 * 1024;
+    const MODULE_DEFINITION_PREFIX: &'static str = "def ";
+    const POPUP_TITLE_SUFFIX: &'static str = "(press any key to close)";
     const QUIT_MESSAGE: &'static str = "quitting!";
+    const SAVE_OUTPUT_TITLE: &'static str = "SAVE OUTPUT AS";
+    const SKIPPED_POPUP_TITLE: &'static str = "SKIPPED RECORDS";
+    const SLURP_NOTICE: &'static str = "input has multiple json documents";
+    const SLURP_LONG_FLAG: &'static str = "--slurp";
+    const SLURP_SHORT_FLAG: char = 's';
+    const TOP_LEVEL_ARRAY_OPERATIONS: &'static [&'static str] = &[
+        "add", "first", "group_by", "last", "length", "map", "max", "min", "reverse", "sort", "unique",
+    ];
+    const INPUT_MATCHES_UNAVAILABLE_BADGE: &'static str = "matching records need ndjson input";
+    const INPUT_FEED_ALL_BADGE: &'static str = "[all inputs fed to jq]";
+    const INPUT_EDITING_BADGE: &'static str = "editing";
+    const INPUT_EDITING_STOP_HINT: &'static str = "esc to stop";
+    const INPUT_PAUSED_BADGE: &'static str = "paused";
+    const INPUT_PRETTY_BADGE: &'static str = "pretty-printed";
+    const STYLE_SELECTED_TAB: Style = Style::new().add_modifier(Modifier::REVERSED);
+    const STYLE_STALE: Style = Style::new().add_modifier(Modifier::DIM);
+    const VARIANT_PENDING_TITLE: &'static str = "VARIANT (running)";
+    const VARIANT_TITLE: &'static str = "VARIANT WITH:";
+    const WORD_ACTIONS: &'static str = "press i to insert it into the filter, y to copy it, or any other key to close";
 
     pub async fn new(
-        input_filepath: Option<&Path>,
+        input_filepaths: &[PathBuf],
         jq_cli_args: &JqCliArgs,
+        app_cli_args: &AppCliArgs,
         filter: Option<String>,
     ) -> Result<Self, Error> {
+        let clipboard_pending = None;
+        let cancellation_token = CancellationToken::new();
         let event_stream = EventStream::new();
-        let input = Self::input(input_filepath).await?;
-        let input_scroll_view = ScrollView::new();
+        let filter_history = FilterHistory::load(app_cli_args.history_filepath()).await;
+        let full_output_key = None;
+        let input_config = app_cli_args.input_config();
+        let input_match_outputs = Channel::new();
+        let input_matches = InputMatches::new();
+        let input_set = Self::input_set(input_filepaths, input_config, app_cli_args, &cancellation_token).await?;
         let interval = Self::interval();
+        let jq_config = app_cli_args.jq_config();
         let jq_output = JqOutput::empty();
         let jq_outputs = Channel::new();
-        let line_editor_set = LineEditorSet::new(jq_cli_args, filter);
-        let output_block_color = Self::COLOR_SUCCESS;
-        let rect_set = RectSet::empty();
-        let app = Self {
+        let jq_process_queue = JqProcessQueue::new(app_cli_args.max_jq_processes);
+        let key_index = KeyIndex::default();
+        let key_index_source = None;
+        let keymap = Keymap::load(app_cli_args.keymap_filepath(), &app_cli_args.key_bindings).await?;
+        let last_click = None;
+        let last_input_instant = Instant::now();
+        let last_run_id = None;
+        let json_args = Self::json_args(&app_cli_args.json_vars).await?;
+        let (layout_profiles, layout_profile_idx) = Self::layout_profiles(app_cli_args)?;
+        let (layout_profile, theme) = layout_profiles[layout_profile_idx];
+        let line_editor_set = LineEditorSet::new(jq_cli_args, filter, &theme);
+        let navigation = None;
+        let notifications = Notifications {
+            bell_pending: false,
+            output_updated: false,
+            flash_on_output_update: app_cli_args.flash_on_output_update,
+        };
+        let output_block_flash = Flash::new();
+        let overlays = Overlays::default();
+        let output_focused = false;
+        let output_status = OutputStatus::default();
+        let rect_set = RectSet::empty(layout_profile);
+        let scroll_memory = ScrollMemory::new();
+        let selected_word = None;
+        let status_notices = StatusNotices::default();
+        let successful_jq_output = None;
+        let time_field = app_cli_args.time_field.clone();
+        let toggles = Toggles {
+            input_paused: false,
+            line_numbers: app_cli_args.line_numbers,
+            skip_record_errors: app_cli_args.skip_record_errors,
+        };
+        let time_window = None;
+        let transcript = Transcript::new();
+        let transcript_filepath = app_cli_args.transcript_filepath.clone();
+        let variant_run = VariantRun::new(&cancellation_token);
+        let view_config = app_cli_args.view_config();
+        let mut app = Self {
+            clipboard_pending,
+            cancellation_token,
             event_stream,
-            input,
-            input_scroll_view,
+            filter_history,
+            full_output_key,
+            input_config,
+            input_match_outputs,
+            input_matches,
+            input_set,
             interval,
+            jq_config,
             jq_output,
             jq_outputs,
+            jq_process_queue,
+            key_index,
+            key_index_source,
+            keymap,
+            last_click,
+            last_input_instant,
+            last_run_id,
+            layout_profiles,
+            layout_profile_idx,
+            json_args,
             line_editor_set,
-            output_block_color,
+            navigation,
+            notifications,
+            output_block_flash,
+            output_focused,
+            output_status,
+            overlays,
             rect_set,
+            scroll_memory,
+            selected_word,
+            status_notices,
+            successful_jq_output,
+            theme,
+            time_field,
+            time_window,
+            toggles,
+            transcript,
+            transcript_filepath,
+            variant_run,
+            view_config,
         };
 
+        app.focus_output_if_editors_hidden();
+
         app.ok()
     }
 
-    async fn input(input_filepath: Option<&Path>) -> Result<Input, IoError> {
-        // NOTE:
-        // - if both an input filepath and `--null-input` are supplied, let `jq` determine what the output should be
-        //   by supplying both stdin and the --null-input flag
-        // - otherwise, if no input filepath is supplied, but `--null-input` is, definitely do not read from stdin
-        if let Some(input_filepath) = input_filepath {
-            Input::from_filepath(input_filepath).await?
-        } else {
-            Input::from_stdin()
+    // NOTE: bails before the terminal is set up so that scripts with optional data don't open an empty session
+    async fn input_set(
+        input_filepaths: &[PathBuf],
+        input_config: InputConfig,
+        app_cli_args: &AppCliArgs,
+        cancellation_token: &CancellationToken,
+    ) -> Result<InputSet, Error> {
+        let mut input_set = InputSet::new(
+            input_filepaths,
+            input_config,
+            app_cli_args.capture_input_filepath.as_deref(),
+            cancellation_token,
+        )
+        .await?;
+
+        if app_cli_args.exit_if_empty && input_set.is_empty().await? {
+            anyhow::bail!(Self::EMPTY_INPUT_MESSAGE);
+        }
+
+        input_set.ok()
+    }
+
+    // NOTE: the files are read and parsed once up front and passed as compact json to every jq run via `--argjson` so
+    // that `$name` is bound to the parsed contents while the main input still comes from the input files or stdin; the
+    // value goes on argv, so a file that isn't a single json document or that exceeds the per-argument limit of linux
+    // is rejected here rather than failing every run
+    async fn json_args(json_vars: &[JsonVar]) -> Result<Vec<JsonArg>, Error> {
+        let mut json_args = Vec::new();
+
+        for json_var in json_vars {
+            let name = json_var.name.clone();
+            let filepath = json_var.filepath.display();
+            let content = match tokio::fs::read_to_string(&json_var.filepath).await {
+                Ok(content) => content,
+                Err(error) => anyhow::bail!("unable to read --json-var {name} from {filepath}: {error}"),
+            };
+            let json = match serde_json::from_str::<Value>(&content) {
+                Ok(value) => value.to_string(),
+                Err(error) => anyhow::bail!("--json-var {name}: {filepath} is not a single json document: {error}"),
+            };
+
+            anyhow::ensure!(
+                json.len() < Self::MAX_JSON_VAR_BYTES,
+                "--json-var {name}: {filepath} is {} as compact json, which exceeds the {} that jq accepts as a single \
+                 argument",
+                json.len().human_bytes(),
+                Self::MAX_JSON_VAR_BYTES.human_bytes(),
+            );
+
+            JsonArg { name, json }.push_to(&mut json_args);
         }
-        .ok()
+
+        json_args.ok()
     }
 
     fn interval() -> Interval {
         tokio::time::interval(Self::INTERVAL_DURATION)
     }
 
-    fn render_scroll_view(frame: &mut Frame, rect: Rect, title: &str, color: Color, scroll_view: &mut ScrollView) {
-        scroll_view.render(frame, rect.decrement());
-        title.block().border_style(color).render_to(frame, rect);
+    fn render_scroll_view(
+        frame: &mut Frame,
+        rect: Rect,
+        block: Block,
+        scroll_view: &mut ScrollView,
+        theme: &Theme,
+        line_numbers: bool,
+    ) {
+        scroll_view.render(frame, rect.decrement(), theme, line_numbers);
+        block.render_to(frame, rect);
+    }
+
+    fn input_block_title(&self) -> String {
+        let input_tab = self.input_set.selected();
+        let content = input_tab.content();
+        let mut title = format!(
+            "{input_title} [{input_source} | {bytes} | {len_lines} lines",
+            input_title = self.view_config.input_title,
+            input_source = input_tab.source(),
+            bytes = content.len().human_bytes(),
+            len_lines = input_tab.len_lines(),
+        );
+
+        if let Some(input_format) = InputFormat::detect(content) {
+            title.push_str(" | ");
+            title.push_str(input_format.name());
+        }
+
+        if input_tab.is_pretty() {
+            title.push_str(" | ");
+            title.push_str(Self::INPUT_PRETTY_BADGE);
+        }
+
+        // NOTE: only inputs that are still being read have a rate worth showing, e.g. stdin or a command
+        if input_tab.is_loading() && !self.toggles.input_paused {
+            title.push_str(" | ");
+            title.push_str(&input_tab.receive_rate().summary());
+        }
+
+        title.push(']');
+
+        if self.input_set.feed_all() {
+            title.push(' ');
+            title.push_str(Self::INPUT_FEED_ALL_BADGE);
+        }
+
+        if self.toggles.input_paused {
+            let hint = self.keymap.hint(Action::PauseInput, "to resume");
+
+            title.push(' ');
+            title.push_str(&Self::badge(Self::INPUT_PAUSED_BADGE, hint));
+        }
+
+        if input_tab.is_editing() {
+            let hint = self
+                .keymap
+                .hint(Action::ToggleEditing, "or esc to stop")
+                .unwrap_or_else(|| Self::INPUT_EDITING_STOP_HINT.to_owned());
+
+            title.push(' ');
+            title.push_str(&Self::badge(Self::INPUT_EDITING_BADGE, hint.some()));
+        }
+
+        if self.input_matches.is_enabled() {
+            let hint = self.keymap.hint(Action::ToggleInputMatches, "to hide");
+            let badge = match self.input_matches.lines() {
+                Some(lines) => Self::badge(&format!("{} matching records", lines.len()), hint),
+                None => Self::badge(Self::INPUT_MATCHES_UNAVAILABLE_BADGE, hint),
+            };
+
+            title.push(' ');
+            title.push_str(&badge);
+        }
+
+        title
+    }
+
+    // NOTE: the hints name the keys currently bound to their action and are left out once it is unbound
+    fn badge(label: &str, hint: Option<String>) -> String {
+        match hint {
+            Some(hint) => format!("[{label}, {hint}]"),
+            None => format!("[{label}]"),
+        }
+    }
+
+    fn with_hint(text: &str, hint: Option<String>) -> String {
+        match hint {
+            Some(hint) => format!("{text} ({hint})"),
+            None => text.to_owned(),
+        }
+    }
+
+    fn render_input_tabs(&self, frame: &mut Frame, rect: Rect) {
+        let titles = self.input_set.tabs().iter().map(InputTab::source);
+
+        Tabs::new(titles)
+            .select(self.input_set.selected_idx())
+            .highlight_style(Self::STYLE_SELECTED_TAB)
+            .render_to(frame, rect);
+    }
+
+    // NOTE: only show tabs when there is more than one input to switch between
+    fn input_rects(&self) -> (Option<Rect>, Rect) {
+        if self.input_set.tabs().len() <= 1 {
+            return (None, self.rect_set.input);
+        }
+
+        let [tabs_rect, input_rect] =
+            Layout::vertical([Constraint::Length(1), Constraint::Fill(1)]).areas(self.rect_set.input);
+
+        (tabs_rect.some(), input_rect)
     }
 
     #[tracing::instrument(skip_all)]
     fn render_input(&mut self, frame: &mut Frame) {
-        Self::render_scroll_view(
-            frame,
-            self.rect_set.input,
-            Self::INPUT_BLOCK_TITLE,
-            Self::COLOR_SUCCESS,
-            &mut self.input_scroll_view,
-        );
+        let title = self.input_block_title();
+        let (tabs_rect, rect) = self.input_rects();
+
+        if let Some(tabs_rect) = tabs_rect {
+            self.render_input_tabs(frame, tabs_rect);
+        }
+
+        let input_tab = self.input_set.selected_mut();
+
+        if let Some(editor) = input_tab.editor_mut() {
+            editor.set_block(title.block().border_style(self.theme.border));
+            (&*editor).render_to(frame, rect);
+        } else {
+            Self::render_scroll_view(
+                frame,
+                rect,
+                title.as_str().block().border_style(self.theme.border),
+                input_tab.scroll_view_mut(),
+                &self.theme,
+                self.toggles.line_numbers,
+            );
+
+            // NOTE: record indices only map onto the raw lines, not onto the pretty-printed ones
+            if let Some(lines) = self.input_matches.lines().filter(|_lines| !input_tab.is_pretty()) {
+                input_tab
+                    .scroll_view()
+                    .highlight_lines(frame, rect.decrement(), lines, self.theme.matched);
+            }
+        }
+    }
+
+    // NOTE: the visible output can come from an older filter (e.g. when the current one fails), so mark it as stale
+    // by showing the filter that produced it
+    fn output_block_title(&self) -> Line<'static> {
+        let mut spans = Vec::new();
+
+        if self.output_status.is_error() {
+            spans.push(Span::styled(
+                format!("{} ", Self::OUTPUT_ERROR_BADGE),
+                self.theme.error.add_modifier(Modifier::BOLD),
+            ));
+        }
+
+        spans.push(Span::raw(self.view_config.output_title.clone()));
+        let filter = self.jq_output.filter();
+
+        if self.notifications.output_updated {
+            spans.push(Span::raw(format!(" {}", Self::OUTPUT_UPDATED_BADGE)));
+        }
+
+        if self.is_output_partial() {
+            spans.push(Span::styled(
+                format!(" {}", Self::PARTIAL_INPUT_BADGE),
+                self.theme.warning,
+            ));
+        }
+
+        let num_skipped = self.jq_output.skipped_errors().len();
+
+        if num_skipped > 0 {
+            spans.push(Span::styled(
+                format!(" [{num_skipped} records skipped]"),
+                self.theme.warning,
+            ));
+        }
+
+        if filter != self.line_editor_set.filter().content() {
+            spans.push(Span::styled(
+                format!(" from: {}", filter.replace('\n', " ")),
+                Self::STYLE_STALE,
+            ));
+        }
+
+        if self.status_notices.slurp {
+            if !spans.is_empty() {
+                spans.push(Span::raw(" | "));
+            }
+
+            spans.push(Span::styled(
+                Self::with_hint(
+                    Self::SLURP_NOTICE,
+                    self.keymap.hint(Action::EnableSlurp, "to enable --slurp"),
+                ),
+                self.theme.warning,
+            ));
+        }
+
+        spans.into()
+    }
+
+    fn output_border_style(&self) -> Style {
+        if self.output_block_flash.is_active() {
+            self.theme.flash
+        } else if self.output_status.is_error() {
+            self.theme.error
+        } else {
+            self.theme.success
+        }
+    }
+
+    fn error_border_type(&self) -> BorderType {
+        match self.view_config.error_border {
+            ErrorBorder::Plain => BorderType::Plain,
+            ErrorBorder::Double => BorderType::Double,
+            ErrorBorder::Thick => BorderType::Thick,
+        }
+    }
+
+    fn output_block(&self) -> Block<'static> {
+        let block = self
+            .output_block_title()
+            .block()
+            .border_style(self.output_border_style());
+
+        if let Some(error) = self.output_status.error() {
+            return block
+                .border_type(self.error_border_type())
+                .title_bottom(Span::styled(error.to_owned(), self.theme.error));
+        }
+
+        match self.jq_output.preview_bytes() {
+            Some(preview_bytes) => block.title_bottom(Span::styled(
+                Self::with_hint(
+                    &format!("truncated at {}", preview_bytes.human_bytes()),
+                    self.keymap.hint(Action::LoadFullOutput, "to load fully"),
+                ),
+                self.theme.warning,
+            )),
+            None => block,
+        }
+    }
+
+    fn render_empty_output_placeholder(frame: &mut Frame, rect: Rect) {
+        let rect = rect.decrement();
+        let rect = Rect::new(rect.x, rect.y + rect.height / 2, rect.width, rect.height.min(1));
+
+        Self::EMPTY_OUTPUT_PLACEHOLDER
+            .paragraph()
+            .centered()
+            .style(Self::STYLE_STALE)
+            .render_to(frame, rect);
     }
 
     #[tracing::instrument(skip_all)]
     fn render_output(&mut self, frame: &mut Frame) {
+        let block = self.output_block();
+        let (output_rect, variant_rect) = self.output_rects();
+
         Self::render_scroll_view(
             frame,
-            self.rect_set.output,
-            Self::OUTPUT_BLOCK_TITLE,
-            self.output_block_color,
+            output_rect,
+            block,
             self.jq_output.scroll_view_mut(),
+            &self.theme,
+            self.toggles.line_numbers,
+        );
+
+        if self.is_output_partial() {
+            frame.buffer_mut().set_style(output_rect.decrement(), Self::STYLE_STALE);
+        }
+
+        // NOTE: distinguish a successful run that produced no output from a blank pane
+        if self.jq_output.scroll_view().content().is_empty() {
+            Self::render_empty_output_placeholder(frame, output_rect);
+        }
+
+        if let Some(variant_rect) = variant_rect {
+            self.render_variant_output(frame, variant_rect);
+        }
+    }
+
+    // NOTE: results computed on partial input are misleading for slurp-style filters, so the output is marked as
+    // provisional while the fed input is still loading or until the run that saw its last lines has finished
+    fn is_output_partial(&self) -> bool {
+        self.input_set.is_loading() || self.jq_output.instant() < self.last_input_instant
+    }
+
+    // NOTE: the output pane is split in half while a flag variant is shown next to it
+    fn output_rects(&self) -> (Rect, Option<Rect>) {
+        if self.variant_run.flag_variant.is_none() {
+            return (self.rect_set.output, None);
+        }
+
+        let [output_rect, variant_rect] =
+            Layout::horizontal([Constraint::Fill(1), Constraint::Fill(1)]).areas(self.rect_set.output);
+
+        (output_rect, variant_rect.some())
+    }
+
+    fn render_variant_output(&mut self, frame: &mut Frame, rect: Rect) {
+        let Some(variant_output) = &mut self.variant_run.output else {
+            return Self::VARIANT_PENDING_TITLE.block().render_to(frame, rect);
+        };
+        let block = format!("{} {}", Self::VARIANT_TITLE, variant_output.cli_flags()).block();
+        let block = match variant_output.error() {
+            Some(error) => block.title_bottom(Span::styled(error.to_owned(), self.theme.error)),
+            None => block,
+        };
+
+        Self::render_scroll_view(
+            frame,
+            rect,
+            block,
+            variant_output.scroll_view_mut(),
+            &self.theme,
+            self.toggles.line_numbers,
         );
     }
 
@@ -119,128 +778,1610 @@ impl App {
 
     #[tracing::instrument(skip_all)]
     fn render_filter(&self, frame: &mut Frame) {
-        self.line_editor_set
-            .filter()
-            .text_area()
-            .render_to(frame, self.rect_set.filter);
+        let filter = self.line_editor_set.filter();
+
+        filter.text_area().render_to(frame, self.rect_set.filter);
+        JqSyntax::highlight(
+            frame.buffer_mut(),
+            self.rect_set.filter.decrement(),
+            &filter.content(),
+            filter.cursor(),
+            &self.theme,
+        );
+    }
+
+    #[tracing::instrument(skip_all)]
+    fn render_completion(&self, frame: &mut Frame) {
+        if let Some(completion) = self.line_editor_set.completion() {
+            completion.render(frame, self.rect_set.filter);
+        }
+    }
+
+    fn status_line(&self) -> Line<'static> {
+        let mut spans = Vec::new();
+
+        if self.navigation.is_some() {
+            spans.push(Span::styled(Self::NORMAL_MODE_BADGE, Self::STYLE_SELECTED_TAB));
+            spans.push(Span::raw(" "));
+        }
+
+        if self.status_notices.keyboard_only {
+            spans.push(Span::styled(
+                Self::with_hint(
+                    Self::KEYBOARD_ONLY_NOTICE,
+                    self.keymap.hint(Action::CommandPalette, "lists every action"),
+                ),
+                self.theme.warning,
+            ));
+        }
+
+        if self.status_notices.precision {
+            if !spans.is_empty() {
+                spans.push(Span::raw(" | "));
+            }
+
+            let warning = match &self.jq_config.precise_executable {
+                Some(precise_jq_bin) => format!("input has integers beyond 2^53, running with {precise_jq_bin}"),
+                None => "input has integers beyond 2^53, which jq rounds (see --precise-jq-bin)".to_owned(),
+            };
+
+            spans.push(Span::styled(warning, self.theme.warning));
+        }
+
+        if let Some(time_window) = &self.time_window {
+            if !spans.is_empty() {
+                spans.push(Span::raw(" | "));
+            }
+
+            spans.push(Span::styled(
+                Self::with_hint(
+                    &format!("time window {} on {}", time_window.description(), self.time_field),
+                    self.keymap.hint(Action::SetTimeWindow, "to change"),
+                ),
+                self.theme.warning,
+            ));
+        }
+
+        if let Some(run_id) = self.jq_output.run_id() {
+            if !spans.is_empty() {
+                spans.push(Span::raw(" | "));
+            }
+
+            spans.push(Span::raw(format!("run #{run_id}")));
+
+            if let Some(last_run_id) = self.last_run_id.filter(|last_run_id| *last_run_id != run_id) {
+                spans.push(Span::styled(format!(" (latest #{last_run_id})"), Self::STYLE_STALE));
+            }
+        }
+
+        let scroll_view = self.focused_scroll_view();
+
+        if let Some(search) = scroll_view.search() {
+            if !spans.is_empty() {
+                spans.push(Span::raw(" | "));
+            }
+
+            let position = match (scroll_view.current_match(), scroll_view.num_matches()) {
+                (_current_match, 0) => "no matches".to_owned(),
+                (Some(current_match), num_matches) => format!("{}/{num_matches}", current_match + 1),
+                (None, num_matches) => format!("{num_matches} matches"),
+            };
+
+            spans.push(Span::raw(format!("/{search}: {position}")));
+        }
+
+        let cli_flags = self.line_editor_set.cli_flags();
+
+        if cli_flags.is_focused() {
+            if let Some(description) = CliFlags::description_at(&cli_flags.content(), cli_flags.cursor_column()) {
+                if !spans.is_empty() {
+                    spans.push(Span::raw(" | "));
+                }
+
+                spans.push(Span::raw(description));
+            }
+        }
+
+        spans.into()
+    }
+
+    #[tracing::instrument(skip_all)]
+    fn render_status(&self, frame: &mut Frame) {
+        self.status_line().render_to(frame, self.rect_set.status);
     }
 
     #[tracing::instrument(skip_all)]
     fn render(&mut self, frame: &mut Frame) {
-        self.rect_set = RectSet::new(frame.area());
+        self.rect_set.resize(frame.area());
 
         self.render_input(frame);
         self.render_output(frame);
         self.render_filter(frame);
         self.render_cli_flags(frame);
-    }
+        self.render_status(frame);
+        self.render_completion(frame);
 
-    fn spawn_jq_process(&self) -> Result<(), Error> {
-        JqProcessBuilder {
-            cli_flags: self.line_editor_set.cli_flags().content(),
-            filter: self.line_editor_set.filter().content(),
-            input: self.input_scroll_view.content().as_bytes(),
-            jq_outputs_sender: self.jq_outputs.sender.clone(),
+        if let Some(popup) = &self.overlays.popup {
+            popup.render(frame);
         }
-        .build()?
-        .run()
-        .spawn_task()
-        .unit()
-        .ok()
-    }
-
-    async fn handle_key_event(&mut self, key_event: &KeyEvent) -> Result<Option<String>, Error> {
-        match key_event {
-            KeyEvent {
-                code: KeyCode::Char('c'),
-                modifiers: KeyModifiers::CONTROL,
-                ..
-            } => anyhow::bail!(Self::QUIT_MESSAGE),
-            KeyEvent {
-                code: KeyCode::Enter, ..
-            } => {
-                // NOTE: allow any recently spawned jq process to run and update self.jq_output before ending the
-                // program with this output value
-                tokio::time::sleep(Self::INTERVAL_DURATION).await;
-
-                self.jq_output.scroll_view_mut().take_content().some().ok()
-            }
-            _key_event => {
-                if self.line_editor_set.handle_key_event(*key_event) {
-                    self.spawn_jq_process()?;
-                }
 
-                None.ok()
-            }
+        if let Some(scratch_editor) = &mut self.overlays.scratch_editor {
+            scratch_editor.render(frame, &self.theme, &self.json_args);
         }
-    }
 
-    fn handle_mouse_event(&mut self, mouse_event: MouseEvent) {
-        let position = (mouse_event.column, mouse_event.row).into();
+        if let Some(time_window_editor) = &mut self.overlays.time_window_editor {
+            time_window_editor.render(frame, &self.theme, &self.time_field);
+        }
 
-        if self.rect_set.input.contains(position) {
-            &mut self.input_scroll_view
-        } else if self.rect_set.output.contains(position) {
-            self.jq_output.scroll_view_mut()
-        } else {
-            return;
+        if let Some(search_prompt) = &self.overlays.search_prompt {
+            search_prompt.render(frame);
+        }
+
+        if let Some(goto_line_prompt) = &mut self.overlays.goto_line_prompt {
+            goto_line_prompt.render(frame, &self.theme);
+        }
+
+        if let Some(global_search) = &mut self.overlays.global_search {
+            global_search.render(frame);
+        }
+
+        if let Some(outline) = &self.overlays.outline {
+            outline.render(frame);
+        }
+
+        if let Some(history_search) = &mut self.overlays.history_search {
+            let num_matches = self.filter_history.matches(history_search.query()).len();
+
+            history_search.render(frame, &self.theme, num_matches);
+        }
+
+        if let Some(command_palette) = &mut self.overlays.command_palette {
+            command_palette.render(frame);
         }
-        .handle_mouse_event(mouse_event);
+
+        if let Some(open_input_prompt) = &mut self.overlays.open_input_prompt {
+            open_input_prompt.render(frame, &self.theme);
+        }
+
+        if let Some(save_output_prompt) = &mut self.overlays.save_output_prompt {
+            save_output_prompt.render(frame, &self.theme);
+        }
+
+        self.theme.apply(frame.buffer_mut());
     }
 
-    fn handle_jq_output(&mut self, jq_output_res: Result<JqOutput, Error>) {
-        let jq_output = match jq_output_res {
-            Ok(jq_output) => {
-                self.output_block_color = Self::COLOR_SUCCESS;
+    fn uses_top_level_array_operation(filter: &str) -> bool {
+        let filter = filter.trim_start();
 
-                jq_output
-            }
-            Err(err) => {
-                self.output_block_color = Self::COLOR_ERROR;
+        Self::TOP_LEVEL_ARRAY_OPERATIONS.iter().any(|operation| {
+            filter
+                .strip_prefix(operation)
+                .is_some_and(|rest| !rest.starts_with(|chr: char| chr.is_alphanumeric() || chr == '_'))
+        })
+    }
 
-                return err.log_error();
-            }
-        };
+    // NOTE: array operations at the top level of the filter are applied to each document separately unless the
+    // documents are slurped into one array, which is rarely what is intended
+    fn slurp_hint(input: &str, cli_flags: &str, filter: &str) -> bool {
+        Self::uses_top_level_array_operation(filter)
+            && !CliFlags::contains(cli_flags, Self::SLURP_LONG_FLAG, Self::SLURP_SHORT_FLAG)
+            && InputFormat::is_multi_document(input)
+    }
 
-        // NOTE: keep scroll offset if the output changes
-        if self.jq_output.instant() < jq_output.instant() {
-            self.jq_output = jq_output.with_scroll_view_offset(&self.jq_output);
+    // NOTE: the index of the selected input is only rebuilt once another tab is selected or its content changes other
+    // than by appending lines, and is otherwise extended with just the appended lines
+    fn refresh_key_index(&mut self) {
+        let input_tab = self.input_set.selected();
+        let key_index_source = (self.input_set.selected_idx(), input_tab.revision()).some();
+
+        if self.key_index_source != key_index_source {
+            self.key_index = KeyIndex::default();
+            self.key_index_source = key_index_source;
         }
+
+        self.key_index.extend(input_tab.content());
     }
 
-    // NOTE:
-    // - Ok(Some(output)) => exit program successfully with the given output
-    // - Ok(None) => ignore the given input and continue running the program
-    // - Err(error) => exit program unsuccessfully with the given error
-    #[tracing::instrument(skip(self), fields(?event))]
-    async fn handle_event(&mut self, event: &Event) -> Result<Option<String>, Error> {
-        match event {
-            Event::Key(key_event) => self.handle_key_event(key_event).await,
-            Event::Mouse(mouse_event) => self.handle_mouse_event(*mouse_event).none().ok(),
-            ignored_event => tracing::debug!(?ignored_event).none().ok(),
+    fn pre_filter(&self) -> Option<String> {
+        self.time_window
+            .as_ref()
+            .map(|time_window| time_window.pre_filter(&self.time_field))
+    }
+
+    fn jq_process(
+        &self,
+        input: &str,
+        cli_flags: &str,
+        filter: &str,
+        preview_bytes: Option<usize>,
+        jq_outputs_sender: BroadcastSender<JqOutput>,
+        cancellation_token: &CancellationToken,
+    ) -> Result<JqProcess, Error> {
+        // NOTE: jq rounds integers beyond 2^53, so route runs through the precision-preserving engine once the input
+        // is known to contain any
+        let executable = match &self.jq_config.precise_executable {
+            Some(precise_executable) if self.status_notices.precision => precise_executable,
+            _precise_executable => &self.jq_config.executable,
+        };
+        let pre_filter = self.pre_filter();
+
+        JqProcessBuilder {
+            engine: self.jq_config.engine,
+            executable,
+            cli_flags,
+            filter,
+            pre_filter: pre_filter.as_deref(),
+            json_args: &self.json_args,
+            input: input.as_bytes(),
+            niceness: self.jq_config.niceness,
+            memory_limit_kb: self.jq_config.memory_limit_kb,
+            max_output_bytes: self.jq_config.max_output_bytes,
+            preview_bytes,
+            timeout: self.jq_config.timeout,
+            skip_record_errors: self.toggles.skip_record_errors,
+            clear_env: false,
+            jq_outputs_sender,
+            cancellation_token: cancellation_token.child_token(),
         }
+        .build()
     }
 
-    pub async fn run(&mut self) -> Result<String, Error> {
-        let mut terminal = Terminal::new()?;
+    fn spawn_jq_process(&mut self) -> Result<(), Error> {
+        self.line_editor_set.lint_filter();
 
-        // NOTE: spawn jq process to render initial output
-        self.spawn_jq_process()?;
+        let input = self.input_set.content();
+        let cli_flags = &self.line_editor_set.cli_flags().content();
+        let filter = &self.line_editor_set.filter().content();
+        let is_full_output = self
+            .full_output_key
+            .as_ref()
+            .is_some_and(|(full_cli_flags, full_filter)| full_cli_flags == cli_flags && full_filter == filter);
+        let preview_bytes = if is_full_output {
+            None
+        } else {
+            self.view_config.output_preview_bytes
+        };
 
-        loop {
-            tokio::select! {
-                _instant = self.interval.tick() => terminal.inner().draw(|frame| self.render(frame))?.unit(),
-                lines_res = self.input.next_lines() => {
-                    self.input_scroll_view.extend(&lines_res?);
-                    self.spawn_jq_process()?;
-                }
-                jq_output_res = self.jq_outputs.receiver.recv().unwrap_or_pending() => self.handle_jq_output(jq_output_res),
-                event_res = self.event_stream.next().unwrap_or_pending() => {
-                    if let Some(output_content) = self.handle_event(&event_res?).await? {
-                        return output_content.ok();
-                    }
-                }
-            }
+        self.status_notices.slurp = Self::slurp_hint(&input, cli_flags, filter);
+
+        let jq_process = self.jq_process(
+            &input,
+            cli_flags,
+            filter,
+            preview_bytes,
+            self.jq_outputs.sender.clone(),
+            &self.cancellation_token,
+        )?;
+
+        tracing::debug!(run_id = jq_process.run_id(), "queued jq run");
+
+        self.last_run_id = jq_process.run_id().some();
+        self.jq_process_queue.push(jq_process);
+        self.spawn_variant_jq_process()?;
+        self.spawn_input_match_jq_process()
+    }
+
+    // NOTE: the time window is applied to each record inside the match filter, where the outer one that every run gets
+    // only sees the null of --null-input and passes it through
+    fn spawn_input_match_jq_process(&mut self) -> Result<(), Error> {
+        if !self.input_matches.is_enabled() || self.input_set.feed_all() {
+            return self.input_matches.reset().ok();
         }
+
+        let input = self.input_set.selected().content().to_owned();
+        let Some(cli_flags) = InputMatches::cli_flags(&self.line_editor_set.cli_flags().content(), &input) else {
+            return self.input_matches.reset().ok();
+        };
+        let filter = InputMatches::filter(&self.line_editor_set.filter().content(), self.pre_filter().as_deref());
+        let cancellation_token = self.input_matches.restart(&self.cancellation_token);
+        let jq_process = self.jq_process(
+            &input,
+            &cli_flags,
+            &filter,
+            None,
+            self.input_match_outputs.sender.clone(),
+            &cancellation_token,
+        )?;
+
+        jq_process.run().spawn_task().unit().ok()
+    }
+
+    fn handle_input_match_output(&mut self, jq_output: &JqOutput) {
+        self.input_matches
+            .set_output(jq_output, self.input_set.selected().content());
+    }
+
+    fn toggle_input_matches(&mut self) -> Result<(), Error> {
+        self.input_matches.toggle();
+        self.spawn_input_match_jq_process()
+    }
+
+    // NOTE: only the newest variant run matters, so any previous one is cancelled rather than queued
+    fn spawn_variant_jq_process(&mut self) -> Result<(), Error> {
+        let Some(flag_variant) = self.variant_run.flag_variant else {
+            return ().ok();
+        };
+        let Some(cli_flags) = flag_variant.cli_flags(&self.line_editor_set.cli_flags().content()) else {
+            return ().ok();
+        };
+
+        self.variant_run.cancellation_token.cancel();
+        self.variant_run.cancellation_token = self.cancellation_token.child_token();
+
+        let input = self.input_set.content();
+        let jq_process = self.jq_process(
+            &input,
+            &cli_flags,
+            &self.line_editor_set.filter().content(),
+            self.view_config.output_preview_bytes,
+            self.variant_run.outputs.sender.clone(),
+            &self.variant_run.cancellation_token,
+        )?;
+
+        jq_process.run().spawn_task().unit().ok()
+    }
+
+    fn cycle_flag_variant(&mut self) -> Result<(), Error> {
+        self.variant_run.flag_variant = FlagVariant::next(self.variant_run.flag_variant);
+        self.variant_run.output = None;
+        self.variant_run.cancellation_token.cancel();
+
+        self.spawn_variant_jq_process()
+    }
+
+    fn handle_variant_output(&mut self, variant_output: JqOutput) {
+        self.variant_run.output = variant_output.some();
+    }
+
+    fn load_full_output(&mut self) -> Result<(), Error> {
+        if self.jq_output.preview_bytes().is_none() {
+            return ().ok();
+        }
+
+        self.full_output_key = (
+            self.line_editor_set.cli_flags().content().into_owned(),
+            self.line_editor_set.filter().content().into_owned(),
+        )
+            .some();
+
+        self.spawn_jq_process()
+    }
+
+    // NOTE: a truncated preview is not what should be written on accept, so jq is run once more without the preview
+    // limit to produce the complete result
+    async fn full_jq_output(&self, jq_output: JqOutput) -> Result<JqOutput, Error> {
+        if jq_output.preview_bytes().is_none() {
+            return jq_output.ok();
+        }
+
+        self.rerun_without_preview(&jq_output).await
+    }
+
+    async fn rerun_without_preview(&self, jq_output: &JqOutput) -> Result<JqOutput, Error> {
+        let input = self.input_set.content();
+        let mut jq_outputs = Channel::new();
+
+        self.jq_process(
+            &input,
+            jq_output.cli_flags(),
+            jq_output.filter(),
+            None,
+            jq_outputs.sender.clone(),
+            &self.cancellation_token,
+        )?
+        .run()
+        .await;
+
+        jq_outputs.receiver.try_recv()?.ok()
+    }
+
+    async fn handle_key_event(&mut self, key_event: &KeyEvent) -> Result<Option<JqOutput>, Error> {
+        self.status_notices.keyboard_only = false;
+
+        if self.overlays.popup.take().is_some() {
+            if let Some(word) = self.selected_word.take() {
+                self.handle_word_action(key_event, &word)?;
+            }
+
+            if self.overlays.error_popup.mem_take() && key_event.code == KeyCode::Enter {
+                self.jump_to_filter_error();
+            }
+
+            return None.ok();
+        }
+
+        if self.overlays.scratch_editor.is_some() {
+            return self.handle_scratch_key_event(key_event)?.none().ok();
+        }
+
+        if self.overlays.history_search.is_some() {
+            return self.handle_history_search_key_event(key_event)?.none().ok();
+        }
+
+        if self.overlays.command_palette.is_some() {
+            return self.handle_command_palette_key_event(key_event).await;
+        }
+
+        if self.overlays.time_window_editor.is_some() {
+            return self.handle_time_window_key_event(key_event)?.none().ok();
+        }
+
+        if self.overlays.search_prompt.is_some() {
+            return self.handle_search_key_event(key_event).none().ok();
+        }
+
+        if self.overlays.goto_line_prompt.is_some() {
+            return self.handle_goto_line_key_event(key_event).none().ok();
+        }
+
+        if self.overlays.global_search.is_some() {
+            return self.handle_global_search_key_event(key_event).none().ok();
+        }
+
+        if self.overlays.outline.is_some() {
+            return self.handle_outline_key_event(key_event).await.none().ok();
+        }
+
+        if self.overlays.open_input_prompt.is_some() {
+            return self.handle_open_input_key_event(key_event).await?.none().ok();
+        }
+
+        if self.overlays.save_output_prompt.is_some() {
+            return self.handle_save_output_key_event(key_event).await?.none().ok();
+        }
+
+        if let Some(action) = self.keymap.action(key_event) {
+            if self.is_action_enabled(action, key_event) {
+                return self.run_action(action).await;
+            }
+        }
+
+        if self.input_set.selected().is_editing() {
+            if key_event.code == KeyCode::Esc {
+                self.input_set.selected_mut().toggle_editing();
+            } else if self.input_set.selected_mut().handle_key_event(*key_event) {
+                self.spawn_jq_process()?;
+            }
+
+            return None.ok();
+        }
+
+        if self.navigation.is_some() {
+            return self.handle_navigation_key_event(key_event).none().ok();
+        }
+
+        // NOTE: keys that aren't bound to an action enabled in the output pane are ignored rather than typed into the
+        // editors
+        if self.output_focused {
+            return tracing::debug!(ignored_key_code = ?key_event.code).none().ok();
+        }
+
+        if key_event.code == KeyCode::Esc && self.line_editor_set.completion().is_none() {
+            self.navigation = Navigation::default().some();
+
+            return None.ok();
+        }
+
+        self.refresh_key_index();
+
+        if self.line_editor_set.handle_key_event(*key_event, &self.key_index) {
+            self.spawn_jq_process()?;
+        }
+
+        None.ok()
+    }
+
+    // NOTE: a bound key that doesn't apply in the current state falls through to whatever has focus, e.g. enter
+    // inserts a line break in the expanded filter and tab accepts the selected completion
+    fn is_action_enabled(&self, action: Action, key_event: &KeyEvent) -> bool {
+        if self.input_set.selected().is_editing() {
+            return action.is_global();
+        }
+
+        let completing = !self.output_focused
+            && self.line_editor_set.completion().is_some()
+            && matches!(
+                key_event.code,
+                KeyCode::Tab | KeyCode::Up | KeyCode::Down | KeyCode::Esc
+            );
+
+        match action {
+            _action if completing => false,
+            Action::Accept => self.output_focused || !self.line_editor_set.is_editing_multi_line(),
+            Action::EnableSlurp => self.status_notices.slurp,
+            Action::ToggleFocus => !self.output_focused,
+            Action::GotoDefinition => !self.output_focused && self.line_editor_set.filter().is_focused(),
+            Action::Search | Action::NextMatch | Action::PreviousMatch | Action::GotoLine => {
+                self.output_focused || self.navigation.is_some()
+            }
+            Action::CopyLine
+            | Action::CopyScreen
+            | Action::CursorDown
+            | Action::CursorUp
+            | Action::ScrollOutputToLeft
+            | Action::ScrollOutputToRight
+            | Action::UnfocusOutput => self.output_focused && self.navigation.is_none(),
+            _action => true,
+        }
+    }
+
+    fn open_command_palette(&mut self) {
+        let entries = Self::ACTIONS
+            .iter()
+            .map(|(action, title)| PaletteEntry {
+                action: *action,
+                title,
+                key: self.keymap.keys(*action),
+            })
+            .collect();
+
+        self.overlays.command_palette = CommandPalette::new(entries).some();
+    }
+
+    async fn accept(&mut self) -> Result<JqOutput, Error> {
+        // NOTE: allow any recently spawned jq process to run and update self.jq_output before ending the program with
+        // this output value
+        tokio::time::sleep(Self::INTERVAL_DURATION).await;
+
+        let jq_output = std::mem::replace(&mut self.jq_output, JqOutput::empty());
+
+        self.full_jq_output(jq_output).await
+    }
+
+    fn enable_slurp(&mut self) -> Result<(), Error> {
+        self.line_editor_set.append_cli_flag(Self::SLURP_LONG_FLAG);
+        self.spawn_jq_process()
+    }
+
+    fn toggle_input_paused(&mut self) {
+        self.toggles.input_paused = !self.toggles.input_paused;
+    }
+
+    fn start_open_input(&mut self) {
+        self.overlays.open_input_prompt = PathPrompt::new(Self::OPEN_INPUT_TITLE).some();
+    }
+
+    fn start_save_output(&mut self) {
+        self.overlays.save_output_prompt = PathPrompt::new(Self::SAVE_OUTPUT_TITLE).some();
+    }
+
+    fn start_scratch_editor(&mut self) {
+        self.overlays.scratch_editor = ScratchEditor::new().some();
+    }
+
+    fn start_global_search(&mut self) {
+        self.overlays.global_search = GlobalSearch::new().some();
+    }
+
+    fn start_time_window_editor(&mut self) {
+        self.overlays.time_window_editor = TimeWindowEditor::new(self.time_window.as_ref()).some();
+    }
+
+    fn toggle_feed_all(&mut self) -> Result<(), Error> {
+        self.input_set.toggle_feed_all();
+        self.spawn_jq_process()
+    }
+
+    fn toggle_cli_flag(&mut self, long_flag: &str, short_flag: char) -> Result<(), Error> {
+        let Some(cli_flags) = CliFlags::toggled(&self.line_editor_set.cli_flags().content(), long_flag, short_flag)
+        else {
+            return ().ok();
+        };
+
+        self.line_editor_set.set_cli_flags(&cli_flags);
+        self.spawn_jq_process()
+    }
+
+    fn clear_input(&mut self) -> Result<(), Error> {
+        self.input_set.selected_mut().clear();
+        self.spawn_jq_process()
+    }
+
+    // NOTE: the palette is closed before the action runs so that actions can open an overlay of their own
+    async fn handle_command_palette_key_event(&mut self, key_event: &KeyEvent) -> Result<Option<JqOutput>, Error> {
+        let Some(command_palette) = &mut self.overlays.command_palette else {
+            return None.ok();
+        };
+
+        match command_palette.handle_key_event(*key_event) {
+            Some(CommandPaletteEvent::Selected(action)) => {
+                self.overlays.command_palette = None;
+
+                self.run_action(action).await
+            }
+            Some(CommandPaletteEvent::Cancelled) => {
+                self.overlays.command_palette = None;
+
+                None.ok()
+            }
+            None => None.ok(),
+        }
+    }
+
+    async fn run_action(&mut self, action: Action) -> Result<Option<JqOutput>, Error> {
+        match action {
+            Action::Accept => self.accept().await?.some().ok(),
+            Action::ClearInput => self.clear_input()?.none().ok(),
+            Action::CommandPalette => self.open_command_palette().none().ok(),
+            Action::CopyFilter => {
+                self.clipboard_pending = self.line_editor_set.filter().content().into_owned().some();
+
+                None.ok()
+            }
+            Action::CopyLine => {
+                self.clipboard_pending = self.jq_output.scroll_view().cursor_line().map(str::to_owned);
+
+                None.ok()
+            }
+            Action::CopyOutput => {
+                self.clipboard_pending = self.jq_output.scroll_view().content().to_owned().some();
+
+                None.ok()
+            }
+            Action::CopyScreen => {
+                self.clipboard_pending = self.jq_output.scroll_view().visible_lines().join("\n").some();
+
+                None.ok()
+            }
+            Action::CursorDown => self.jq_output.scroll_view_mut().move_cursor_down().none().ok(),
+            Action::CursorUp => self.jq_output.scroll_view_mut().move_cursor_up().none().ok(),
+            Action::CycleFlagVariant => self.cycle_flag_variant()?.none().ok(),
+            Action::CycleLayout => self.cycle_layout_profile().none().ok(),
+            Action::EnableSlurp => self.enable_slurp()?.none().ok(),
+            Action::ExpandFilter => self.toggle_filter_expanded().none().ok(),
+            Action::FocusOutput | Action::UnfocusOutput => self.toggle_output_focus().none().ok(),
+            Action::LoadFullOutput => self.load_full_output()?.none().ok(),
+            Action::NextInput => self.select_input_tab(InputSet::select_next)?.none().ok(),
+            Action::NextMatch => self.jump_to_match(true).none().ok(),
+            Action::OpenInput => self.start_open_input().none().ok(),
+            Action::PageDown => self
+                .scroll_focused_pane(ScrollView::scroll_down, KeyModifiers::CONTROL)
+                .none()
+                .ok(),
+            Action::PageUp => self
+                .scroll_focused_pane(ScrollView::scroll_up, KeyModifiers::CONTROL)
+                .none()
+                .ok(),
+            Action::PauseInput => self.toggle_input_paused().none().ok(),
+            Action::PreviousInput => self.select_input_tab(InputSet::select_previous)?.none().ok(),
+            Action::PreviousMatch => self.jump_to_match(false).none().ok(),
+            Action::Quit => anyhow::bail!(Self::QUIT_MESSAGE),
+            Action::SaveOutput => self.start_save_output().none().ok(),
+            Action::ScrollDown => self
+                .scroll_focused_pane(ScrollView::scroll_down, KeyModifiers::NONE)
+                .none()
+                .ok(),
+            Action::ScrollLeft => self
+                .scroll_focused_pane(ScrollView::scroll_left, KeyModifiers::NONE)
+                .none()
+                .ok(),
+            Action::ScrollOutputToLeft => self.jq_output.scroll_view_mut().scroll_to_left().none().ok(),
+            Action::ScrollOutputToRight => self.jq_output.scroll_view_mut().scroll_to_right().none().ok(),
+            Action::ScrollRight => self
+                .scroll_focused_pane(ScrollView::scroll_right, KeyModifiers::NONE)
+                .none()
+                .ok(),
+            Action::ScrollUp => self
+                .scroll_focused_pane(ScrollView::scroll_up, KeyModifiers::NONE)
+                .none()
+                .ok(),
+            Action::ScrollToBottom => self.focused_scroll_view_mut().scroll_to_bottom().none().ok(),
+            Action::ScrollToLeft => self.focused_scroll_view_mut().scroll_to_left().none().ok(),
+            Action::ScrollToRight => self.focused_scroll_view_mut().scroll_to_right().none().ok(),
+            Action::ScrollToTop => self.focused_scroll_view_mut().scroll_to_top().none().ok(),
+            Action::Search => self.start_search().none().ok(),
+            Action::SearchPanes => self.start_global_search().none().ok(),
+            Action::SearchHistory => self.start_history_search().none().ok(),
+            Action::SetTimeWindow => self.start_time_window_editor().none().ok(),
+            Action::SetVariable => self.start_scratch_editor().none().ok(),
+            Action::ShowError => self.show_error_popup().none().ok(),
+            Action::GotoDefinition => self.goto_definition().await.none().ok(),
+            Action::GotoLine => self.start_goto_line().none().ok(),
+            Action::ShowOutline => self.show_outline().await.none().ok(),
+            Action::ToggleEditing => self.input_set.selected_mut().toggle_editing().none().ok(),
+            Action::ToggleFeedAll => self.toggle_feed_all()?.none().ok(),
+            Action::ToggleFlag(long_flag, short_flag) => self.toggle_cli_flag(long_flag, short_flag)?.none().ok(),
+            Action::ToggleFocus => self.line_editor_set.toggle_focus().none().ok(),
+            Action::ToggleInputMatches => self.toggle_input_matches()?.none().ok(),
+            Action::TogglePretty => self.input_set.selected_mut().toggle_pretty().none().ok(),
+            Action::ToggleLineNumbers => self.toggle_line_numbers().none().ok(),
+            Action::ToggleSkipRecordErrors => self.toggle_skip_record_errors()?.none().ok(),
+        }
+    }
+
+    // NOTE: the output pane while it is focused and the selected input otherwise, where ctrl scrolls a full page as it
+    // does with the mouse wheel
+    fn scroll_focused_pane(
+        &mut self,
+        scroll: fn(&mut ScrollView, KeyModifiers, ScrollSpeed),
+        key_modifiers: KeyModifiers,
+    ) {
+        if self.output_focused {
+            scroll(
+                self.jq_output.scroll_view_mut(),
+                key_modifiers,
+                self.view_config.output_scroll_speed,
+            );
+        } else {
+            scroll(
+                self.input_set.selected_mut().scroll_view_mut(),
+                key_modifiers,
+                self.view_config.input_scroll_speed,
+            );
+        }
+    }
+
+    fn toggle_line_numbers(&mut self) {
+        self.toggles.line_numbers = !self.toggles.line_numbers;
+    }
+
+    fn toggle_skip_record_errors(&mut self) -> Result<(), Error> {
+        self.toggles.skip_record_errors = !self.toggles.skip_record_errors;
+
+        self.spawn_jq_process()
+    }
+
+    async fn definitions(&self) -> Vec<Definition> {
+        let filter = self.line_editor_set.filter().content();
+        let library_paths =
+            JqOutline::library_paths(CliFlags::library_paths(&self.line_editor_set.cli_flags().content()));
+        let mut definitions = JqOutline::definitions(&filter, None);
+
+        definitions.extend(JqOutline::module_definitions(&filter, &library_paths).await);
+
+        definitions
+    }
+
+    async fn goto_definition(&mut self) {
+        let filter = self.line_editor_set.filter();
+        let cursor = filter.cursor();
+        let Some(name) = JqOutline::identifier_at(&filter.content(), cursor) else {
+            return;
+        };
+        let definitions = self.definitions().await;
+
+        match JqOutline::find(&definitions, &name, cursor) {
+            Some(definition) => self.jump_to_definition(definition).await,
+            None => self.notifications.bell_pending = true,
+        }
+    }
+
+    async fn show_outline(&mut self) {
+        self.overlays.outline = Outline::new(self.definitions().await).some();
+    }
+
+    async fn handle_outline_key_event(&mut self, key_event: &KeyEvent) {
+        let Some(outline) = &mut self.overlays.outline else {
+            return;
+        };
+
+        match outline.handle_key_event(*key_event) {
+            Some(OutlineEvent::Selected(definition)) => {
+                self.overlays.outline = None;
+                self.jump_to_definition(&definition).await;
+            }
+            Some(OutlineEvent::Cancelled) => self.overlays.outline = None,
+            None => {}
+        }
+    }
+
+    // NOTE: a definition in the filter moves the cursor of the filter editor to it, expanding the editor if the filter
+    // spans several lines, while one from a module is shown in a popup since modules can't be edited in rq
+    async fn jump_to_definition(&mut self, definition: &Definition) {
+        let Some(module) = &definition.module else {
+            if self.output_focused {
+                self.toggle_output_focus();
+            }
+
+            if 0 < definition.row && !self.line_editor_set.is_filter_expanded() {
+                self.toggle_filter_expanded();
+            }
+
+            return self.line_editor_set.focus_filter_at(definition.row, definition.column);
+        };
+        let Some(content) = tokio::fs::read_to_string(module).await.log_if_error() else {
+            return;
+        };
+        let mut lines = content.lines().skip(definition.row);
+        let first_line = lines.next().unwrap_or_default();
+        let body = lines.take_while(|line| !line.starts_with(Self::MODULE_DEFINITION_PREFIX));
+        let content = std::iter::once(first_line).chain(body).collect::<Vec<_>>().join("\n");
+        let title = format!(
+            "{}:{} {}",
+            module.display(),
+            definition.row + 1,
+            Self::POPUP_TITLE_SUFFIX
+        );
+
+        self.overlays.popup = Popup::new(title, content).some();
+    }
+
+    fn toggle_filter_expanded(&mut self) {
+        self.line_editor_set.toggle_filter_expanded();
+        self.rect_set
+            .set_filter_expanded(self.line_editor_set.is_filter_expanded());
+    }
+
+    // NOTE: the built-in layouts come first and a `--layout-profile` of the same name replaces one of them, while the
+    // others are appended in order; every profile gets its own theme so that switching to one is just a copy
+    fn layout_profiles(app_cli_args: &AppCliArgs) -> Result<(Vec<(LayoutProfile, Theme)>, usize), Error> {
+        let mut layout_profile_entries = LayoutProfileEntry::builtin();
+
+        for layout_profile_entry in &app_cli_args.layout_profiles {
+            match layout_profile_entries
+                .iter_mut()
+                .find(|entry| entry.name == layout_profile_entry.name)
+            {
+                Some(entry) => *entry = layout_profile_entry.clone(),
+                None => layout_profile_entry.clone().push_to(&mut layout_profile_entries),
+            }
+        }
+
+        let Some(layout_profile_idx) = layout_profile_entries
+            .iter()
+            .position(|entry| entry.name == app_cli_args.layout)
+        else {
+            anyhow::bail!("unknown layout profile {:?}", app_cli_args.layout);
+        };
+        let layout_profiles = layout_profile_entries
+            .into_iter()
+            .map(|entry| {
+                let theme_entries = app_cli_args
+                    .theme_entries
+                    .iter()
+                    .chain(&entry.theme_entries)
+                    .cloned()
+                    .collect::<Vec<_>>();
+
+                (entry.layout, Theme::new(app_cli_args.color, &theme_entries))
+            })
+            .collect();
+
+        (layout_profiles, layout_profile_idx).ok()
+    }
+
+    fn cycle_layout_profile(&mut self) {
+        self.layout_profile_idx = (self.layout_profile_idx + 1) % self.layout_profiles.len();
+
+        let (layout_profile, theme) = self.layout_profiles[self.layout_profile_idx];
+
+        self.rect_set.set_layout_profile(layout_profile);
+        self.theme = theme;
+        self.line_editor_set.set_theme(&theme);
+        self.focus_output_if_editors_hidden();
+    }
+
+    // NOTE: the review layout hides the editors, so the output pane takes the focus there and keeps it rather than
+    // passing keys on to editors that can't be seen
+    fn focus_output_if_editors_hidden(&mut self) {
+        if !self.output_focused && !self.rect_set.shows_editors() {
+            self.toggle_output_focus();
+        }
+    }
+
+    async fn handle_open_input_key_event(&mut self, key_event: &KeyEvent) -> Result<(), Error> {
+        let Some(open_input_prompt) = &mut self.overlays.open_input_prompt else {
+            return ().ok();
+        };
+
+        match open_input_prompt.handle_key_event(*key_event).await {
+            Some(PathPromptEvent::Submitted(text)) => match self.open_input(&text).await {
+                Ok(()) => {
+                    self.overlays.open_input_prompt = None;
+
+                    return self.spawn_jq_process();
+                }
+                Err(error) => {
+                    if let Some(open_input_prompt) = &mut self.overlays.open_input_prompt {
+                        open_input_prompt.set_error(&error);
+                    }
+                }
+            },
+            Some(PathPromptEvent::Cancelled) => self.overlays.open_input_prompt = None,
+            None => {}
+        }
+
+        ().ok()
+    }
+
+    async fn open_input(&mut self, text: &str) -> Result<(), Error> {
+        let input_source = text.parse::<InputSource>()?;
+        let input = Input::from_source(&input_source, self.input_config, self.cancellation_token.child_token()).await?;
+
+        self.input_set.add(input, input_source.name());
+
+        ().ok()
+    }
+
+    async fn handle_save_output_key_event(&mut self, key_event: &KeyEvent) -> Result<(), Error> {
+        let Some(save_output_prompt) = &mut self.overlays.save_output_prompt else {
+            return ().ok();
+        };
+
+        match save_output_prompt.handle_key_event(*key_event).await {
+            Some(PathPromptEvent::Submitted(text)) => match self.save_output(&text).await {
+                Ok(()) => self.overlays.save_output_prompt = None,
+                Err(error) => {
+                    if let Some(save_output_prompt) = &mut self.overlays.save_output_prompt {
+                        save_output_prompt.set_error(&error);
+                    }
+                }
+            },
+            Some(PathPromptEvent::Cancelled) => self.overlays.save_output_prompt = None,
+            None => {}
+        }
+
+        ().ok()
+    }
+
+    // NOTE: like on accept, a truncated preview is replaced by the complete result before it is written
+    async fn save_output(&self, text: &str) -> Result<(), Error> {
+        let output_filepath = Path::new(text.trim());
+
+        anyhow::ensure!(!text.trim().is_empty(), "expected a path to save the output to");
+        anyhow::ensure!(
+            !output_filepath.is_dir(),
+            "{} is a directory",
+            output_filepath.display()
+        );
+
+        let mut content = match self.jq_output.preview_bytes() {
+            Some(_preview_bytes) => self.rerun_without_preview(&self.jq_output).await?.into_content(),
+            None => self.jq_output.scroll_view().content().to_owned(),
+        };
+
+        if !content.is_empty() && !content.ends_with('\n') {
+            content.push('\n');
+        }
+
+        output_filepath.create().await?.write_all_and_flush(content).await?.ok()
+    }
+
+    // NOTE: a variable set again replaces its previous value
+    fn handle_scratch_key_event(&mut self, key_event: &KeyEvent) -> Result<(), Error> {
+        let Some(scratch_editor) = &mut self.overlays.scratch_editor else {
+            return ().ok();
+        };
+
+        match key_event.code {
+            KeyCode::Esc => self.overlays.scratch_editor = None,
+            KeyCode::Enter => match scratch_editor.json_arg() {
+                Ok(json_arg) => {
+                    self.json_args.retain(|existing| existing.name != json_arg.name);
+                    json_arg.push_to(&mut self.json_args);
+                    self.overlays.scratch_editor = None;
+
+                    return self.spawn_jq_process();
+                }
+                Err(error) => scratch_editor.set_error(&error),
+            },
+            _code => scratch_editor.handle_key_event(*key_event),
+        }
+
+        ().ok()
+    }
+
+    // NOTE: the filter being searched from is added to the history first so that it can be found again later on
+    fn start_history_search(&mut self) {
+        let filter = self.line_editor_set.filter().content().into_owned();
+
+        if !self.output_status.is_error() {
+            self.filter_history.push(filter.clone());
+        }
+
+        self.overlays.history_search = HistorySearch::new(filter).some();
+    }
+
+    // NOTE: every match is run as it is shown, so accepting only has to close the search
+    fn handle_history_search_key_event(&mut self, key_event: &KeyEvent) -> Result<(), Error> {
+        let Some(history_search) = &mut self.overlays.history_search else {
+            return ().ok();
+        };
+        let num_matches = self.filter_history.matches(history_search.query()).len();
+        let filter = match history_search.handle_key_event(*key_event, num_matches) {
+            None => return ().ok(),
+            Some(HistorySearchEvent::Changed) => {
+                let Some(filter) = self
+                    .filter_history
+                    .matches(history_search.query())
+                    .get(history_search.match_idx())
+                    .map(|filter| (*filter).to_owned())
+                else {
+                    return ().ok();
+                };
+
+                filter
+            }
+            Some(HistorySearchEvent::Accepted) => {
+                self.overlays.history_search = None;
+
+                return ().ok();
+            }
+            Some(HistorySearchEvent::Cancelled) => {
+                let original_filter = history_search.original_filter().to_owned();
+
+                self.overlays.history_search = None;
+
+                original_filter
+            }
+        };
+
+        if filter == self.line_editor_set.filter().content() {
+            return ().ok();
+        }
+
+        self.line_editor_set.set_filter(&filter);
+        self.spawn_jq_process()
+    }
+
+    fn handle_time_window_key_event(&mut self, key_event: &KeyEvent) -> Result<(), Error> {
+        let Some(time_window_editor) = &mut self.overlays.time_window_editor else {
+            return ().ok();
+        };
+
+        match key_event.code {
+            KeyCode::Esc => self.overlays.time_window_editor = None,
+            KeyCode::Enter => match time_window_editor.time_window() {
+                Ok(time_window) => {
+                    self.time_window = time_window;
+                    self.overlays.time_window_editor = None;
+
+                    return self.spawn_jq_process();
+                }
+                Err(error) => time_window_editor.set_error(&error),
+            },
+            _code => time_window_editor.handle_key_event(*key_event),
+        }
+
+        ().ok()
+    }
+
+    // NOTE: the content fed to jq only changes when not feeding all inputs
+    fn select_input_tab(&mut self, select: fn(&mut InputSet)) -> Result<(), Error> {
+        select(&mut self.input_set);
+
+        if self.input_set.feed_all() {
+            ().ok()
+        } else {
+            self.spawn_jq_process()
+        }
+    }
+
+    fn handle_input_lines(&mut self, idx: usize, lines: &VecDeque<String>) -> Result<(), Error> {
+        if !self.status_notices.precision {
+            self.status_notices.precision = lines.iter().any(|line| Precision::has_unsafe_integer(line));
+        }
+
+        self.input_set.extend(idx, lines);
+
+        if self.input_set.is_fed(idx) {
+            self.last_input_instant = Instant::now();
+            self.spawn_jq_process()?;
+        }
+
+        ().ok()
+    }
+
+    // NOTE: clamp scroll offsets to the new page sizes right away rather than waiting for the next render
+    fn handle_resize(&mut self, width: u16, height: u16) {
+        self.rect_set.resize(Rect::new(0, 0, width, height));
+
+        self.input_set
+            .selected_mut()
+            .scroll_view_mut()
+            .resize(self.rect_set.input.decrement().as_size());
+        self.jq_output
+            .scroll_view_mut()
+            .resize(self.rect_set.output.decrement().as_size());
+    }
+
+    fn decode_output_line(&mut self, row: u16) {
+        let scroll_view = self.jq_output.scroll_view();
+        let index = scroll_view
+            .offset()
+            .y
+            .saturating_add(row.saturating_sub(self.rect_set.output.decrement().y));
+        let Some(line) = scroll_view.line(index.cast()) else {
+            return;
+        };
+        let (title, content) = Decoder::decode(line).unwrap_or_else(|| {
+            (
+                "DECODE",
+                format!("unable to decode {line:?} as a jwt, url, or base64 value"),
+            )
+        });
+        let title = format!("{title} {}", Self::POPUP_TITLE_SUFFIX);
+
+        self.overlays.popup = Popup::new(title, content).some();
+    }
+
+    // NOTE: without an error to show, the errors of the records skipped by --skip-record-errors are listed instead
+    fn show_error_popup(&mut self) {
+        let Some(error) = self.output_status.error() else {
+            let skipped_errors = self.jq_output.skipped_errors();
+
+            if !skipped_errors.is_empty() {
+                self.overlays.popup =
+                    Popup::new(Self::SKIPPED_POPUP_TITLE.to_owned(), skipped_errors.join("\n")).some();
+            }
+
+            return;
+        };
+        let content = format!("{error}\n\n{}", Self::ERROR_ACTIONS);
+
+        self.overlays.popup = Popup::new(Self::ERROR_POPUP_TITLE.to_owned(), content).some();
+        self.overlays.error_popup = true;
+    }
+
+    // NOTE: leaves the output pane so that typing goes straight to the filter again
+    fn jump_to_filter_error(&mut self) {
+        let Some(error) = self.output_status.error() else {
+            return;
+        };
+        let filter = self.line_editor_set.filter().content();
+        let first_line = self.pre_filter().map_or(0, |pre_filter| pre_filter.lines().count());
+        let (row, column) = JqError::position(error, &filter, first_line).unwrap_or_else(|| {
+            let last_line = filter.rsplit('\n').next().unwrap_or_default();
+
+            (filter.matches('\n').count(), last_line.chars().count())
+        });
+
+        if self.output_focused {
+            self.toggle_output_focus();
+        }
+
+        self.line_editor_set.focus_filter_at(row, column);
+    }
+
+    // NOTE: the cursor only exists while the output pane is focused and starts at the top of the visible page
+    fn toggle_output_focus(&mut self) {
+        if self.output_focused && !self.rect_set.shows_editors() {
+            return;
+        }
+
+        self.output_focused = !self.output_focused;
+
+        let cursor = self
+            .output_focused
+            .then(|| self.jq_output.scroll_view().offset().y.cast());
+
+        self.jq_output.scroll_view_mut().set_cursor(cursor);
+    }
+
+    // NOTE: motions go to the output pane while it is focused and to the selected input otherwise, since the editors
+    // themselves are a line or a few lines at most
+    fn handle_navigation_key_event(&mut self, key_event: &KeyEvent) {
+        let Some(navigation) = &mut self.navigation else {
+            return;
+        };
+
+        match navigation.handle_key_event(key_event) {
+            Some(NavigationEvent::Motion(motion)) if self.output_focused => {
+                self.jq_output.scroll_view_mut().navigate(motion);
+            }
+            Some(NavigationEvent::Motion(motion)) => self.input_set.selected_mut().scroll_view_mut().navigate(motion),
+            Some(NavigationEvent::Insert) => {
+                self.navigation = None;
+
+                if self.output_focused {
+                    self.toggle_output_focus();
+                }
+            }
+            None => {}
+        }
+    }
+
+    // NOTE: the focused output pane, or the selected input otherwise, like the scrolling keys
+    fn focused_scroll_view(&self) -> &ScrollView {
+        if self.output_focused {
+            self.jq_output.scroll_view()
+        } else {
+            self.input_set.selected().scroll_view()
+        }
+    }
+
+    fn focused_scroll_view_mut(&mut self) -> &mut ScrollView {
+        if self.output_focused {
+            self.jq_output.scroll_view_mut()
+        } else {
+            self.input_set.selected_mut().scroll_view_mut()
+        }
+    }
+
+    fn start_goto_line(&mut self) {
+        self.overlays.goto_line_prompt = GotoLinePrompt::new().some();
+    }
+
+    fn start_search(&mut self) {
+        let match_hints = [
+            self.keymap.hint(Action::NextMatch, "for the next match"),
+            self.keymap.hint(Action::PreviousMatch, "for the previous match"),
+        ]
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>();
+
+        self.overlays.search_prompt = SearchPrompt::new(self.focused_scroll_view().search(), &match_hints).some();
+    }
+
+    fn jump_to_match(&mut self, forward: bool) {
+        let scroll_off = self.view_config.scroll_off;
+
+        if !self.focused_scroll_view_mut().jump_to_match(forward, scroll_off) {
+            self.notifications.bell_pending = true;
+        }
+    }
+
+    // NOTE: the query becomes the search of both panes so that the value is highlighted wherever else it appears
+    fn handle_global_search_key_event(&mut self, key_event: &KeyEvent) {
+        let Some(global_search) = &mut self.overlays.global_search else {
+            return;
+        };
+        let input = self.input_set.selected().scroll_view();
+        let output = self.jq_output.scroll_view();
+
+        match global_search.handle_key_event(*key_event, input, output) {
+            Some(GlobalSearchEvent::Selected {
+                pane,
+                line_index,
+                query,
+            }) => {
+                self.overlays.global_search = None;
+                self.input_set
+                    .selected_mut()
+                    .scroll_view_mut()
+                    .set_search(query.clone().some());
+                self.jq_output.scroll_view_mut().set_search(query.some());
+
+                match pane {
+                    Pane::Input => self
+                        .input_set
+                        .selected_mut()
+                        .scroll_view_mut()
+                        .jump_to_line(line_index, self.view_config.scroll_off),
+                    Pane::Output => self
+                        .jq_output
+                        .scroll_view_mut()
+                        .jump_to_line(line_index, self.view_config.scroll_off),
+                }
+            }
+            Some(GlobalSearchEvent::Cancelled) => self.overlays.global_search = None,
+            None => {}
+        }
+    }
+
+    fn handle_goto_line_key_event(&mut self, key_event: &KeyEvent) {
+        let Some(goto_line_prompt) = &mut self.overlays.goto_line_prompt else {
+            return;
+        };
+
+        match goto_line_prompt.handle_key_event(*key_event) {
+            Some(GotoLineEvent::Submitted(line_index)) => {
+                let scroll_off = self.view_config.scroll_off;
+
+                self.overlays.goto_line_prompt = None;
+                self.focused_scroll_view_mut().jump_to_line(line_index, scroll_off);
+            }
+            Some(GotoLineEvent::Cancelled) => self.overlays.goto_line_prompt = None,
+            None => {}
+        }
+    }
+
+    fn handle_search_key_event(&mut self, key_event: &KeyEvent) {
+        let Some(search_prompt) = &mut self.overlays.search_prompt else {
+            return;
+        };
+
+        match search_prompt.handle_key_event(*key_event) {
+            Some(SearchPromptEvent::Submitted(search)) => {
+                self.overlays.search_prompt = None;
+                self.focused_scroll_view_mut().set_search(search.some());
+                self.jump_to_match(true);
+            }
+            Some(SearchPromptEvent::Cancelled) => self.overlays.search_prompt = None,
+            None => {}
+        }
+    }
+
+    fn handle_word_action(&mut self, key_event: &KeyEvent, word: &str) -> Result<(), Error> {
+        match key_event.code {
+            KeyCode::Char('i') if self.line_editor_set.insert_into_filter(word) => self.spawn_jq_process()?,
+            KeyCode::Char('y') => self.clipboard_pending = word.to_owned().some(),
+            _code => {}
+        }
+
+        ().ok()
+    }
+
+    fn word_at(scroll_view: &ScrollView, rect: Rect, position: Position) -> Option<String> {
+        let rect = rect.decrement();
+
+        if !rect.contains(position) {
+            return None;
+        }
+
+        let position = Position::new(position.x - rect.x, position.y - rect.y);
+
+        scroll_view.word_at(position).map(str::to_owned)
+    }
+
+    // NOTE: crossterm does not report double clicks, so two left clicks on the same cell in quick succession are
+    // treated as one
+    fn handle_left_click(&mut self, position: Position) {
+        let now = Instant::now();
+        let is_double_click = self.last_click.is_some_and(|(instant, last_position)| {
+            last_position == position && now.duration_since(instant) < Self::DOUBLE_CLICK_DURATION
+        });
+
+        if !is_double_click {
+            self.last_click = (now, position).some();
+
+            return;
+        }
+
+        self.last_click = None;
+
+        let (_tabs_rect, input_rect) = self.input_rects();
+        let word = if input_rect.contains(position) {
+            Self::word_at(self.input_set.selected().scroll_view(), input_rect, position)
+        } else if self.rect_set.output.contains(position) {
+            Self::word_at(self.jq_output.scroll_view(), self.rect_set.output, position)
+        } else {
+            None
+        };
+        let Some(word) = word else {
+            return;
+        };
+        let title = format!("WORD {}", Self::POPUP_TITLE_SUFFIX);
+        let content = format!("{word}\n\n{}", Self::WORD_ACTIONS);
+
+        self.overlays.popup = Popup::new(title, content).some();
+        self.selected_word = word.some();
+    }
+
+    fn handle_mouse_event(&mut self, mouse_event: MouseEvent) {
+        let position = (mouse_event.column, mouse_event.row).into();
+
+        if let MouseEventKind::Down(MouseButton::Left) = mouse_event.kind {
+            return self.handle_left_click(position);
+        }
+
+        if let MouseEventKind::Down(MouseButton::Right) = mouse_event.kind {
+            if self.rect_set.output.contains(position) {
+                self.decode_output_line(mouse_event.row);
+            }
+
+            return;
+        }
+
+        if self.rect_set.input.contains(position) {
+            self.input_set
+                .selected_mut()
+                .scroll_view_mut()
+                .handle_mouse_event(mouse_event, self.view_config.input_scroll_speed);
+        } else if self.rect_set.output.contains(position) {
+            self.notifications.output_updated = false;
+
+            self.jq_output
+                .scroll_view_mut()
+                .handle_mouse_event(mouse_event, self.view_config.output_scroll_speed);
+        } else if self.rect_set.filter.contains(position) {
+            self.line_editor_set.filter_mut().handle_mouse_event(mouse_event);
+            self.line_editor_set.dismiss_completion();
+        } else if self.rect_set.cli_flags.contains(position) {
+            self.line_editor_set.cli_flags_mut().handle_mouse_event(mouse_event);
+        }
+    }
+
+    // NOTE: only flag the update when the user is scrolled away from the top of an output whose content changed,
+    // otherwise the new content is already in plain sight
+    fn on_output_updated(&mut self, jq_output: &JqOutput) {
+        let scroll_view = self.jq_output.scroll_view();
+
+        if scroll_view.offset() == Position::ORIGIN || scroll_view.content() == jq_output.scroll_view().content() {
+            return;
+        }
+
+        self.notifications.output_updated = true;
+
+        if self.notifications.flash_on_output_update {
+            self.output_block_flash.start();
+        }
+    }
+
+    fn notify_error(&mut self) {
+        match self.view_config.error_notification {
+            ErrorNotification::Off => {}
+            ErrorNotification::Bell => self.notifications.bell_pending = true,
+            ErrorNotification::Flash => self.output_block_flash.start(),
+        }
+    }
+
+    // NOTE: results are ordered by run id rather than by when they arrive, so that a slow older run never replaces a
+    // newer result; the error of a run whose filter or cli-flags have since been edited is dropped as well since it no
+    // longer describes what is in the editors
+    fn is_outdated(&self, jq_output: &JqOutput) -> bool {
+        if !self.output_status.is_superseded_by(jq_output) {
+            return true;
+        }
+
+        jq_output.error().is_some()
+            && (jq_output.filter() != self.line_editor_set.filter().content()
+                || jq_output.cli_flags() != self.line_editor_set.cli_flags().content())
+    }
+
+    fn handle_jq_output(&mut self, jq_output: JqOutput) {
+        self.jq_process_queue.on_finished();
+
+        self.transcript.record(&jq_output);
+
+        if self.is_outdated(&jq_output) {
+            return tracing::debug!(run_id = jq_output.run_id(), "dropping outdated jq output");
+        }
+
+        if self.output_status.set(&jq_output) {
+            self.notify_error();
+        }
+
+        self.line_editor_set.set_filter_error(jq_output.error());
+
+        if let Some(error) = jq_output.error() {
+            error.log_error();
+
+            // NOTE: keep the previous output visible unless jq managed to write some partial output
+            if jq_output.scroll_view().content().is_empty() {
+                return;
+            }
+        } else {
+            // NOTE: the stale marker in the title makes it clear that the retained output is from an older filter
+            if matches!(self.view_config.empty_output, EmptyOutput::Retain)
+                && jq_output.scroll_view().content().is_empty()
+            {
+                return;
+            }
+        }
+
+        // NOTE: keep scroll offset if the output changes
+        self.on_output_updated(&jq_output);
+
+        let jq_output = jq_output.with_remembered_scroll_view_offset(&self.jq_output, &mut self.scroll_memory);
+        let previous_jq_output = std::mem::replace(&mut self.jq_output, jq_output);
+
+        // NOTE: the partial output of a failed run replaces the output shown, so the last successful output is set
+        // aside in case rq is terminated by a signal before another run succeeds
+        if self.jq_output.error().is_none() {
+            self.successful_jq_output = None;
+        } else if previous_jq_output.error().is_none() {
+            self.successful_jq_output = previous_jq_output.some();
+        }
+    }
+
+    pub async fn take_successful_output(&mut self) -> Result<Option<JqOutput>, Error> {
+        let jq_output = match self.successful_jq_output.take() {
+            Some(jq_output) => jq_output,
+            None if self.jq_output.error().is_none() => std::mem::replace(&mut self.jq_output, JqOutput::empty()),
+            None => return None.ok(),
+        };
+
+        self.full_jq_output(jq_output).await?.some().ok()
+    }
+
+    // NOTE:
+    // - Ok(Some(output)) => exit program successfully with the given output
+    // - Ok(None) => ignore the given input and continue running the program
+    // - Err(error) => exit program unsuccessfully with the given error
+    #[tracing::instrument(skip(self), fields(?event))]
+    async fn handle_event(&mut self, event: &Event) -> Result<Option<JqOutput>, Error> {
+        let output_offset = self.jq_output.scroll_view().offset();
+        let jq_output_res = match event {
+            Event::Key(key_event) => self.handle_key_event(key_event).await,
+            Event::Mouse(mouse_event) => self.handle_mouse_event(*mouse_event).none().ok(),
+            Event::Resize(width, height) => self.handle_resize(*width, *height).none().ok(),
+            ignored_event => tracing::debug!(?ignored_event).none().ok(),
+        };
+
+        // NOTE: the updated badge has served its purpose once the output is focused or scrolled, whether with the
+        // keyboard, the mouse, or a jump to a line or search match
+        if self.output_focused || self.jq_output.scroll_view().offset() != output_offset {
+            self.notifications.output_updated = false;
+        }
+
+        jq_output_res
+    }
+
+    fn on_tick(&mut self, terminal: &mut Terminal) -> Result<(), Error> {
+        if self.notifications.bell_pending.mem_take() {
+            terminal.bell()?;
+        }
+
+        if let Some(text) = self.clipboard_pending.take() {
+            terminal.copy(&text)?;
+        }
+
+        self.draw(terminal)
+    }
+
+    fn draw(&mut self, terminal: &mut Terminal) -> Result<(), Error> {
+        terminal.inner().draw(|frame| self.render(frame))?.unit().ok()
+    }
+
+    async fn write_transcript(&self) -> Result<(), Error> {
+        let Some(transcript_filepath) = &self.transcript_filepath else {
+            return ().ok();
+        };
+
+        tokio::fs::write(transcript_filepath, self.transcript.to_markdown())
+            .await?
+            .ok()
+    }
+
+    // NOTE: the final filter is what the session arrived at, so it is added to the history unless it failed; failing to
+    // save the history must not lose the accepted output, so errors are only logged
+    async fn save_filter_history(&mut self) {
+        if !self.output_status.is_error() {
+            self.filter_history
+                .push(self.line_editor_set.filter().content().into_owned());
+        }
+
+        self.filter_history.save().await.log_if_error();
+    }
+
+    // NOTE: the transcript and filter history are written whether the session ends by accepting the output or by
+    // quitting; like the history, failing to write the transcript is only logged so that the accepted output is kept
+    pub async fn run(&mut self) -> Result<AppExit, Error> {
+        let jq_output_res = self.run_loop().await;
+
+        self.write_transcript().await.log_if_error();
+        self.save_filter_history().await;
+
+        jq_output_res
+    }
+
+    async fn run_loop(&mut self) -> Result<AppExit, Error> {
+        let mut terminal = Terminal::new()?;
+        let mut shutdown_signal = ShutdownSignal::new()?;
+
+        self.status_notices.keyboard_only = !terminal.is_mouse_captured();
+
+        // NOTE: spawn jq process to render initial output
+        self.spawn_jq_process()?;
+
+        loop {
+            tokio::select! {
+                _instant = self.interval.tick() => self.on_tick(&mut terminal)?,
+                // NOTE: while paused, lines stay in the bounded input channels, which in turn applies backpressure to
+                // the producers, and are caught up on once resumed
+                (idx, lines_res) = self.input_set.next_lines(), if !self.toggles.input_paused => {
+                    self.handle_input_lines(idx, &lines_res?)?;
+                }
+                jq_output = self.jq_outputs.receiver.recv().unwrap_or_pending() => self.handle_jq_output(jq_output),
+                variant_output = self.variant_run.outputs.receiver.recv().unwrap_or_pending() => {
+                    self.handle_variant_output(variant_output);
+                }
+                input_match_output = self.input_match_outputs.receiver.recv().unwrap_or_pending() => {
+                    self.handle_input_match_output(&input_match_output);
+                }
+                signal = shutdown_signal.recv() => return AppExit::Signaled(signal).ok(),
+                event_res = self.event_stream.next().unwrap_or_pending() => {
+                    let event = event_res?;
+
+                    if let Some(jq_output) = self.handle_event(&event).await? {
+                        return AppExit::Accepted(jq_output.into()).ok();
+                    }
+
+                    // NOTE: redraw immediately on resize rather than waiting for the next interval tick
+                    if let Event::Resize(..) = event {
+                        self.draw(&mut terminal)?;
+                    }
+                }
+            }
+        }
+    }
+}
+
+// NOTE: cancel the input reader and any in-flight jq processes so nothing outlives the app
+impl Drop for App {
+    fn drop(&mut self) {
+        self.cancellation_token.cancel();
     }
 }
@@ -1,13 +1,80 @@
-use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender};
+use crate::any::Any;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use tokio::sync::mpsc::{Receiver, Sender, UnboundedReceiver, UnboundedSender};
+
+pub struct BroadcastSender<T> {
+    senders: Arc<Mutex<Vec<UnboundedSender<T>>>>,
+}
+
+impl<T> BroadcastSender<T> {
+    pub fn new() -> Self {
+        let senders = Arc::default();
+
+        Self { senders }
+    }
+
+    fn senders(&self) -> MutexGuard<'_, Vec<UnboundedSender<T>>> {
+        self.senders.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    pub fn subscribe(&self) -> UnboundedReceiver<T> {
+        let (sender, receiver) = tokio::sync::mpsc::unbounded_channel();
+
+        self.senders().push(sender);
+
+        receiver
+    }
+}
+
+impl<T: Clone> BroadcastSender<T> {
+    // NOTE: the value is moved into the last subscriber to avoid cloning in the common single-subscriber case
+    pub fn send(&self, value: T) {
+        let mut senders = self.senders();
+
+        senders.retain(|sender| !sender.is_closed());
+
+        let Some((last_sender, other_senders)) = senders.split_last() else {
+            return;
+        };
+
+        for sender in other_senders {
+            sender.send(value.clone()).log_if_error();
+        }
+
+        last_sender.send(value).log_if_error();
+    }
+}
+
+impl<T> Clone for BroadcastSender<T> {
+    fn clone(&self) -> Self {
+        let senders = self.senders.clone();
+
+        Self { senders }
+    }
+}
 
 pub struct Channel<T> {
     pub receiver: UnboundedReceiver<T>,
-    pub sender: UnboundedSender<T>,
+    pub sender: BroadcastSender<T>,
 }
 
 impl<T> Channel<T> {
     pub fn new() -> Self {
-        let (sender, receiver) = tokio::sync::mpsc::unbounded_channel();
+        let sender = BroadcastSender::new();
+        let receiver = sender.subscribe();
+
+        Self { receiver, sender }
+    }
+}
+
+pub struct BoundedChannel<T> {
+    pub receiver: Receiver<T>,
+    pub sender: Sender<T>,
+}
+
+impl<T> BoundedChannel<T> {
+    pub fn new(capacity: usize) -> Self {
+        let (sender, receiver) = tokio::sync::mpsc::channel(capacity);
 
         Self { receiver, sender }
     }
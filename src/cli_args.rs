@@ -1,10 +1,31 @@
-use crate::{any::Any, app::App};
+use crate::{
+    any::Any,
+    app::{App, AppExit, ViewConfig},
+    cli_flags::CliFlags,
+    config::Config,
+    doctor::{Doctor, DoctorCliArgs},
+    filter_history::FilterHistory,
+    input::InputConfig,
+    jq_process::{JqConfig, JqOutput},
+    keymap::Keymap,
+    scroll::ScrollSpeed,
+    server::{ServeCliArgs, Server},
+    shutdown_signal::ShutdownSignal,
+    theme::ThemeEntry,
+};
 use anyhow::Error;
-use clap::{Args, Parser};
+use clap::{Args, CommandFactory, Parser, Subcommand, ValueEnum};
 use std::{
-    fmt::{Display, Formatter, Result as FmtResult},
+    ffi::OsString,
+    fmt::{Display, Error as FmtError, Formatter, Result as FmtResult},
+    io::{Error as IoError, IsTerminal},
+    num::NonZeroUsize,
     path::{Path, PathBuf},
+    process::Stdio,
+    str::FromStr,
+    time::Duration,
 };
+use tokio::process::Command as ProcessCommand;
 use tracing_subscriber::{
     filter::LevelFilter, fmt::format::FmtSpan, layer::SubscriberExt, util::SubscriberInitExt, Layer,
 };
@@ -26,6 +47,33 @@ pub struct JqCliArgs {
 
     #[arg(long)]
     pub slurp: bool,
+
+    #[arg(long)]
+    pub seq: bool,
+
+    #[arg(long = "args", num_args = 1.., value_name = "STRING")]
+    pub positional_args: Vec<String>,
+
+    #[arg(long = "jsonargs", num_args = 1.., value_name = "JSON")]
+    pub positional_json_args: Vec<String>,
+}
+
+impl JqCliArgs {
+    fn write_positional(formatter: &mut Formatter, flag: &str, values: &[String]) -> FmtResult {
+        if values.is_empty() {
+            return ().ok();
+        }
+
+        formatter.write_str(flag)?;
+
+        for value in values {
+            let value = shlex::try_quote(value).map_err(|_error| FmtError)?;
+
+            write!(formatter, " {value}")?;
+        }
+
+        formatter.write_str(" ")
+    }
 }
 
 impl Display for JqCliArgs {
@@ -52,33 +100,451 @@ impl Display for JqCliArgs {
             formatter.write_str("--slurp ")?;
         }
 
-        ().ok()
+        if self.seq {
+            formatter.write_str("--seq ")?;
+        }
+
+        // NOTE: jq takes everything after --args/--jsonargs as positional values, so these go last
+        Self::write_positional(formatter, "--args", &self.positional_args)?;
+        Self::write_positional(formatter, "--jsonargs", &self.positional_json_args)
+    }
+}
+
+#[derive(Clone)]
+pub struct JsonVar {
+    pub name: String,
+    pub filepath: PathBuf,
+}
+
+impl FromStr for JsonVar {
+    type Err = Error;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let (name, filepath) = value
+            .split_once('=')
+            .ok_or_error::<(&str, &str)>("expected a json-var of the form NAME=PATH")?;
+        let name = name.to_owned();
+        let filepath = filepath.into();
+
+        Self { name, filepath }.ok()
+    }
+}
+
+// NOTE: how the output border is drawn while jq fails, in addition to its color, so that the state can be told apart
+// without relying on color
+#[derive(Clone, Copy, ValueEnum)]
+pub enum ErrorBorder {
+    Plain,
+    Double,
+    Thick,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum ErrorNotification {
+    Off,
+    Bell,
+    Flash,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum EmptyOutput {
+    Placeholder,
+    Retain,
+}
+
+// NOTE:
+// - explore: input and output side by side above the editors
+// - stacked: input above output, both using the full width
+// - review: output only, using the full screen
+#[derive(Clone, Copy, Debug, PartialEq, ValueEnum)]
+pub enum LayoutProfile {
+    Explore,
+    Stacked,
+    Review,
+}
+
+// NOTE: one `--layout-profile` value, e.g. `triage=review;border=dim;error=magenta+bold`, i.e. a name for one of the
+// layouts above followed by the theme entries that apply on top of the `--theme` ones while the profile is active; the
+// layouts themselves are profiles named after them, which a profile of the same name replaces
+#[derive(Clone)]
+pub struct LayoutProfileEntry {
+    pub name: String,
+    pub layout: LayoutProfile,
+    pub theme_entries: Vec<ThemeEntry>,
+}
+
+impl LayoutProfileEntry {
+    const SEPARATOR: char = ';';
+
+    pub fn builtin() -> Vec<Self> {
+        LayoutProfile::value_variants()
+            .iter()
+            .filter_map(|layout| {
+                let name = layout.to_possible_value()?.get_name().to_owned();
+
+                Self {
+                    name,
+                    layout: *layout,
+                    theme_entries: Vec::new(),
+                }
+                .some()
+            })
+            .collect()
+    }
+}
+
+impl FromStr for LayoutProfileEntry {
+    type Err = Error;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let mut specs = value.split(Self::SEPARATOR);
+        let (name, layout) = specs
+            .next()
+            .and_then(|spec| spec.split_once('='))
+            .ok_or_error::<(&str, &str)>("expected a layout profile of the form NAME=LAYOUT[;ELEMENT=STYLE...]")?;
+        let name = name.trim();
+
+        anyhow::ensure!(!name.is_empty(), "expected a layout profile name");
+
+        let layout = match LayoutProfile::from_str(layout.trim(), true) {
+            Ok(layout) => layout,
+            Err(error) => anyhow::bail!("layout profile {name:?}: {error}"),
+        };
+        let theme_entries = specs
+            .filter(|spec| !spec.trim().is_empty())
+            .map(ThemeEntry::from_str)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Self {
+            name: name.to_owned(),
+            layout,
+            theme_entries,
+        }
+        .ok()
+    }
+}
+
+#[derive(Args)]
+#[allow(clippy::struct_excessive_bools)]
+pub struct AppCliArgs {
+    #[arg(long, default_value = "INPUT")]
+    pub input_title: String,
+
+    #[arg(long, default_value = "OUTPUT")]
+    pub output_title: String,
+
+    #[arg(long)]
+    pub flash_on_output_update: bool,
+
+    #[arg(long, value_enum, default_value_t = ErrorNotification::Off)]
+    pub error_notification: ErrorNotification,
+
+    #[arg(long, value_enum, default_value_t = ErrorBorder::Thick)]
+    pub error_border: ErrorBorder,
+
+    #[arg(long, value_enum, default_value_t = EmptyOutput::Placeholder)]
+    pub empty_output: EmptyOutput,
+
+    #[arg(long, default_value = "explore", value_name = "PROFILE")]
+    pub layout: String,
+
+    #[arg(long = "layout-profile", value_name = "NAME=LAYOUT[;ELEMENT=STYLE...]")]
+    pub layout_profiles: Vec<LayoutProfileEntry>,
+
+    #[arg(long, value_enum, default_value_t = ColorPolicy::Auto)]
+    pub color: ColorPolicy,
+
+    #[arg(long = "theme", value_delimiter = ',', value_name = "ELEMENT=STYLE")]
+    pub theme_entries: Vec<ThemeEntry>,
+
+    #[arg(long, value_enum, default_value_t = Engine::Jq)]
+    pub engine: Engine,
+
+    #[arg(long, value_parser = clap::value_parser!(i32).range(-20..=19))]
+    pub jq_niceness: Option<i32>,
+
+    #[arg(long, env = "RQ_JQ_BIN", default_value = "jq")]
+    pub jq_bin: String,
+
+    #[arg(long)]
+    pub precise_jq_bin: Option<String>,
+
+    #[arg(long)]
+    pub jq_memory_limit_mb: Option<u64>,
+
+    #[arg(long)]
+    pub jq_max_output_mb: Option<u64>,
+
+    #[arg(long, default_value_t = 50)]
+    pub output_preview_mb: u64,
+
+    #[arg(long = "timeout", value_name = "MS")]
+    pub jq_timeout_ms: Option<u64>,
+
+    #[arg(long = "json-var", value_name = "NAME=PATH")]
+    pub json_vars: Vec<JsonVar>,
+
+    #[arg(long = "transcript")]
+    pub transcript_filepath: Option<PathBuf>,
+
+    #[arg(long = "capture-input", value_name = "PATH")]
+    pub capture_input_filepath: Option<PathBuf>,
+
+    #[arg(long = "history-file", value_name = "PATH")]
+    pub history_filepath: Option<PathBuf>,
+
+    #[arg(long, conflicts_with = "history_filepath")]
+    pub no_history: bool,
+
+    #[arg(long = "keymap-file", value_name = "PATH")]
+    pub keymap_filepath: Option<PathBuf>,
+
+    #[arg(long = "bind", value_name = "KEY=ACTION")]
+    pub key_bindings: Vec<String>,
+
+    #[arg(long)]
+    pub exit_if_empty: bool,
+
+    #[arg(long)]
+    pub skip_record_errors: bool,
+
+    #[arg(long)]
+    pub line_numbers: bool,
+
+    #[arg(long, default_value = "2")]
+    pub max_jq_processes: NonZeroUsize,
+
+    #[arg(long, default_value = "8192")]
+    pub input_channel_capacity: NonZeroUsize,
+
+    #[arg(long, default_value_t = 100)]
+    pub input_batch_window_ms: u64,
+
+    #[arg(long, default_value = "4096")]
+    pub input_batch_max_lines: NonZeroUsize,
+
+    #[arg(long)]
+    pub keep_input_cr: bool,
+
+    #[arg(long, value_name = "N")]
+    pub keep_last: Option<NonZeroUsize>,
+
+    #[arg(long, default_value = ".timestamp", value_name = "PATH")]
+    pub time_field: String,
+
+    #[arg(long, default_value_t = 1)]
+    pub input_scroll_lines: u16,
+
+    #[arg(long, default_value_t = 5)]
+    pub input_fast_scroll_lines: u16,
+
+    #[arg(long, default_value_t = 1)]
+    pub output_scroll_lines: u16,
+
+    #[arg(long, default_value_t = 5)]
+    pub output_fast_scroll_lines: u16,
+
+    #[arg(long, default_value_t = 3, value_name = "LINES")]
+    pub scroll_off: u16,
+}
+
+impl AppCliArgs {
+    const KIB: u64 = 1024;
+
+    pub fn input_config(&self) -> InputConfig {
+        InputConfig {
+            channel_capacity: self.input_channel_capacity,
+            batch_window: Duration::from_millis(self.input_batch_window_ms),
+            batch_max_lines: self.input_batch_max_lines,
+            strip_cr: !self.keep_input_cr,
+            keep_last: self.keep_last,
+        }
+    }
+
+    pub fn history_filepath(&self) -> Option<PathBuf> {
+        if self.no_history {
+            return None;
+        }
+
+        self.history_filepath.clone().or_else(FilterHistory::default_filepath)
+    }
+
+    pub fn keymap_filepath(&self) -> Option<PathBuf> {
+        self.keymap_filepath.clone().or_else(Keymap::default_filepath)
+    }
+
+    pub fn jq_config(&self) -> JqConfig {
+        JqConfig {
+            engine: self.engine,
+            executable: self.jq_bin.clone(),
+            precise_executable: self.precise_jq_bin.clone(),
+            niceness: self.jq_niceness,
+            memory_limit_kb: self.jq_memory_limit_mb.map(|mb| mb.saturating_mul(Self::KIB)),
+            max_output_bytes: self
+                .jq_max_output_mb
+                .map(|mb| mb.saturating_mul(Self::KIB).saturating_mul(Self::KIB).cast()),
+            timeout: self.jq_timeout_ms.map(Duration::from_millis),
+        }
+    }
+
+    // NOTE: a preview limit of zero disables truncated previews altogether
+    fn output_preview_bytes(&self) -> Option<usize> {
+        if self.output_preview_mb == 0 {
+            return None;
+        }
+
+        self.output_preview_mb
+            .saturating_mul(Self::KIB)
+            .saturating_mul(Self::KIB)
+            .cast::<usize>()
+            .some()
+    }
+
+    pub fn view_config(&self) -> ViewConfig {
+        ViewConfig {
+            empty_output: self.empty_output,
+            error_border: self.error_border,
+            error_notification: self.error_notification,
+            input_scroll_speed: self.input_scroll_speed(),
+            input_title: self.input_title.clone(),
+            output_preview_bytes: self.output_preview_bytes(),
+            output_scroll_speed: self.output_scroll_speed(),
+            output_title: self.output_title.clone(),
+            scroll_off: self.scroll_off,
+        }
+    }
+
+    fn input_scroll_speed(&self) -> ScrollSpeed {
+        ScrollSpeed {
+            normal: self.input_scroll_lines,
+            large: self.input_fast_scroll_lines,
+        }
     }
+
+    fn output_scroll_speed(&self) -> ScrollSpeed {
+        ScrollSpeed {
+            normal: self.output_scroll_lines,
+            large: self.output_fast_scroll_lines,
+        }
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum ColorPolicy {
+    Auto,
+    Always,
+    Never,
+}
+
+impl ColorPolicy {
+    const NO_COLOR: &'static str = "NO_COLOR";
+
+    // NOTE: per the NO_COLOR convention, color is disabled when the variable is set to any non-empty value unless color
+    // was explicitly asked for
+    pub fn is_colored(self) -> bool {
+        match self {
+            Self::Auto => std::env::var_os(Self::NO_COLOR).is_none_or(|no_color| no_color.is_empty()),
+            Self::Always => true,
+            Self::Never => false,
+        }
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum Engine {
+    Jq,
+    #[cfg(feature = "jaq")]
+    Jaq,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum LineEnding {
+    Lf,
+    Crlf,
+}
+
+impl LineEnding {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Lf => "\n",
+            Self::Crlf => "\r\n",
+        }
+    }
+}
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// Serve jq results over http instead of running the tui
+    Serve(ServeCliArgs),
+
+    /// Check the jq executable, terminal capabilities, and state files and print a diagnostic report
+    Doctor(DoctorCliArgs),
 }
 
 #[derive(Parser)]
+#[command(args_conflicts_with_subcommands = true)]
+#[allow(clippy::struct_excessive_bools)]
 pub struct CliArgs {
+    #[command(subcommand)]
+    command: Option<Command>,
+
     #[arg(long = "logs")]
     log_filepath: Option<PathBuf>,
 
     #[arg(long = "log-level", default_value_t = LevelFilter::INFO)]
     log_level_filter: LevelFilter,
 
-    #[arg(long = "out")]
-    output_filepath: Option<PathBuf>,
+    #[arg(long = "out", value_delimiter = ',')]
+    output_filepaths: Vec<PathBuf>,
+
+    #[arg(long)]
+    emit_meta: bool,
+
+    #[arg(long, conflicts_with = "emit_meta")]
+    print_filter_only: bool,
+
+    #[arg(long, value_name = "CMD", conflicts_with = "output_filepaths")]
+    on_accept: Option<String>,
+
+    #[arg(long, value_enum, default_value_t = LineEnding::Lf)]
+    line_ending: LineEnding,
+
+    #[arg(long)]
+    no_final_newline: bool,
+
+    #[arg(long)]
+    print_on_exit: bool,
+
+    #[arg(long, requires = "output_filepaths")]
+    out_on_signal: bool,
 
     #[command(flatten)]
     jq_cli_args: JqCliArgs,
 
+    #[command(flatten)]
+    app_cli_args: AppCliArgs,
+
     #[arg(long)]
     filter: Option<String>,
 
-    input_filepath: Option<PathBuf>,
+    #[arg(long = "config", value_name = "PATH")]
+    config_filepath: Option<PathBuf>,
+
+    #[arg(long, conflicts_with = "config_filepath")]
+    no_config: bool,
+
+    input_filepaths: Vec<PathBuf>,
 }
 
 impl CliArgs {
     const FMT_SPAN: FmtSpan = FmtSpan::CLOSE;
     const DEFAULT_LOG_FILEPATH_STR: &'static str = "/dev/null";
+    const SHELL: &'static str = "sh";
+    const STDOUT_FILEPATH_STR: &'static str = "-";
+    const JOIN_OUTPUT_LONG_FLAG: &'static str = "--join-output";
+    const JOIN_OUTPUT_SHORT_FLAG: char = 'j';
+    const CONFIG_EXCLUDED_FLAGS: &'static [&'static str] = &["config", "no-config", "help", "version"];
+    const CLAP_ERROR_PREFIX: &'static str = "error: ";
 
     fn default_log_filepath() -> &'static Path {
         Path::new(Self::DEFAULT_LOG_FILEPATH_STR)
@@ -107,22 +573,278 @@ impl CliArgs {
             .ok()
     }
 
-    pub async fn run(self) -> Result<(), Error> {
-        self.init_tracing().await?;
+    // NOTE: this runs after the app and thus the terminal have been torn down, so the command is free to use the
+    // terminal itself
+    async fn run_on_accept(on_accept: &str, output_value: String) -> Result<(), Error> {
+        let mut child = ProcessCommand::new(Self::SHELL)
+            .arg("-c")
+            .arg(on_accept)
+            .stdin(Stdio::piped())
+            .spawn()?;
+
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin.write_all_and_flush(output_value).await?;
+        }
 
-        let input_filepath = self.input_filepath.as_deref();
-        let output_value = App::new(input_filepath, &self.jq_cli_args, self.filter)
-            .await?
-            .run()
-            .await?;
+        let status = child.wait().await?;
 
-        if let Some(output_filepath) = &self.output_filepath {
-            output_filepath.create().await?.left()
-        } else {
-            tokio::io::stdout().right()
+        if !status.success() {
+            anyhow::bail!("on-accept command {on_accept:?} failed: {status}");
+        }
+
+        ().ok()
+    }
+
+    // NOTE: every line is stripped of any carriage return before the requested line ending is applied so that no stray
+    // `\r` ends up in the emitted output; the lines are split on `\n` rather than with str::lines() so that an output
+    // consisting of a single empty line, e.g. `""` with --raw-output, is kept
+    fn normalize_line_endings(output_value: &str, line_ending: LineEnding, final_newline: bool) -> String {
+        if output_value.is_empty() {
+            return String::new();
+        }
+
+        let line_ending = line_ending.as_str();
+        let mut normalized = output_value
+            .strip_suffix('\n')
+            .unwrap_or(output_value)
+            .split('\n')
+            .map(|line| line.trim_end_matches('\r'))
+            .collect::<Vec<_>>()
+            .join(line_ending);
+
+        if final_newline {
+            normalized.push_str(line_ending);
+        }
+
+        normalized
+    }
+
+    async fn write_output(output_filepath: Option<&Path>, output_value: &str) -> Result<(), IoError> {
+        match output_filepath {
+            Some(output_filepath) if output_filepath != Path::new(Self::STDOUT_FILEPATH_STR) => {
+                output_filepath.create().await?.left()
+            }
+            _output_filepath => tokio::io::stdout().right(),
         }
         .write_all_and_flush(output_value)
-        .await?
+        .await
+    }
+
+    // NOTE: every destination is attempted even if an earlier one fails, and all failures are reported together
+    async fn write_outputs(output_filepaths: &[PathBuf], output_value: &str) -> Result<(), Error> {
+        if output_filepaths.is_empty() {
+            return Self::write_output(None, output_value).await?.ok();
+        }
+
+        let mut errors = Vec::new();
+
+        for output_filepath in output_filepaths {
+            if let Err(error) = Self::write_output(output_filepath.as_path().some(), output_value).await {
+                format!("{}: {error}", output_filepath.display()).push_to(&mut errors);
+            }
+        }
+
+        if !errors.is_empty() {
+            anyhow::bail!("unable to write the output to {}", errors.join(", "));
+        }
+
+        ().ok()
+    }
+
+    // NOTE: the output already lands in the scrollback when it is written to stdout and stdout is the terminal
+    fn writes_to_terminal(&self) -> bool {
+        let writes_to_stdout = self.output_filepaths.is_empty()
+            || self
+                .output_filepaths
+                .iter()
+                .any(|output_filepath| output_filepath == Path::new(Self::STDOUT_FILEPATH_STR));
+
+        self.on_accept.is_none() && writes_to_stdout && std::io::stdout().is_terminal()
+    }
+
+    // NOTE: the tui is drawn on stderr, so that is where the terminal is when stdout is redirected
+    async fn print_to_terminal(output_value: &str) -> Result<(), IoError> {
+        tokio::io::stderr().write_all_and_flush(output_value).await
+    }
+
+    // NOTE: the config values are inserted ahead of the command-line args and everything is parsed again, so that they
+    // go through the same parsing and validation as the command-line flags; subcommands don't take the tui flags that
+    // the config sets, so they are run without it
+    async fn merge_config(self) -> Result<Self, Error> {
+        if self.no_config || self.command.is_some() {
+            return self.ok();
+        }
+
+        let Some(config_filepath) = self.config_filepath.clone().or_else(Config::default_filepath) else {
+            return self.ok();
+        };
+        let Some(config) = Config::load(&config_filepath).await? else {
+            return self.ok();
+        };
+        let command = Self::command();
+        let mut args = std::env::args_os();
+        let bin = args.next().unwrap_or_default();
+        let args = args.collect::<Vec<_>>();
+        let arg_matches = command
+            .clone()
+            .try_get_matches_from(std::iter::once(&bin).chain(&args))?;
+        let config_args = config.args(&command, &arg_matches, Self::CONFIG_EXCLUDED_FLAGS)?;
+
+        if config_args.is_empty() {
+            return self.ok();
+        }
+
+        Self::try_parse_from(
+            std::iter::once(bin)
+                .chain(config_args.into_iter().map(OsString::from))
+                .chain(args),
+        )?
         .ok()
     }
+
+    // NOTE: goes through the same parsing as merge_config but without any command-line args, so that every key and
+    // value in the config is checked, e.g. by `rq doctor`
+    pub fn validate_config(config: &Config) -> Result<(), Error> {
+        let command = Self::command();
+        let bin = command.get_name().to_owned();
+        let arg_matches = command.clone().try_get_matches_from([&bin])?;
+        let config_args = config.args(&command, &arg_matches, Self::CONFIG_EXCLUDED_FLAGS)?;
+
+        if let Err(error) = Self::try_parse_from(std::iter::once(bin).chain(config_args)) {
+            let error = error.to_string();
+            let error = error.lines().next().unwrap_or_default();
+            let error = error.strip_prefix(Self::CLAP_ERROR_PREFIX).unwrap_or(error);
+
+            anyhow::bail!("invalid config {}: {error}", config.filepath().display());
+        }
+
+        ().ok()
+    }
+
+    // NOTE: --print-filter-only is meant for shell widgets that compose a filter with rq and then paste it back onto the
+    // command line; the output of jq with --join-output ends without a newline, which is kept that way
+    fn output_value(&self, jq_output: JqOutput) -> Result<String, Error> {
+        let mut final_newline = !self.no_final_newline;
+        let output_value = if self.emit_meta {
+            serde_json::to_string(&jq_output.into_meta())?
+        } else if self.print_filter_only {
+            jq_output.filter().to_owned()
+        } else {
+            final_newline &= !CliFlags::contains(
+                jq_output.cli_flags(),
+                Self::JOIN_OUTPUT_LONG_FLAG,
+                Self::JOIN_OUTPUT_SHORT_FLAG,
+            );
+
+            jq_output.into_content()
+        };
+
+        Self::normalize_line_endings(&output_value, self.line_ending, final_newline).ok()
+    }
+
+    // NOTE: the terminal has already been restored by the time the app returns, and the output is only written to the
+    // --out files since stdout may well be the terminal that just went away
+    async fn on_signal(&self, app: &mut App, signal: &str) -> Result<(), Error> {
+        if self.out_on_signal {
+            if let Some(jq_output) = app.take_successful_output().await? {
+                let output_value = self.output_value(jq_output)?;
+
+                Self::write_outputs(&self.output_filepaths, &output_value).await?;
+            }
+        }
+
+        anyhow::bail!("received {signal}")
+    }
+
+    async fn run_app(mut self) -> Result<(), Error> {
+        let mut app = App::new(
+            &self.input_filepaths,
+            &self.jq_cli_args,
+            &self.app_cli_args,
+            self.filter.take(),
+        )
+        .await?;
+        let jq_output = match app.run().await? {
+            AppExit::Accepted(jq_output) => *jq_output,
+            AppExit::Signaled(signal) => return self.on_signal(&mut app, signal).await,
+        };
+        let output_value = self.output_value(jq_output)?;
+
+        if self.print_on_exit && !self.writes_to_terminal() {
+            Self::print_to_terminal(&output_value).await?;
+        }
+
+        if let Some(on_accept) = &self.on_accept {
+            return Self::run_on_accept(on_accept, output_value).await;
+        }
+
+        Self::write_outputs(&self.output_filepaths, &output_value).await
+    }
+
+    pub async fn run(self) -> Result<(), Error> {
+        let cli_args = self.merge_config().await?;
+
+        cli_args.init_tracing().await?;
+
+        match cli_args.command {
+            Some(Command::Serve(serve_cli_args)) => {
+                ShutdownSignal::run_until(Server::new(serve_cli_args).serve()).await
+            }
+            Some(Command::Doctor(doctor_cli_args)) => {
+                ShutdownSignal::run_until(Doctor::new(doctor_cli_args).run()).await
+            }
+            None => cli_args.run_app().await,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CliArgs, LayoutProfile, LayoutProfileEntry, LineEnding};
+
+    #[test]
+    fn normalize_line_endings_keeps_a_sole_empty_line() {
+        assert_eq!(CliArgs::normalize_line_endings("\n", LineEnding::Lf, true), "\n");
+        assert_eq!(CliArgs::normalize_line_endings("", LineEnding::Lf, true), "");
+    }
+
+    #[test]
+    fn normalize_line_endings_applies_the_line_ending() {
+        assert_eq!(
+            CliArgs::normalize_line_endings("a\r\nb\n", LineEnding::Crlf, true),
+            "a\r\nb\r\n"
+        );
+        assert_eq!(
+            CliArgs::normalize_line_endings("a\n\nb\n", LineEnding::Lf, false),
+            "a\n\nb"
+        );
+    }
+
+    #[test]
+    fn layout_profile_entry_reads_a_layout_and_theme_entries() {
+        let layout_profile_entry = "triage = Review;border=dim; error=magenta+bold;"
+            .parse::<LayoutProfileEntry>()
+            .unwrap();
+
+        assert_eq!(layout_profile_entry.name, "triage");
+        assert_eq!(layout_profile_entry.layout, LayoutProfile::Review);
+        assert_eq!(layout_profile_entry.theme_entries.len(), 2);
+    }
+
+    #[test]
+    fn layout_profile_entry_rejects_invalid_profiles() {
+        for value in ["triage", "=review", "triage=sideways", "triage=review;border=blinking"] {
+            assert!(value.parse::<LayoutProfileEntry>().is_err(), "{value}");
+        }
+    }
+
+    #[test]
+    fn layout_profile_entry_builtin_is_named_after_the_layouts() {
+        let names = LayoutProfileEntry::builtin()
+            .into_iter()
+            .map(|layout_profile_entry| layout_profile_entry.name)
+            .collect::<Vec<_>>();
+
+        assert_eq!(names, ["explore", "stacked", "review"]);
+    }
 }
@@ -0,0 +1,475 @@
+use crate::any::Any;
+use serde_json::Value;
+use std::path::PathBuf;
+
+pub struct CliFlagsError {
+    message: String,
+    invalid_flags: Vec<String>,
+}
+
+impl CliFlagsError {
+    fn new(message: String, invalid_flags: Vec<String>) -> Self {
+        Self { message, invalid_flags }
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    pub fn invalid_flags(&self) -> &[String] {
+        &self.invalid_flags
+    }
+}
+
+// NOTE: checks the cli-flags editor contents against the options jq accepts so that problems can be shown while
+// editing rather than only when the spawned jq rejects them
+pub struct CliFlags;
+
+impl CliFlags {
+    // NOTE: (flag, number of values the flag consumes)
+    const LONG_FLAGS: &'static [(&'static str, usize)] = &[
+        ("--arg", 2),
+        ("--args", 0),
+        ("--argjson", 2),
+        ("--ascii-output", 0),
+        ("--binary", 0),
+        ("--color-output", 0),
+        ("--compact-output", 0),
+        ("--exit-status", 0),
+        ("--from-file", 1),
+        ("--indent", 1),
+        ("--join-output", 0),
+        ("--jsonargs", 0),
+        ("--monochrome-output", 0),
+        ("--null-input", 0),
+        ("--raw-input", 0),
+        ("--raw-output", 0),
+        ("--raw-output0", 0),
+        ("--rawfile", 2),
+        ("--seq", 0),
+        ("--slurp", 0),
+        ("--slurpfile", 2),
+        ("--sort-keys", 0),
+        ("--stream", 0),
+        ("--stream-errors", 0),
+        ("--tab", 0),
+        ("--unbuffered", 0),
+    ];
+    const SHORT_FLAGS: &'static str = "CMSabcejnrs";
+    const SHORT_FLAGS_WITH_VALUE: &'static str = "Lf";
+    // NOTE: the flags that make jq read a file named in the flags themselves, i.e. everything taking a path
+    const FILE_FLAGS: &'static [&'static str] = &["--from-file", "--rawfile", "--slurpfile"];
+    const POSITIONAL_FLAGS: &'static [&'static str] = &["--args", Self::JSON_ARGS_FLAG];
+    const JSON_ARGS_FLAG: &'static str = "--jsonargs";
+    const LIBRARY_PATH_FLAG: &'static str = "-L";
+    // NOTE: (long flag, short flag, one-line description) as summarized from the jq manual
+    const DESCRIPTIONS: &'static [(&'static str, Option<char>, &'static str)] = &[
+        ("--arg", None, "--arg name value: bind $name to the string value"),
+        (
+            "--args",
+            None,
+            "--args: treat the remaining arguments as positional strings in $ARGS.positional",
+        ),
+        (
+            "--argjson",
+            None,
+            "--argjson name json: bind $name to the parsed json value",
+        ),
+        (
+            "--ascii-output",
+            Some('a'),
+            "-a, --ascii-output: escape non-ascii characters in the output",
+        ),
+        (
+            "--binary",
+            Some('b'),
+            "-b, --binary: do not convert line endings on windows",
+        ),
+        (
+            "--color-output",
+            Some('C'),
+            "-C, --color-output: colorize the output even when not writing to a terminal",
+        ),
+        (
+            "--compact-output",
+            Some('c'),
+            "-c, --compact-output: print each result on a single line",
+        ),
+        (
+            "--exit-status",
+            Some('e'),
+            "-e, --exit-status: set the exit status from the last output (false/null fail)",
+        ),
+        (
+            "--from-file",
+            Some('f'),
+            "-f, --from-file file: read the filter from a file",
+        ),
+        (
+            "--indent",
+            None,
+            "--indent n: indent the output with n spaces (at most 7)",
+        ),
+        (
+            "--join-output",
+            Some('j'),
+            "-j, --join-output: like --raw-output but without a newline after each result",
+        ),
+        (
+            "--jsonargs",
+            None,
+            "--jsonargs: treat the remaining arguments as positional json in $ARGS.positional",
+        ),
+        (
+            "--monochrome-output",
+            Some('M'),
+            "-M, --monochrome-output: disable colorized output",
+        ),
+        (
+            "--null-input",
+            Some('n'),
+            "-n, --null-input: run the filter once with null as the input",
+        ),
+        (
+            "--raw-input",
+            Some('R'),
+            "-R, --raw-input: pass each line of the input to the filter as a string",
+        ),
+        (
+            "--raw-output",
+            Some('r'),
+            "-r, --raw-output: print strings without quotes",
+        ),
+        (
+            "--raw-output0",
+            None,
+            "--raw-output0: like --raw-output but separate results with nul bytes",
+        ),
+        (
+            "--rawfile",
+            None,
+            "--rawfile name file: bind $name to the contents of the file as a string",
+        ),
+        (
+            "--seq",
+            None,
+            "--seq: use the application/json-seq format for the output",
+        ),
+        (
+            "--slurp",
+            Some('s'),
+            "-s, --slurp: read all inputs into one array and run the filter once",
+        ),
+        (
+            "--slurpfile",
+            None,
+            "--slurpfile name file: bind $name to an array of the json values in the file",
+        ),
+        (
+            "--sort-keys",
+            Some('S'),
+            "-S, --sort-keys: print object keys in sorted order",
+        ),
+        ("--stream", None, "--stream: parse the input into [path, leaf] events"),
+        (
+            "--stream-errors",
+            None,
+            "--stream-errors: like --stream but report parse errors as values",
+        ),
+        ("--tab", None, "--tab: indent the output with tabs"),
+        ("--unbuffered", None, "--unbuffered: flush the output after each result"),
+        (
+            "-L",
+            Some('L'),
+            "-L directory: prepend the directory to the module search path",
+        ),
+    ];
+
+    pub fn is_positional_flag(token: &str) -> bool {
+        Self::POSITIONAL_FLAGS.contains(&token)
+    }
+
+    // NOTE: values after --jsonargs are parsed by jq as json, so the ones that aren't are reported while editing;
+    // --args and --jsonargs can follow each other to switch how the values after them are taken
+    fn invalid_json_args(positional_flag: &str, tokens: impl Iterator<Item = String>) -> Vec<String> {
+        let mut is_json = positional_flag == Self::JSON_ARGS_FLAG;
+        let mut invalid_json_args = Vec::new();
+
+        for token in tokens {
+            if Self::is_positional_flag(&token) {
+                is_json = token == Self::JSON_ARGS_FLAG;
+            } else if is_json && serde_json::from_str::<Value>(&token).is_err() {
+                token.push_to(&mut invalid_json_args);
+            }
+        }
+
+        invalid_json_args
+    }
+
+    fn num_values(flag: &str) -> Option<usize> {
+        Self::LONG_FLAGS
+            .iter()
+            .find_map(|(long_flag, num_values)| (*long_flag == flag).then_some(*num_values))
+    }
+
+    // NOTE: returns the number of values the short flag cluster consumes, e.g. `-nr` consumes none and `-nf` consumes
+    // the following token, or None if the cluster contains an unknown flag
+    fn num_short_values(cluster: &str) -> Option<usize> {
+        for (index, flag) in cluster.char_indices() {
+            if Self::SHORT_FLAGS_WITH_VALUE.contains(flag) {
+                let is_last = index + flag.len_utf8() == cluster.len();
+
+                return usize::from(is_last).some();
+            }
+
+            if !Self::SHORT_FLAGS.contains(flag) {
+                return None;
+            }
+        }
+
+        0.some()
+    }
+
+    pub fn contains(content: &str, long_flag: &str, short_flag: char) -> bool {
+        shlex::split(content).unwrap_or_default().iter().any(|token| {
+            token == long_flag
+                || token
+                    .strip_prefix('-')
+                    .is_some_and(|cluster| !cluster.starts_with('-') && cluster.contains(short_flag))
+        })
+    }
+
+    // NOTE: removes the flag wherever it appears, including from short flag clusters like `-sr`, or appends it if it
+    // does not appear at all; None if the contents cannot be split or re-joined
+    pub fn toggled(content: &str, long_flag: &str, short_flag: char) -> Option<String> {
+        let tokens = shlex::split(content)?;
+        let mut toggled_tokens = Vec::with_capacity(tokens.len() + 1);
+        let mut found = false;
+
+        for token in tokens {
+            if token == long_flag {
+                found = true;
+
+                continue;
+            }
+
+            if let Some(cluster) = token.strip_prefix('-').filter(|cluster| !cluster.starts_with('-')) {
+                if cluster.contains(short_flag) {
+                    let cluster = cluster.replace(short_flag, "");
+
+                    found = true;
+
+                    if !cluster.is_empty() {
+                        format!("-{cluster}").push_to(&mut toggled_tokens);
+                    }
+
+                    continue;
+                }
+            }
+
+            token.push_to(&mut toggled_tokens);
+        }
+
+        if !found {
+            long_flag.to_owned().push_to(&mut toggled_tokens);
+        }
+
+        shlex::try_join(toggled_tokens.iter().map(String::as_str)).ok()
+    }
+
+    fn description(token: &str, offset: usize) -> Option<&'static str> {
+        if token.starts_with("--") {
+            return Self::DESCRIPTIONS
+                .iter()
+                .find_map(|(long_flag, _short_flag, description)| (*long_flag == token).then_some(*description));
+        }
+
+        // NOTE: within a cluster like `-nr`, describe the flag the cursor is on
+        let cluster = token.strip_prefix('-')?;
+        let index = offset.saturating_sub(1).min(cluster.chars().count().saturating_sub(1));
+        let flag = cluster.chars().nth(index)?;
+
+        Self::DESCRIPTIONS
+            .iter()
+            .find_map(|(_long_flag, short_flag, description)| (*short_flag == flag.some()).then_some(*description))
+    }
+
+    // NOTE: a cursor right after a flag still counts as being on it so that the description shows while typing
+    pub fn description_at(content: &str, column: usize) -> Option<&'static str> {
+        let mut start = 0;
+
+        for token in content.split(' ') {
+            let len = token.chars().count();
+
+            if column <= start + len {
+                return Self::description(token, column - start);
+            }
+
+            start += len + 1;
+        }
+
+        None
+    }
+
+    // NOTE: the directories given with `-L dir` or `-Ldir`, in order, up to the first positional flag
+    pub fn library_paths(content: &str) -> Vec<PathBuf> {
+        let mut tokens = shlex::split(content).unwrap_or_default().into_iter();
+        let mut library_paths = Vec::new();
+
+        while let Some(token) = tokens.next() {
+            if Self::is_positional_flag(&token) {
+                break;
+            }
+
+            let library_path = match token.strip_prefix(Self::LIBRARY_PATH_FLAG) {
+                Some("") => tokens.next(),
+                Some(library_path) => library_path.to_owned().some(),
+                None => continue,
+            };
+
+            if let Some(library_path) = library_path {
+                PathBuf::from(library_path).push_to(&mut library_paths);
+            }
+        }
+
+        library_paths
+    }
+
+    fn sandbox_error(token: String) -> Result<(), CliFlagsError> {
+        let message = format!("{token} is not allowed");
+
+        CliFlagsError::new(message, vec![token]).err()
+    }
+
+    // NOTE: for flags that come from untrusted clients, e.g. through the server, only known flags that don't read files
+    // are accepted; jq keeps parsing flags after --args/--jsonargs, so every token is checked, and a bare token before
+    // them would be taken as the filter or as an input file
+    pub fn validate_sandboxed(content: &str) -> Result<(), CliFlagsError> {
+        let Some(tokens) = shlex::split(content) else {
+            return CliFlagsError::new("unbalanced quotes".to_owned(), Vec::new()).err();
+        };
+        let mut tokens = tokens.into_iter();
+        let mut is_positional = false;
+
+        while let Some(token) = tokens.next() {
+            let num_values = if token.starts_with("--") {
+                Self::num_values(&token).filter(|_num_values| !Self::FILE_FLAGS.contains(&token.as_str()))
+            } else if let Some(cluster) = token.strip_prefix('-').filter(|cluster| !cluster.is_empty()) {
+                cluster
+                    .chars()
+                    .all(|flag| Self::SHORT_FLAGS.contains(flag))
+                    .then_some(0)
+            } else if is_positional {
+                0.some()
+            } else {
+                None
+            };
+            let Some(num_values) = num_values else {
+                return Self::sandbox_error(token);
+            };
+
+            is_positional |= Self::is_positional_flag(&token);
+
+            if tokens.by_ref().take(num_values).count() < num_values {
+                let message = format!("{token} expects {num_values} value(s)");
+
+                return CliFlagsError::new(message, vec![token]).err();
+            }
+        }
+
+        ().ok()
+    }
+
+    pub fn validate(content: &str) -> Result<(), CliFlagsError> {
+        let Some(tokens) = shlex::split(content) else {
+            return CliFlagsError::new("unbalanced quotes".to_owned(), Vec::new()).err();
+        };
+        let mut invalid_flags = Vec::new();
+        let mut tokens = tokens.into_iter();
+
+        while let Some(token) = tokens.next() {
+            // NOTE: everything after --args/--jsonargs is a positional value rather than a flag
+            if Self::is_positional_flag(&token) {
+                let invalid_json_args = Self::invalid_json_args(&token, tokens.by_ref());
+
+                if !invalid_json_args.is_empty() {
+                    let message = format!(
+                        "{flag} expects json values: {values}",
+                        flag = Self::JSON_ARGS_FLAG,
+                        values = invalid_json_args.join(", ")
+                    );
+
+                    return CliFlagsError::new(message, invalid_json_args).err();
+                }
+
+                break;
+            }
+
+            let num_values = if token.starts_with("--") {
+                Self::num_values(&token)
+            } else if let Some(cluster) = token.strip_prefix('-').filter(|cluster| !cluster.is_empty()) {
+                Self::num_short_values(cluster)
+            } else {
+                None
+            };
+            let Some(num_values) = num_values else {
+                token.push_to(&mut invalid_flags);
+
+                continue;
+            };
+
+            if tokens.by_ref().take(num_values).count() < num_values {
+                let message = format!("{token} expects {num_values} value(s)");
+
+                return CliFlagsError::new(message, vec![token]).err();
+            }
+        }
+
+        if invalid_flags.is_empty() {
+            return ().ok();
+        }
+
+        let message = format!("unknown flags: {flags}", flags = invalid_flags.join(", "));
+
+        CliFlagsError::new(message, invalid_flags).err()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CliFlags;
+
+    #[test]
+    fn validate_sandboxed_accepts_flags_that_read_no_files() {
+        for content in [
+            "",
+            "-c",
+            "-nr --tab",
+            "--arg name value --argjson n 1",
+            "--indent 4 --args a b -c",
+        ] {
+            assert!(CliFlags::validate_sandboxed(content).is_ok(), "{content}");
+        }
+    }
+
+    #[test]
+    fn validate_sandboxed_rejects_flags_that_read_files() {
+        for content in [
+            "--rawfile name /etc/passwd",
+            "--slurpfile name /etc/passwd",
+            "--from-file /etc/passwd",
+            "-f /etc/passwd",
+            "-nf /etc/passwd",
+            "-L /etc",
+            "-L/etc",
+            "--args a --rawfile name /etc/passwd",
+            "/etc/passwd",
+            ". /etc/passwd",
+            "-- /etc/passwd",
+            "--unknown",
+            "'unbalanced",
+        ] {
+            assert!(CliFlags::validate_sandboxed(content).is_err(), "{content}");
+        }
+    }
+}
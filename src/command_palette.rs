@@ -0,0 +1,151 @@
+use crate::any::Any;
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    layout::{Constraint, Flex, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Clear, List, ListState},
+    Frame,
+};
+use tui_textarea::TextArea;
+
+pub struct PaletteEntry<T> {
+    pub action: T,
+    pub title: &'static str,
+    pub key: String,
+}
+
+pub enum CommandPaletteEvent<T> {
+    Selected(T),
+    Cancelled,
+}
+
+// NOTE: lists the entries whose title contains the typed query as a subsequence, e.g. `tgs` for `toggle --slurp`,
+// ranked by how closely together the query chars matched
+pub struct CommandPalette<T> {
+    entries: Vec<PaletteEntry<T>>,
+    text_area: TextArea<'static>,
+    matches: Vec<usize>,
+    selected: usize,
+}
+
+impl<T: Copy> CommandPalette<T> {
+    const HEIGHT: u16 = 3;
+    const PERCENTAGE: u16 = 60;
+    const STYLE_SELECTED: Style = Style::new().add_modifier(Modifier::REVERSED);
+    const STYLE_KEY: Style = Style::new().add_modifier(Modifier::DIM);
+    const TITLE: &'static str = "COMMAND PALETTE (enter to run, esc to cancel)";
+
+    pub fn new(entries: Vec<PaletteEntry<T>>) -> Self {
+        let text_area = TextArea::default();
+        let matches = (0..entries.len()).collect();
+        let selected = 0;
+
+        Self {
+            entries,
+            text_area,
+            matches,
+            selected,
+        }
+    }
+
+    // NOTE: the sum of the gaps before each matched char, so that matches at the start of the title and of adjacent
+    // chars rank first
+    fn score(title: &str, query: &str) -> Option<usize> {
+        let mut title_chars = title.chars().flat_map(char::to_lowercase).enumerate();
+        let mut previous_index = 0;
+        let mut score = 0;
+
+        for query_chr in query.chars().flat_map(char::to_lowercase) {
+            let (index, _title_chr) = title_chars.find(|(_index, title_chr)| *title_chr == query_chr)?;
+
+            score += index - previous_index;
+            previous_index = index + 1;
+        }
+
+        score.some()
+    }
+
+    fn refresh_matches(&mut self) {
+        let query = &self.text_area.lines()[0];
+        let mut scored_matches = self
+            .entries
+            .iter()
+            .enumerate()
+            .filter_map(|(index, entry)| Self::score(entry.title, query).map(|score| (score, index)))
+            .collect::<Vec<_>>();
+
+        scored_matches.sort_unstable();
+
+        self.matches = scored_matches.into_iter().map(|(_score, index)| index).collect();
+        self.selected = 0;
+    }
+
+    pub fn handle_key_event(&mut self, key_event: KeyEvent) -> Option<CommandPaletteEvent<T>> {
+        match key_event.code {
+            KeyCode::Esc => CommandPaletteEvent::Cancelled.some(),
+            KeyCode::Enter => {
+                let index = self.matches.get(self.selected)?;
+
+                CommandPaletteEvent::Selected(self.entries[*index].action).some()
+            }
+            KeyCode::Up => {
+                self.selected = self.selected.saturating_sub(1);
+
+                None
+            }
+            KeyCode::Down => {
+                if self.selected + 1 < self.matches.len() {
+                    self.selected += 1;
+                }
+
+                None
+            }
+            _code => {
+                if self.text_area.input(key_event) {
+                    self.refresh_matches();
+                }
+
+                None
+            }
+        }
+    }
+
+    fn rect(area: Rect) -> Rect {
+        let [rect] = Layout::vertical([Constraint::Percentage(Self::PERCENTAGE)])
+            .flex(Flex::Center)
+            .areas(area);
+        let [rect] = Layout::horizontal([Constraint::Percentage(Self::PERCENTAGE)])
+            .flex(Flex::Center)
+            .areas(rect);
+
+        rect
+    }
+
+    fn line(entry: &PaletteEntry<T>) -> Line<'static> {
+        if entry.key.is_empty() {
+            return Line::raw(entry.title);
+        }
+
+        Line::from(vec![
+            Span::raw(entry.title),
+            Span::styled(format!(" ({})", entry.key), Self::STYLE_KEY),
+        ])
+    }
+
+    pub fn render(&mut self, frame: &mut Frame) {
+        let rect = Self::rect(frame.area());
+        let [query_rect, list_rect] =
+            Layout::vertical([Constraint::Length(Self::HEIGHT), Constraint::Fill(1)]).areas(rect);
+        let mut list_state = ListState::default().with_selected(self.selected.some());
+        let list = List::new(self.matches.iter().map(|index| Self::line(&self.entries[*index])))
+            .block(format!("{} of {}", self.matches.len(), self.entries.len()).block())
+            .highlight_style(Self::STYLE_SELECTED);
+
+        self.text_area.set_block(Self::TITLE.block());
+
+        Clear.render_to(frame, rect);
+        (&self.text_area).render_to(frame, query_rect);
+        frame.render_stateful_widget(list, list_rect, &mut list_state);
+    }
+}
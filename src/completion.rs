@@ -0,0 +1,182 @@
+use crate::{any::Any, jq_syntax::JqSyntax, key_index::KeyIndex};
+use ratatui::{
+    layout::Rect,
+    style::{Modifier, Style},
+    widgets::{Clear, List, ListState},
+    Frame,
+};
+
+// NOTE: completes the word being typed at the cursor, either with the jq builtins or, right after a `.`, with the keys
+// found in the input at the path typed so far; words after a `$` are variables, so they are never completed
+pub struct Completion {
+    prefix: String,
+    prefix_column: usize,
+    candidates: Vec<String>,
+    selected: usize,
+}
+
+impl Completion {
+    const MAX_CANDIDATES: usize = 8;
+    const STYLE_SELECTED: Style = Style::new().add_modifier(Modifier::REVERSED);
+    const TITLE: &'static str = "tab to complete";
+
+    fn is_word_char(chr: char) -> bool {
+        chr.is_alphanumeric() || chr == '_'
+    }
+
+    // NOTE: keys that would need quoting, e.g. `."content-type"`, can't be completed by appending to the typed prefix
+    fn is_identifier(key: &str) -> bool {
+        key.starts_with(|chr: char| chr.is_alphabetic() || chr == '_') && key.chars().all(Self::is_word_char)
+    }
+
+    // NOTE: walks back over `.key` and `[]` segments from the `.` being completed, e.g. `.items[].user.` yields
+    // ["items", "user"]; returns None when the path doesn't start from the input, e.g. `map(.a).`
+    fn path(before_dot: &[char]) -> Option<Vec<String>> {
+        let mut path = Vec::new();
+        let mut end = before_dot.len();
+
+        loop {
+            if before_dot[..end].ends_with(&['[', ']']) {
+                end -= 2;
+                continue;
+            }
+
+            let key_begin = before_dot[..end]
+                .iter()
+                .rposition(|chr| !Self::is_word_char(*chr))
+                .map_or(0, |index| index + 1);
+
+            if key_begin < end && key_begin > 0 && before_dot[key_begin - 1] == '.' {
+                before_dot[key_begin..end].iter().collect::<String>().push_to(&mut path);
+                end = key_begin - 1;
+                continue;
+            }
+
+            // NOTE: the identity of `.[]`
+            if end > 0 && before_dot[end - 1] == '.' {
+                end -= 1;
+            }
+
+            break;
+        }
+
+        if end > 0 && (Self::is_word_char(before_dot[end - 1]) || matches!(before_dot[end - 1], ')' | ']' | '"')) {
+            return None;
+        }
+
+        path.reverse();
+
+        path.some()
+    }
+
+    fn key_candidates(before_dot: &[char], prefix: &str, key_index: &KeyIndex) -> Option<Vec<String>> {
+        let path = Self::path(before_dot)?;
+
+        key_index
+            .keys(&path)
+            .into_iter()
+            .filter(|key| Self::is_identifier(key) && key.starts_with(prefix) && *key != prefix)
+            .take(Self::MAX_CANDIDATES)
+            .map(str::to_owned)
+            .collect::<Vec<_>>()
+            .some()
+    }
+
+    fn builtin_candidates(prefix: &str) -> Vec<String> {
+        if prefix.is_empty() {
+            return Vec::new();
+        }
+
+        JqSyntax::BUILTINS
+            .iter()
+            .filter(|builtin| builtin.starts_with(prefix) && **builtin != prefix)
+            .take(Self::MAX_CANDIDATES)
+            .map(|builtin| (*builtin).to_owned())
+            .collect()
+    }
+
+    pub fn new(content: &str, cursor_column: usize, key_index: &KeyIndex) -> Option<Self> {
+        let chars = content.chars().collect::<Vec<_>>();
+        let before_cursor = chars.get(..cursor_column)?;
+        let prefix_column = before_cursor
+            .iter()
+            .rposition(|chr| !Self::is_word_char(*chr))
+            .map_or(0, |index| index + 1);
+
+        // NOTE: only complete at the end of a word
+        if chars.get(cursor_column).copied().is_some_and(Self::is_word_char) {
+            return None;
+        }
+
+        let prefix = before_cursor[prefix_column..].iter().collect::<String>();
+        let candidates = match prefix_column.checked_sub(1).map(|index| chars[index]) {
+            Some('$') => return None,
+            Some('.') => Self::key_candidates(&chars[..prefix_column - 1], &prefix, key_index)?,
+            _chr => Self::builtin_candidates(&prefix),
+        };
+
+        if candidates.is_empty() {
+            return None;
+        }
+
+        Self {
+            prefix,
+            prefix_column,
+            candidates,
+            selected: 0,
+        }
+        .some()
+    }
+
+    pub fn select_previous(&mut self) {
+        self.selected = self.selected.checked_sub(1).unwrap_or(self.candidates.len() - 1);
+    }
+
+    pub fn select_next(&mut self) {
+        self.selected = (self.selected + 1) % self.candidates.len();
+    }
+
+    // NOTE: what remains to be inserted at the cursor to complete the selected candidate
+    pub fn suffix(&self) -> &str {
+        &self.candidates[self.selected][self.prefix.len()..]
+    }
+
+    // NOTE: shown just below the editor (or above it when there is no room below), aligned with the start of the word
+    // being completed
+    fn rect(&self, editor_rect: Rect, area: Rect) -> Rect {
+        let width = self
+            .candidates
+            .iter()
+            .map(String::len)
+            .max()
+            .unwrap_or_default()
+            .max(Self::TITLE.len())
+            .saturating_add(2)
+            .cast::<u16>()
+            .min(area.width);
+        let height = (self.candidates.len() + 2).cast::<u16>();
+        let x = editor_rect
+            .x
+            .saturating_add(1)
+            .saturating_add(self.prefix_column.cast())
+            .min(area.right().saturating_sub(width));
+        let y = if editor_rect.bottom().saturating_add(height) <= area.bottom() {
+            editor_rect.bottom()
+        } else {
+            editor_rect.y.saturating_sub(height)
+        };
+
+        Rect::new(x, y, width, height).intersection(area)
+    }
+
+    pub fn render(&self, frame: &mut Frame, editor_rect: Rect) {
+        let rect = self.rect(editor_rect, frame.area());
+        let mut list_state = ListState::default().with_selected(self.selected.some());
+        let list = List::new(self.candidates.iter().map(String::as_str))
+            .block(Self::TITLE.block())
+            .highlight_style(Self::STYLE_SELECTED);
+
+        Clear.render_to(frame, rect);
+        frame.render_stateful_widget(list, rect, &mut list_state);
+    }
+}
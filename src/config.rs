@@ -0,0 +1,105 @@
+use crate::{any::Any, paths::Paths};
+use anyhow::Error;
+use clap::{parser::ValueSource, ArgMatches, Command};
+use std::{
+    io::ErrorKind,
+    path::{Path, PathBuf},
+};
+use toml::{Table, Value};
+
+// NOTE: defaults for the command-line flags, one `<long-flag> = <value>` per key (e.g. `jq-bin = "gojq"`,
+// `slurp = true`, or `bind = ["ctrl+j = accept"]`), which only apply to the flags that weren't given on the command
+// line or through their env var
+pub struct Config {
+    filepath: PathBuf,
+    table: Table,
+}
+
+impl Config {
+    const FILE_NAME: &'static str = "config.toml";
+
+    pub fn default_filepath() -> Option<PathBuf> {
+        Paths::config_dir().map(|directory| directory.join(Self::FILE_NAME))
+    }
+
+    // NOTE: a missing config file means there is nothing to merge, while an invalid one is reported so that a typo
+    // doesn't go unnoticed
+    pub async fn load(filepath: &Path) -> Result<Option<Self>, Error> {
+        let content = match tokio::fs::read_to_string(filepath).await {
+            Ok(content) => content,
+            Err(error) if error.kind() == ErrorKind::NotFound => return None.ok(),
+            Err(error) => anyhow::bail!("unable to read config {}: {error}", filepath.display()),
+        };
+        let table = match content.parse::<Table>() {
+            Ok(table) => table,
+            Err(error) => anyhow::bail!("invalid config {}: {error}", filepath.display()),
+        };
+        let filepath = filepath.to_path_buf();
+
+        Self { filepath, table }.some().ok()
+    }
+
+    pub fn filepath(&self) -> &Path {
+        &self.filepath
+    }
+
+    fn push_values(args: &mut Vec<String>, long: &str, value: &Value) -> Result<(), Error> {
+        match value {
+            Value::Boolean(true) => format!("--{long}").push_to(args),
+            Value::Boolean(false) => {}
+            Value::String(string) => format!("--{long}={string}").push_to(args),
+            Value::Integer(integer) => format!("--{long}={integer}").push_to(args),
+            Value::Float(float) => format!("--{long}={float}").push_to(args),
+            Value::Array(values) => {
+                for value in values {
+                    if value.is_array() {
+                        anyhow::bail!("{long}: nested arrays are not supported");
+                    }
+
+                    Self::push_values(args, long, value)?;
+                }
+            }
+            Value::Datetime(_) | Value::Table(_) => {
+                anyhow::bail!("{long}: expected a string, number, boolean, or array")
+            }
+        }
+
+        ().ok()
+    }
+
+    // NOTE: the args are meant to go before the command-line args, so flags that take a variable number of values
+    // (`args` and `jsonargs`) can't be set here since they would take the command-line positionals as well
+    pub fn args(&self, command: &Command, arg_matches: &ArgMatches, excluded: &[&str]) -> Result<Vec<String>, Error> {
+        let mut args = Vec::new();
+
+        for (long, value) in &self.table {
+            let arg = command
+                .get_arguments()
+                .find(|arg| arg.get_long() == long.as_str().some())
+                .filter(|_arg| !excluded.contains(&long.as_str()));
+            let Some(arg) = arg else {
+                anyhow::bail!("invalid config {}: unknown key {long:?}", self.filepath.display());
+            };
+
+            if arg.get_num_args().is_some_and(|num_args| num_args.max_values() > 1) {
+                anyhow::bail!(
+                    "invalid config {}: {long:?} can only be given on the command line",
+                    self.filepath.display()
+                );
+            }
+
+            if matches!(
+                arg_matches.value_source(arg.get_id().as_str()),
+                Some(ValueSource::CommandLine | ValueSource::EnvVariable)
+            ) {
+                continue;
+            }
+
+            if let Err(error) = Self::push_values(&mut args, long, value) {
+                anyhow::bail!("invalid config {}: {error}", self.filepath.display());
+            }
+        }
+
+        args.ok()
+    }
+}
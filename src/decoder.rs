@@ -0,0 +1,136 @@
+use crate::{any::Any, pretty::PrettyPrinter};
+use base64::{
+    engine::general_purpose::{STANDARD, URL_SAFE_NO_PAD},
+    Engine,
+};
+use serde::de::IgnoredAny;
+use std::borrow::Cow;
+
+pub struct Decoder;
+
+impl Decoder {
+    const BASE64_PADDING: char = '=';
+    const JWT_SEPARATOR: char = '.';
+    const JWT_SEGMENT_COUNT: usize = 3;
+    const STRING_DELIMITER: char = '"';
+    const URL_ESCAPE: char = '%';
+
+    // NOTE: use the last string literal on the line (e.g. the value in `"key": "value",`), otherwise use the whole
+    // line (e.g. for `--raw-output` output)
+    fn value(line: &str) -> &str {
+        let line = line.trim().trim_end_matches(',');
+        let Some(line_without_suffix) = line.strip_suffix(Self::STRING_DELIMITER) else {
+            return line;
+        };
+
+        match line_without_suffix.rfind(Self::STRING_DELIMITER) {
+            Some(idx) => &line_without_suffix[idx + 1..],
+            None => line_without_suffix,
+        }
+    }
+
+    fn base64_url(value: &str) -> Option<Vec<u8>> {
+        URL_SAFE_NO_PAD
+            .decode(value.trim_end_matches(Self::BASE64_PADDING))
+            .ok()
+    }
+
+    fn jwt(value: &str) -> Option<String> {
+        let segments = value.split(Self::JWT_SEPARATOR).collect::<Vec<_>>();
+
+        if segments.len() != Self::JWT_SEGMENT_COUNT {
+            return None;
+        }
+
+        String::from_utf8(Self::base64_url(segments[1])?).ok()
+    }
+
+    fn url(value: &str) -> Option<String> {
+        if !value.contains(Self::URL_ESCAPE) {
+            return None;
+        }
+
+        percent_encoding::percent_decode_str(value)
+            .decode_utf8()
+            .ok()
+            .map(Cow::into_owned)
+    }
+
+    fn base64(value: &str) -> Option<String> {
+        let bytes = match STANDARD.decode(value) {
+            Ok(bytes) => bytes,
+            Err(_err) => Self::base64_url(value)?,
+        };
+
+        String::from_utf8(bytes).ok()
+    }
+
+    // NOTE: decoded json, e.g. every jwt payload, is pretty-printed with its keys in their original order, while any
+    // other decoded text is shown as is
+    fn pretty(content: String) -> String {
+        if serde_json::from_str::<IgnoredAny>(&content).is_err() {
+            return content;
+        }
+
+        let mut pretty_printer = PrettyPrinter::new();
+
+        content
+            .lines()
+            .flat_map(|line| pretty_printer.push_line(line))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    // NOTE: returns the kind of decoding that succeeded along with the decoded content
+    pub fn decode(line: &str) -> Option<(&'static str, String)> {
+        let value = Self::value(line);
+
+        if value.is_empty() {
+            return None;
+        }
+
+        let (kind, content) = if let Some(content) = Self::jwt(value) {
+            ("JWT PAYLOAD", content)
+        } else if let Some(content) = Self::url(value) {
+            ("URL DECODED", content)
+        } else if let Some(content) = Self::base64(value) {
+            ("BASE64 DECODED", content)
+        } else {
+            return None;
+        };
+
+        (kind, Self::pretty(content)).some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Decoder;
+
+    #[test]
+    fn decode_uses_the_last_string_on_the_line() {
+        assert_eq!(
+            Decoder::decode(r#"  "query": "a%20b%2Fc","#),
+            ("URL DECODED", "a b/c".to_owned()).into()
+        );
+        assert_eq!(
+            Decoder::decode(r#""aGVsbG8gd29ybGQ=""#),
+            ("BASE64 DECODED", "hello world".to_owned()).into()
+        );
+    }
+
+    #[test]
+    fn decode_pretty_prints_jwt_payloads() {
+        let (kind, content) = Decoder::decode("eyJhbGciOiJIUzI1NiJ9.eyJzdWIiOiIxMjMiLCJhZG1pbiI6dHJ1ZX0.c2ln").unwrap();
+
+        assert_eq!(kind, "JWT PAYLOAD");
+        assert_eq!(content, "{\n  \"sub\": \"123\",\n  \"admin\": true\n}");
+    }
+
+    #[test]
+    fn decode_rejects_values_that_are_not_encoded() {
+        assert_eq!(Decoder::decode(""), None);
+        assert_eq!(Decoder::decode(r#""key": 42,"#), None);
+        assert_eq!(Decoder::decode(r#""not base64!""#), None);
+    }
+}
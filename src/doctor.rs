@@ -0,0 +1,326 @@
+use crate::{any::Any, cli_args::CliArgs, config::Config, filter_history::FilterHistory, keymap::Keymap};
+use anyhow::Error;
+use clap::Args;
+use std::{
+    fmt::{Display, Formatter, Result as FmtResult},
+    io::{ErrorKind, IsTerminal},
+    path::{Path, PathBuf},
+};
+use tokio::process::Command;
+
+#[derive(Args)]
+pub struct DoctorCliArgs {
+    #[arg(long, env = "RQ_JQ_BIN", default_value = "jq")]
+    jq_bin: String,
+
+    #[arg(long = "history-file", value_name = "PATH")]
+    history_filepath: Option<PathBuf>,
+
+    #[arg(long = "config", value_name = "PATH")]
+    config_filepath: Option<PathBuf>,
+
+    #[arg(long = "keymap-file", value_name = "PATH")]
+    keymap_filepath: Option<PathBuf>,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum CheckStatus {
+    Ok,
+    Warn,
+    Fail,
+}
+
+impl Display for CheckStatus {
+    fn fmt(&self, formatter: &mut Formatter) -> FmtResult {
+        let status = match self {
+            Self::Ok => "ok  ",
+            Self::Warn => "warn",
+            Self::Fail => "FAIL",
+        };
+
+        formatter.write_str(status)
+    }
+}
+
+struct Check {
+    name: &'static str,
+    status: CheckStatus,
+    detail: String,
+}
+
+impl Check {
+    fn new(name: &'static str, status: CheckStatus, detail: impl Into<String>) -> Self {
+        let detail = detail.into();
+
+        Self { name, status, detail }
+    }
+}
+
+// NOTE: checks what rq depends on at runtime and prints one line per check, so that a report can be pasted as is into
+// an issue; only failed checks (as opposed to warnings) make the command exit unsuccessfully
+pub struct Doctor {
+    jq_bin: String,
+    history_filepath: Option<PathBuf>,
+    config_filepath: Option<PathBuf>,
+    keymap_filepath: Option<PathBuf>,
+}
+
+impl Doctor {
+    const TERM_VAR: &'static str = "TERM";
+    const COLORTERM_VAR: &'static str = "COLORTERM";
+    const TRUECOLOR_VALUES: &'static [&'static str] = &["truecolor", "24bit"];
+    const DUMB_TERM: &'static str = "dumb";
+    const PROBE_FILE_NAME: &'static str = ".rq-doctor-probe";
+    const NO_CONFIG_HOME: &'static str = "neither XDG_CONFIG_HOME nor HOME is set";
+
+    pub fn new(doctor_cli_args: DoctorCliArgs) -> Self {
+        let jq_bin = doctor_cli_args.jq_bin;
+        let history_filepath = doctor_cli_args
+            .history_filepath
+            .or_else(FilterHistory::default_filepath);
+        let config_filepath = doctor_cli_args.config_filepath.or_else(Config::default_filepath);
+        let keymap_filepath = doctor_cli_args.keymap_filepath.or_else(Keymap::default_filepath);
+
+        Self {
+            jq_bin,
+            history_filepath,
+            config_filepath,
+            keymap_filepath,
+        }
+    }
+
+    async fn check_jq(&self) -> Check {
+        let name = "jq";
+
+        match Command::new(&self.jq_bin)
+            .arg("--version")
+            .kill_on_drop(true)
+            .output()
+            .await
+        {
+            Ok(output) if output.status.success() => {
+                let version = String::from_utf8_lossy(&output.stdout).trim().to_owned();
+
+                Check::new(name, CheckStatus::Ok, format!("{version} ({})", self.jq_bin))
+            }
+            Ok(output) => Check::new(
+                name,
+                CheckStatus::Fail,
+                format!("{} --version failed: {}", self.jq_bin, output.status),
+            ),
+            Err(error) => Check::new(
+                name,
+                CheckStatus::Fail,
+                format!("unable to run {}: {error} (see --jq-bin or RQ_JQ_BIN)", self.jq_bin),
+            ),
+        }
+    }
+
+    // NOTE: the tui is drawn on stderr so that stdout stays free for the accepted output
+    fn check_tty() -> Check {
+        let name = "tty";
+
+        if std::io::stderr().is_terminal() {
+            Check::new(name, CheckStatus::Ok, "stderr is a terminal")
+        } else {
+            Check::new(
+                name,
+                CheckStatus::Fail,
+                "stderr is not a terminal, which the tui is drawn on",
+            )
+        }
+    }
+
+    // NOTE: terminals don't report whether they support mouse reporting, so it is only ruled out for dumb terminals
+    fn check_term() -> Check {
+        let name = "mouse";
+
+        match std::env::var(Self::TERM_VAR) {
+            Ok(term) if term != Self::DUMB_TERM => Check::new(
+                name,
+                CheckStatus::Ok,
+                format!("TERM={term} is expected to report the mouse"),
+            ),
+            Ok(term) => Check::new(name, CheckStatus::Warn, format!("TERM={term} doesn't report the mouse")),
+            Err(_error) => Check::new(name, CheckStatus::Warn, "TERM is not set"),
+        }
+    }
+
+    fn check_truecolor() -> Check {
+        let name = "truecolor";
+
+        match std::env::var(Self::COLORTERM_VAR) {
+            Ok(colorterm) if Self::TRUECOLOR_VALUES.contains(&colorterm.as_str()) => {
+                Check::new(name, CheckStatus::Ok, format!("COLORTERM={colorterm}"))
+            }
+            _colorterm_res => Check::new(
+                name,
+                CheckStatus::Warn,
+                "COLORTERM doesn't advertise truecolor, so rgb colors may be approximated",
+            ),
+        }
+    }
+
+    // NOTE: the query waits for the terminal to answer, so it is only sent to an actual terminal
+    fn check_kitty_protocol() -> Check {
+        let name = "kitty keyboard protocol";
+
+        if !std::io::stderr().is_terminal() {
+            return Check::new(name, CheckStatus::Warn, "not checked without a terminal");
+        }
+
+        match crossterm::terminal::supports_keyboard_enhancement() {
+            Ok(true) => Check::new(name, CheckStatus::Ok, "supported"),
+            Ok(false) => Check::new(
+                name,
+                CheckStatus::Warn,
+                "not supported, so some modified keys may not register",
+            ),
+            Err(error) => Check::new(
+                name,
+                CheckStatus::Warn,
+                format!("unable to query the terminal: {error}"),
+            ),
+        }
+    }
+
+    async fn check_history_file(filepath: &Path) -> Check {
+        let name = "history file";
+
+        match tokio::fs::read_to_string(filepath).await {
+            Ok(content) => {
+                let num_invalid = content
+                    .lines()
+                    .filter(|line| FilterHistory::parse_line(line).is_err())
+                    .count();
+
+                if num_invalid == 0 {
+                    Check::new(name, CheckStatus::Ok, filepath.display().to_string())
+                } else {
+                    Check::new(
+                        name,
+                        CheckStatus::Fail,
+                        format!("{}: {num_invalid} invalid lines", filepath.display()),
+                    )
+                }
+            }
+            Err(error) if error.kind() == ErrorKind::NotFound => Check::new(
+                name,
+                CheckStatus::Ok,
+                format!("{} (not created yet)", filepath.display()),
+            ),
+            Err(error) => Check::new(name, CheckStatus::Fail, format!("{}: {error}", filepath.display())),
+        }
+    }
+
+    async fn check_config(filepath: &Path) -> Check {
+        let name = "config";
+
+        match Config::load(filepath).await {
+            Ok(Some(config)) => match CliArgs::validate_config(&config) {
+                Ok(()) => Check::new(name, CheckStatus::Ok, filepath.display().to_string()),
+                Err(error) => Check::new(name, CheckStatus::Fail, error.to_string()),
+            },
+            Ok(None) => Check::new(
+                name,
+                CheckStatus::Ok,
+                format!("{} (not created yet)", filepath.display()),
+            ),
+            Err(error) => Check::new(name, CheckStatus::Fail, error.to_string()),
+        }
+    }
+
+    // NOTE: loading the keymap ignores a missing file, so whether it exists is checked separately for the report
+    async fn check_keymap(filepath: &Path) -> Check {
+        let name = "keymap";
+
+        match Keymap::load(filepath.to_path_buf().some(), &[]).await {
+            Ok(_keymap) if filepath.is_file() => Check::new(name, CheckStatus::Ok, filepath.display().to_string()),
+            Ok(_keymap) => Check::new(
+                name,
+                CheckStatus::Ok,
+                format!("{} (not created yet)", filepath.display()),
+            ),
+            Err(error) => Check::new(name, CheckStatus::Fail, error.to_string()),
+        }
+    }
+
+    async fn probe_directory(directory: &Path) -> Result<(), Error> {
+        let probe_filepath = directory.join(Self::PROBE_FILE_NAME);
+
+        tokio::fs::create_dir_all(directory).await?;
+        tokio::fs::write(&probe_filepath, b"").await?;
+        tokio::fs::remove_file(&probe_filepath).await?.ok()
+    }
+
+    async fn check_state_directory(filepath: &Path) -> Check {
+        let name = "state directory";
+        let Some(directory) = filepath.parent() else {
+            return Check::new(name, CheckStatus::Fail, format!("{} has no parent", filepath.display()));
+        };
+
+        match Self::probe_directory(directory).await {
+            Ok(()) => Check::new(name, CheckStatus::Ok, format!("{} is writable", directory.display())),
+            Err(error) => Check::new(name, CheckStatus::Fail, format!("{}: {error}", directory.display())),
+        }
+    }
+
+    async fn checks(&self) -> Vec<Check> {
+        let mut checks = vec![
+            self.check_jq().await,
+            Self::check_tty(),
+            Self::check_term(),
+            Self::check_truecolor(),
+            Self::check_kitty_protocol(),
+        ];
+
+        match &self.history_filepath {
+            Some(history_filepath) => {
+                Self::check_history_file(history_filepath).await.push_to(&mut checks);
+                Self::check_state_directory(history_filepath).await.push_to(&mut checks);
+            }
+            None => Check::new(
+                "history file",
+                CheckStatus::Warn,
+                "neither XDG_STATE_HOME nor HOME is set",
+            )
+            .push_to(&mut checks),
+        }
+
+        match &self.config_filepath {
+            Some(config_filepath) => Self::check_config(config_filepath).await.push_to(&mut checks),
+            None => Check::new("config", CheckStatus::Warn, Self::NO_CONFIG_HOME).push_to(&mut checks),
+        }
+
+        match &self.keymap_filepath {
+            Some(keymap_filepath) => Self::check_keymap(keymap_filepath).await.push_to(&mut checks),
+            None => Check::new("keymap", CheckStatus::Warn, Self::NO_CONFIG_HOME).push_to(&mut checks),
+        }
+
+        checks
+    }
+
+    pub async fn run(self) -> Result<(), Error> {
+        let checks = self.checks().await;
+        let name_width = checks.iter().map(|check| check.name.len()).max().unwrap_or_default();
+
+        println!("rq {}", env!("CARGO_PKG_VERSION"));
+
+        for check in &checks {
+            println!(
+                "{status} {name:name_width$}  {detail}",
+                status = check.status,
+                name = check.name,
+                detail = check.detail,
+            );
+        }
+
+        let num_failed = checks.iter().filter(|check| check.status == CheckStatus::Fail).count();
+
+        if num_failed > 0 {
+            anyhow::bail!("{num_failed} check(s) failed");
+        }
+
+        ().ok()
+    }
+}
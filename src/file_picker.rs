@@ -0,0 +1,170 @@
+use crate::any::Any;
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    layout::{Constraint, Flex, Layout, Rect},
+    style::{Modifier, Style},
+    widgets::{Clear, List, ListState},
+    Frame,
+};
+use std::{
+    io::Error as IoError,
+    path::{Path, PathBuf},
+};
+
+struct Entry {
+    name: String,
+    is_dir: bool,
+}
+
+pub enum FilePickerEvent {
+    Picked(PathBuf),
+    Cancelled,
+}
+
+// NOTE: a minimal directory browser so that paths don't have to be typed blind; directories are listed first and hidden
+// entries are only shown once toggled on
+pub struct FilePicker {
+    directory: PathBuf,
+    entries: Vec<Entry>,
+    list_state: ListState,
+    show_hidden: bool,
+}
+
+impl FilePicker {
+    const HIDDEN_PREFIX: char = '.';
+    const PARENT_NAME: &'static str = "..";
+    const PERCENTAGE: u16 = 60;
+    const STYLE_SELECTED: Style = Style::new().add_modifier(Modifier::REVERSED);
+    const TITLE_SUFFIX: &'static str =
+        "(enter to open, tab to pick this directory, . to toggle hidden files, esc to cancel)";
+
+    pub async fn new(directory: &Path) -> Result<Self, IoError> {
+        let directory = tokio::fs::canonicalize(directory).await?;
+        let show_hidden = false;
+        let entries = Self::entries(&directory, show_hidden).await?;
+        let list_state = ListState::default().with_selected(0.some());
+
+        Self {
+            directory,
+            entries,
+            list_state,
+            show_hidden,
+        }
+        .ok()
+    }
+
+    async fn entries(directory: &Path, show_hidden: bool) -> Result<Vec<Entry>, IoError> {
+        let mut entries = Vec::new();
+        let mut read_dir = tokio::fs::read_dir(directory).await?;
+
+        while let Some(dir_entry) = read_dir.next_entry().await? {
+            let name = dir_entry.file_name().to_string_lossy().into_owned();
+
+            if !show_hidden && name.starts_with(Self::HIDDEN_PREFIX) {
+                continue;
+            }
+
+            // NOTE: follow symlinks so that links to directories can be browsed into as well
+            let is_dir = tokio::fs::metadata(dir_entry.path())
+                .await
+                .is_ok_and(|metadata| metadata.is_dir());
+
+            Entry { name, is_dir }.push_to(&mut entries);
+        }
+
+        entries.sort_by(|lhs, rhs| rhs.is_dir.cmp(&lhs.is_dir).then_with(|| lhs.name.cmp(&rhs.name)));
+
+        if directory.parent().is_some() {
+            let parent = Entry {
+                name: Self::PARENT_NAME.to_owned(),
+                is_dir: true,
+            };
+
+            entries.insert(0, parent);
+        }
+
+        entries.ok()
+    }
+
+    async fn change_directory(&mut self, directory: PathBuf) -> Result<(), IoError> {
+        self.entries = Self::entries(&directory, self.show_hidden).await?;
+        self.directory = directory;
+        self.list_state.select(0.some());
+
+        ().ok()
+    }
+
+    async fn open_parent(&mut self) -> Result<(), IoError> {
+        let Some(parent) = self.directory.parent() else {
+            return ().ok();
+        };
+
+        self.change_directory(parent.to_path_buf()).await
+    }
+
+    async fn open_selected(&mut self) -> Result<Option<FilePickerEvent>, IoError> {
+        let Some(entry) = self.list_state.selected().and_then(|index| self.entries.get(index)) else {
+            return None.ok();
+        };
+
+        if entry.name == Self::PARENT_NAME {
+            return self.open_parent().await?.none().ok();
+        }
+
+        let path = self.directory.join(&entry.name);
+
+        if !entry.is_dir {
+            return FilePickerEvent::Picked(path).some().ok();
+        }
+
+        self.change_directory(path).await?.none().ok()
+    }
+
+    async fn toggle_hidden(&mut self) -> Result<(), IoError> {
+        self.show_hidden = !self.show_hidden;
+
+        self.change_directory(self.directory.clone()).await
+    }
+
+    pub async fn handle_key_event(&mut self, key_event: KeyEvent) -> Result<Option<FilePickerEvent>, IoError> {
+        match key_event.code {
+            KeyCode::Esc => FilePickerEvent::Cancelled.some().ok(),
+            KeyCode::Tab => FilePickerEvent::Picked(self.directory.clone()).some().ok(),
+            KeyCode::Enter => self.open_selected().await,
+            KeyCode::Left | KeyCode::Backspace => self.open_parent().await?.none().ok(),
+            KeyCode::Char('.') => self.toggle_hidden().await?.none().ok(),
+            KeyCode::Up => self.list_state.select_previous().none().ok(),
+            KeyCode::Down => self.list_state.select_next().none().ok(),
+            _code => None.ok(),
+        }
+    }
+
+    fn rect(area: Rect) -> Rect {
+        let [rect] = Layout::vertical([Constraint::Percentage(Self::PERCENTAGE)])
+            .flex(Flex::Center)
+            .areas(area);
+        let [rect] = Layout::horizontal([Constraint::Percentage(Self::PERCENTAGE)])
+            .flex(Flex::Center)
+            .areas(rect);
+
+        rect
+    }
+
+    pub fn render(&mut self, frame: &mut Frame) {
+        let rect = Self::rect(frame.area());
+        let title = format!("{} {}", self.directory.display(), Self::TITLE_SUFFIX);
+        let names = self.entries.iter().map(|entry| {
+            if entry.is_dir {
+                format!("{}/", entry.name)
+            } else {
+                entry.name.clone()
+            }
+        });
+        let list = List::new(names)
+            .block(title.block())
+            .highlight_style(Self::STYLE_SELECTED);
+
+        Clear.render_to(frame, rect);
+        frame.render_stateful_widget(list, rect, &mut self.list_state);
+    }
+}
@@ -0,0 +1,90 @@
+use crate::{any::Any, paths::Paths};
+use anyhow::Error;
+use std::{io::ErrorKind, path::PathBuf};
+
+// NOTE: the filters that ran successfully, oldest first and without duplicates, kept across sessions in a file with
+// one json string per line so that filters spanning several lines stay one entry
+pub struct FilterHistory {
+    filepath: Option<PathBuf>,
+    filters: Vec<String>,
+}
+
+impl FilterHistory {
+    const MAX_FILTERS: usize = 1000;
+    const FILE_NAME: &'static str = "filter_history";
+
+    pub fn default_filepath() -> Option<PathBuf> {
+        Paths::state_dir().map(|directory| directory.join(Self::FILE_NAME))
+    }
+
+    // NOTE: a missing history file is what a first session looks like and an unreadable one shouldn't keep rq from
+    // starting, so both start with an empty history
+    pub async fn load(filepath: Option<PathBuf>) -> Self {
+        let mut filter_history = Self {
+            filepath,
+            filters: Vec::new(),
+        };
+        let Some(filepath) = &filter_history.filepath else {
+            return filter_history;
+        };
+        let content = match tokio::fs::read_to_string(filepath).await {
+            Ok(content) => content,
+            Err(error) if error.kind() == ErrorKind::NotFound => return filter_history,
+            Err(error) => return error.log_error().with(filter_history),
+        };
+
+        for line in content.lines() {
+            if let Some(filter) = Self::parse_line(line).log_if_error() {
+                filter_history.push(filter);
+            }
+        }
+
+        filter_history
+    }
+
+    pub fn parse_line(line: &str) -> Result<String, serde_json::Error> {
+        serde_json::from_str(line)
+    }
+
+    pub fn push(&mut self, filter: String) {
+        if filter.trim().is_empty() {
+            return;
+        }
+
+        self.filters.retain(|existing| *existing != filter);
+
+        if self.filters.len() == Self::MAX_FILTERS {
+            self.filters.remove(0);
+        }
+
+        filter.push_to(&mut self.filters);
+    }
+
+    // NOTE: newest first, as the search goes back in time
+    pub fn matches(&self, query: &str) -> Vec<&str> {
+        self.filters
+            .iter()
+            .rev()
+            .filter(|filter| filter.contains(query))
+            .map(String::as_str)
+            .collect()
+    }
+
+    pub async fn save(&self) -> Result<(), Error> {
+        let Some(filepath) = &self.filepath else {
+            return ().ok();
+        };
+        let mut content = String::new();
+
+        if let Some(directory) = filepath.parent() {
+            tokio::fs::create_dir_all(directory).await?;
+        }
+
+        for filter in &self.filters {
+            content.push_str(&serde_json::to_string(filter)?);
+            content.push('\n');
+        }
+
+        tokio::fs::write(filepath, content).await?.ok()
+    }
+}
@@ -0,0 +1,37 @@
+use crate::cli_flags::CliFlags;
+
+// NOTE: a flag whose effect on the current filter is shown by running the filter a second time with the flag toggled
+#[derive(Clone, Copy, PartialEq)]
+pub enum FlagVariant {
+    Slurp,
+    RawInput,
+}
+
+impl FlagVariant {
+    fn long_flag(self) -> &'static str {
+        match self {
+            Self::Slurp => "--slurp",
+            Self::RawInput => "--raw-input",
+        }
+    }
+
+    fn short_flag(self) -> char {
+        match self {
+            Self::Slurp => 's',
+            Self::RawInput => 'R',
+        }
+    }
+
+    // NOTE: cycles off -> slurp -> raw-input -> off
+    pub fn next(flag_variant: Option<Self>) -> Option<Self> {
+        match flag_variant {
+            None => Some(Self::Slurp),
+            Some(Self::Slurp) => Some(Self::RawInput),
+            Some(Self::RawInput) => None,
+        }
+    }
+
+    pub fn cli_flags(self, cli_flags: &str) -> Option<String> {
+        CliFlags::toggled(cli_flags, self.long_flag(), self.short_flag())
+    }
+}
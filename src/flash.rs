@@ -0,0 +1,22 @@
+use crate::any::Any;
+use std::time::{Duration, Instant};
+
+pub struct Flash {
+    end: Option<Instant>,
+}
+
+impl Flash {
+    const DURATION: Duration = Duration::from_millis(300);
+
+    pub fn new() -> Self {
+        Self { end: None }
+    }
+
+    pub fn start(&mut self) {
+        self.end = (Instant::now() + Self::DURATION).some();
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.end.is_some_and(|end| Instant::now() < end)
+    }
+}
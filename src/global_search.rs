@@ -0,0 +1,183 @@
+use crate::{any::Any, scroll::ScrollView};
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    layout::{Constraint, Flex, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Clear, List, ListState},
+    Frame,
+};
+use tui_textarea::TextArea;
+
+#[derive(Clone, Copy)]
+pub enum Pane {
+    Input,
+    Output,
+}
+
+struct SearchHit {
+    pane: Pane,
+    line_index: usize,
+    preview: String,
+}
+
+pub enum GlobalSearchEvent {
+    Selected {
+        pane: Pane,
+        line_index: usize,
+        query: String,
+    },
+    Cancelled,
+}
+
+// NOTE: searches the selected input and the output at once and lists every line containing the query, input lines
+// first, so that a value can be traced from one pane to the other
+pub struct GlobalSearch {
+    text_area: TextArea<'static>,
+    hits: Vec<SearchHit>,
+    selected: usize,
+}
+
+impl GlobalSearch {
+    const HEIGHT: u16 = 3;
+    const PERCENTAGE: u16 = 60;
+    const MAX_HITS: usize = 1000;
+    const MAX_PREVIEW_CHARS: usize = 200;
+    const STYLE_SELECTED: Style = Style::new().add_modifier(Modifier::REVERSED);
+    const STYLE_LOCATION: Style = Style::new().add_modifier(Modifier::DIM);
+    const TITLE: &'static str = "SEARCH INPUT AND OUTPUT (enter to jump, esc to cancel)";
+
+    pub fn new() -> Self {
+        let text_area = TextArea::default();
+        let hits = Vec::new();
+        let selected = 0;
+
+        Self {
+            text_area,
+            hits,
+            selected,
+        }
+    }
+
+    fn query(&self) -> &str {
+        &self.text_area.lines()[0]
+    }
+
+    fn push_hits(&mut self, pane: Pane, scroll_view: &ScrollView) {
+        for line_index in 0..scroll_view.len_lines() {
+            if Self::MAX_HITS <= self.hits.len() {
+                return;
+            }
+
+            let Some(line) = scroll_view.line(line_index).filter(|line| line.contains(self.query())) else {
+                continue;
+            };
+            let preview = line.trim().chars().take(Self::MAX_PREVIEW_CHARS).collect();
+
+            SearchHit {
+                pane,
+                line_index,
+                preview,
+            }
+            .push_to(&mut self.hits);
+        }
+    }
+
+    // NOTE: runs on every change of the query, which stops at MAX_HITS so that a short query over a large input stays
+    // responsive
+    fn refresh_hits(&mut self, input: &ScrollView, output: &ScrollView) {
+        self.hits.clear();
+        self.selected = 0;
+
+        if self.query().is_empty() {
+            return;
+        }
+
+        self.push_hits(Pane::Input, input);
+        self.push_hits(Pane::Output, output);
+    }
+
+    pub fn handle_key_event(
+        &mut self,
+        key_event: KeyEvent,
+        input: &ScrollView,
+        output: &ScrollView,
+    ) -> Option<GlobalSearchEvent> {
+        match key_event.code {
+            KeyCode::Esc => GlobalSearchEvent::Cancelled.some(),
+            KeyCode::Enter => {
+                let hit = self.hits.get(self.selected)?;
+
+                GlobalSearchEvent::Selected {
+                    pane: hit.pane,
+                    line_index: hit.line_index,
+                    query: self.query().to_owned(),
+                }
+                .some()
+            }
+            KeyCode::Up => {
+                self.selected = self.selected.saturating_sub(1);
+
+                None
+            }
+            KeyCode::Down => {
+                if self.selected + 1 < self.hits.len() {
+                    self.selected += 1;
+                }
+
+                None
+            }
+            _code => {
+                if self.text_area.input(key_event) {
+                    self.refresh_hits(input, output);
+                }
+
+                None
+            }
+        }
+    }
+
+    fn rect(area: Rect) -> Rect {
+        let [rect] = Layout::vertical([Constraint::Percentage(Self::PERCENTAGE)])
+            .flex(Flex::Center)
+            .areas(area);
+        let [rect] = Layout::horizontal([Constraint::Percentage(Self::PERCENTAGE)])
+            .flex(Flex::Center)
+            .areas(rect);
+
+        rect
+    }
+
+    fn line(hit: &SearchHit) -> Line<'static> {
+        let pane = match hit.pane {
+            Pane::Input => "input",
+            Pane::Output => "output",
+        };
+
+        Line::from(vec![
+            Span::styled(format!("{pane}:{} ", hit.line_index + 1), Self::STYLE_LOCATION),
+            Span::raw(hit.preview.clone()),
+        ])
+    }
+
+    pub fn render(&mut self, frame: &mut Frame) {
+        let rect = Self::rect(frame.area());
+        let [query_rect, list_rect] =
+            Layout::vertical([Constraint::Length(Self::HEIGHT), Constraint::Fill(1)]).areas(rect);
+        let mut list_state = ListState::default().with_selected(self.selected.some());
+        let num_hits = if Self::MAX_HITS <= self.hits.len() {
+            format!("{}+ hits", Self::MAX_HITS)
+        } else {
+            format!("{} hits", self.hits.len())
+        };
+        let list = List::new(self.hits.iter().map(Self::line))
+            .block(num_hits.block())
+            .highlight_style(Self::STYLE_SELECTED);
+
+        self.text_area.set_block(Self::TITLE.block());
+
+        Clear.render_to(frame, rect);
+        (&self.text_area).render_to(frame, query_rect);
+        frame.render_stateful_widget(list, list_rect, &mut list_state);
+    }
+}
@@ -0,0 +1,90 @@
+use crate::{any::Any, theme::Theme};
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    layout::{Constraint, Flex, Layout, Rect},
+    text::Line,
+    widgets::Clear,
+    Frame,
+};
+use tui_textarea::TextArea;
+
+pub enum GotoLineEvent {
+    Submitted(usize),
+    Cancelled,
+}
+
+// NOTE: the single line overlay opened with `:` for jumping the focused pane to a line, numbered from 1 like the
+// lines of the global search; the number can also be typed with a leading `:` out of habit
+pub struct GotoLinePrompt {
+    text_area: TextArea<'static>,
+    error: Option<String>,
+}
+
+impl GotoLinePrompt {
+    const HEIGHT: u16 = 3;
+    const PERCENTAGE: u16 = 60;
+    const TITLE: &'static str = "GO TO LINE (enter to jump, esc to cancel)";
+    const PREFIX: char = ':';
+
+    pub fn new() -> Self {
+        let text_area = TextArea::default();
+        let error = None;
+
+        Self { text_area, error }
+    }
+
+    fn line_index(&self) -> Option<usize> {
+        let text = self.text_area.lines()[0].trim();
+        let line_number = text.strip_prefix(Self::PREFIX).unwrap_or(text).parse::<usize>().ok()?;
+
+        line_number.saturating_sub(1).some()
+    }
+
+    pub fn handle_key_event(&mut self, key_event: KeyEvent) -> Option<GotoLineEvent> {
+        match key_event.code {
+            KeyCode::Esc => GotoLineEvent::Cancelled.some(),
+            KeyCode::Enter => {
+                let Some(line_index) = self.line_index() else {
+                    self.error = "expected a line number".to_owned().some();
+
+                    return None;
+                };
+
+                GotoLineEvent::Submitted(line_index).some()
+            }
+            _code => {
+                if self.text_area.input(key_event) {
+                    self.error = None;
+                }
+
+                None
+            }
+        }
+    }
+
+    fn rect(area: Rect) -> Rect {
+        let [rect] = Layout::vertical([Constraint::Length(Self::HEIGHT)])
+            .flex(Flex::Center)
+            .areas(area);
+        let [rect] = Layout::horizontal([Constraint::Percentage(Self::PERCENTAGE)])
+            .flex(Flex::Center)
+            .areas(rect);
+
+        rect
+    }
+
+    pub fn render(&mut self, frame: &mut Frame, theme: &Theme) {
+        let rect = Self::rect(frame.area());
+        let block = match &self.error {
+            Some(error) => Self::TITLE
+                .block()
+                .title_bottom(Line::styled(error.clone(), theme.error)),
+            None => Self::TITLE.block(),
+        };
+
+        self.text_area.set_block(block);
+
+        Clear.render_to(frame, rect);
+        (&self.text_area).render_to(frame, rect);
+    }
+}
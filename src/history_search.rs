@@ -0,0 +1,111 @@
+use crate::{any::Any, theme::Theme};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{
+    layout::{Constraint, Flex, Layout, Rect},
+    text::Line,
+    widgets::Clear,
+    Frame,
+};
+use tui_textarea::TextArea;
+
+pub enum HistorySearchEvent {
+    Changed,
+    Accepted,
+    Cancelled,
+}
+
+// NOTE: a readline-style reverse incremental search over the filter history, where typing narrows the matches and
+// ctrl+r steps back to the next older one; the filter being searched from is kept so that cancelling restores it
+pub struct HistorySearch {
+    text_area: TextArea<'static>,
+    original_filter: String,
+    match_idx: usize,
+}
+
+impl HistorySearch {
+    const HEIGHT: u16 = 3;
+    const PERCENTAGE: u16 = 60;
+    const TITLE: &'static str = "REVERSE SEARCH (ctrl+r for older, enter to accept, esc to cancel)";
+
+    pub fn new(original_filter: String) -> Self {
+        let text_area = TextArea::default();
+        let match_idx = 0;
+
+        Self {
+            text_area,
+            original_filter,
+            match_idx,
+        }
+    }
+
+    pub fn original_filter(&self) -> &str {
+        &self.original_filter
+    }
+
+    pub fn query(&self) -> &str {
+        &self.text_area.lines()[0]
+    }
+
+    pub fn match_idx(&self) -> usize {
+        self.match_idx
+    }
+
+    // NOTE: stepping past the oldest match stays on it, as readline does
+    pub fn handle_key_event(&mut self, key_event: KeyEvent, num_matches: usize) -> Option<HistorySearchEvent> {
+        match key_event {
+            KeyEvent { code: KeyCode::Esc, .. } => HistorySearchEvent::Cancelled.some(),
+            KeyEvent {
+                code: KeyCode::Enter, ..
+            } => HistorySearchEvent::Accepted.some(),
+            KeyEvent {
+                code: KeyCode::Char('r'),
+                modifiers: KeyModifiers::CONTROL,
+                ..
+            } => {
+                if self.match_idx + 1 < num_matches {
+                    self.match_idx += 1;
+                }
+
+                HistorySearchEvent::Changed.some()
+            }
+            key_event => {
+                if !self.text_area.input(key_event) {
+                    return None;
+                }
+
+                self.match_idx = 0;
+
+                HistorySearchEvent::Changed.some()
+            }
+        }
+    }
+
+    fn rect(area: Rect) -> Rect {
+        let [rect] = Layout::vertical([Constraint::Length(Self::HEIGHT)])
+            .flex(Flex::Center)
+            .areas(area);
+        let [rect] = Layout::horizontal([Constraint::Percentage(Self::PERCENTAGE)])
+            .flex(Flex::Center)
+            .areas(rect);
+
+        rect
+    }
+
+    pub fn render(&mut self, frame: &mut Frame, theme: &Theme, num_matches: usize) {
+        let rect = Self::rect(frame.area());
+        let block = if num_matches == 0 {
+            Self::TITLE
+                .block()
+                .title_bottom(Line::styled("no matching filter", theme.error))
+        } else {
+            Self::TITLE
+                .block()
+                .title_bottom(format!("match {} of {num_matches}", self.match_idx + 1))
+        };
+
+        self.text_area.set_block(block);
+
+        Clear.render_to(frame, rect);
+        (&self.text_area).render_to(frame, rect);
+    }
+}
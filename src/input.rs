@@ -1,81 +1,367 @@
-use crate::{any::Any, channel::Channel};
+use crate::{any::Any, channel::BoundedChannel};
 use anyhow::Error;
-use derive_more::From;
 use std::{
     collections::VecDeque,
-    io::{Error as IoError, IsTerminal},
+    io::{Error as IoError, ErrorKind, IsTerminal},
     marker::Unpin,
+    num::NonZeroUsize,
     os::fd::AsFd,
-    path::Path,
+    path::{Path, PathBuf},
+    process::Stdio,
+    str::FromStr,
+    time::Duration,
 };
 use tokio::{
-    io::AsyncBufReadExt,
-    sync::mpsc::{error::TryRecvError, UnboundedSender},
+    fs::File,
+    io::{AsyncBufReadExt, AsyncWriteExt},
+    process::{ChildStdout, Command},
+    sync::mpsc::{Receiver, Sender},
+    time::Instant,
 };
+use tokio_util::sync::CancellationToken;
+
+#[derive(Clone, Copy)]
+pub enum InputFormat {
+    Json,
+    Ndjson,
+    Text,
+}
+
+impl InputFormat {
+    fn is_object_line(line: &str) -> bool {
+        line.starts_with('{') && line.ends_with('}')
+    }
+
+    // NOTE: only the first two non-empty lines are inspected so that this is cheap enough to run every frame
+    pub fn detect(content: &str) -> Option<Self> {
+        let mut lines = content.lines().map(str::trim).filter(|line| !line.is_empty());
+        let first_line = lines.next()?;
+
+        if Self::is_object_line(first_line) && lines.next().is_some_and(Self::is_object_line) {
+            Self::Ndjson
+        } else if first_line.starts_with(['{', '[']) {
+            Self::Json
+        } else {
+            Self::Text
+        }
+        .some()
+    }
+
+    // NOTE: besides ndjson, concatenated pretty-printed documents are recognized by a closing bracket at the start of a
+    // line that is followed by more content
+    pub fn is_multi_document(content: &str) -> bool {
+        if let Some(Self::Ndjson) = Self::detect(content) {
+            return true;
+        }
+
+        let mut lines = content.lines().filter(|line| !line.trim().is_empty());
+
+        while let Some(line) = lines.next() {
+            if matches!(line, "}" | "]") && lines.next().is_some() {
+                return true;
+            }
+        }
+
+        false
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Json => "json",
+            Self::Ndjson => "ndjson",
+            Self::Text => "text",
+        }
+    }
+}
+
+// NOTE: urls are fetched with curl and commands are run with sh so that neither needs a dedicated client here
+pub enum InputSource {
+    Filepath(PathBuf),
+    Url(String),
+    Command(String),
+}
+
+impl InputSource {
+    const COMMAND_PREFIX: char = '!';
+    const URL_PREFIXES: [&'static str; 2] = ["http://", "https://"];
+
+    pub fn name(&self) -> String {
+        match self {
+            Self::Filepath(filepath) => filepath.display().to_string(),
+            Self::Url(url) => url.clone(),
+            Self::Command(command) => format!("{}{command}", Self::COMMAND_PREFIX),
+        }
+    }
+}
+
+impl FromStr for InputSource {
+    type Err = Error;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let text = text.trim();
+
+        anyhow::ensure!(!text.is_empty(), "expected a path, url, or !command");
+
+        if let Some(command) = text.strip_prefix(Self::COMMAND_PREFIX) {
+            return Self::Command(command.trim().to_owned()).ok();
+        }
+
+        if Self::URL_PREFIXES.iter().any(|url_prefix| text.starts_with(url_prefix)) {
+            return Self::Url(text.to_owned()).ok();
+        }
+
+        Self::Filepath(text.into()).ok()
+    }
+}
+
+#[derive(Clone, Copy)]
+pub struct InputConfig {
+    pub channel_capacity: NonZeroUsize,
+    pub batch_window: Duration,
+    pub batch_max_lines: NonZeroUsize,
+    pub strip_cr: bool,
+    pub keep_last: Option<NonZeroUsize>,
+}
+
+impl InputConfig {
+    // NOTE: nothing is ever sent on the channel of an empty input, so these values are irrelevant
+    const EMPTY: Self = Self {
+        channel_capacity: NonZeroUsize::MIN,
+        batch_window: Duration::ZERO,
+        batch_max_lines: NonZeroUsize::MIN,
+        strip_cr: true,
+        keep_last: None,
+    };
+}
 
 pub struct Input {
-    channel: Channel<Result<String, IoError>>,
+    batch_deadline: Instant,
+    batch_max_lines: NonZeroUsize,
+    batch_window: Duration,
+    first_batch: bool,
+    receiver: Receiver<Result<String, IoError>>,
     lines: VecDeque<String>,
 }
 
 impl Input {
-    pub fn empty() -> Self {
-        let channel = Channel::new();
+    const SHELL_EXECUTABLE_NAME: &'static str = "sh";
+    const URL_COMMAND: &'static str = "curl --fail --silent --show-error --location";
+
+    // NOTE: the sender is only held by the reader task, so the receiver is closed once the whole input has been read
+    fn new(config: InputConfig) -> (Self, Sender<Result<String, IoError>>) {
+        let batch_deadline = Instant::now();
+        let batch_max_lines = config.batch_max_lines;
+        let batch_window = config.batch_window;
+        let first_batch = true;
+        let BoundedChannel { receiver, sender } = BoundedChannel::new(config.channel_capacity.get());
         let lines = VecDeque::new();
+        let input = Self {
+            batch_deadline,
+            batch_max_lines,
+            batch_window,
+            first_batch,
+            receiver,
+            lines,
+        };
+
+        (input, sender)
+    }
+
+    pub fn empty() -> Self {
+        let (input, _sender) = Self::new(InputConfig::EMPTY);
 
-        Self { channel, lines }
+        input
     }
 
-    pub async fn from_filepath(filepath: &Path) -> Result<Self, IoError> {
-        filepath.open().await?.buf_reader().ok()
+    pub async fn from_filepath(
+        filepath: &Path,
+        config: InputConfig,
+        cancellation_token: CancellationToken,
+    ) -> Result<Self, IoError> {
+        let buf_reader = filepath.open().await?.buf_reader();
+
+        Self::from_buf_reader(buf_reader, config, None, cancellation_token).ok()
     }
 
-    pub fn from_stdin() -> Self {
+    // NOTE: the child is moved into the reading task and killed on drop once reading ends or is cancelled
+    fn from_command(command: &str, config: InputConfig, cancellation_token: CancellationToken) -> Result<Self, Error> {
+        let mut child = Command::new(Self::SHELL_EXECUTABLE_NAME)
+            .arg("-c")
+            .arg(command)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .kill_on_drop(true)
+            .spawn()?;
+        let stdout = child
+            .stdout
+            .take()
+            .ok_or_error::<ChildStdout>("command stdout is not piped")?;
+        let (input, sender) = Self::new(config);
+
+        async move {
+            let _child = child;
+
+            Self::read_lines(stdout.buf_reader(), sender, config.strip_cr, None, cancellation_token).await;
+        }
+        .spawn_task();
+
+        input.ok()
+    }
+
+    pub async fn from_source(
+        input_source: &InputSource,
+        config: InputConfig,
+        cancellation_token: CancellationToken,
+    ) -> Result<Self, Error> {
+        match input_source {
+            InputSource::Filepath(filepath) => Self::from_filepath(filepath, config, cancellation_token).await?.ok(),
+            InputSource::Url(url) => {
+                let command = format!("{} {}", Self::URL_COMMAND, shlex::try_quote(url)?);
+
+                Self::from_command(&command, config, cancellation_token)
+            }
+            InputSource::Command(command) => Self::from_command(command, config, cancellation_token),
+        }
+    }
+
+    pub async fn from_stdin(
+        config: InputConfig,
+        capture_filepath: Option<&Path>,
+        cancellation_token: CancellationToken,
+    ) -> Result<Self, IoError> {
         let stdin = tokio::io::stdin();
 
         // NOTE: without this, `rq` (run by itself, with no stdin input) becomes laggy
         // TODO: figure out why
         if stdin.as_fd().is_terminal() {
-            Self::empty()
-        } else {
-            stdin.buf_reader().into()
+            return Self::empty().ok();
         }
+
+        let capture_file = match capture_filepath {
+            Some(capture_filepath) => capture_filepath.create().await?.some(),
+            None => None,
+        };
+
+        Self::from_buf_reader(stdin.buf_reader(), config, capture_file, cancellation_token).ok()
+    }
+
+    fn from_buf_reader<B: 'static + AsyncBufReadExt + Send + Unpin>(
+        buf_reader: B,
+        config: InputConfig,
+        capture_file: Option<File>,
+        cancellation_token: CancellationToken,
+    ) -> Self {
+        let (input, sender) = Self::new(config);
+
+        Self::read_lines(buf_reader, sender, config.strip_cr, capture_file, cancellation_token).spawn_task();
+
+        input
+    }
+
+    // NOTE: lines are split on `\n` only so that the trailing `\r` of windows-formatted input is removed here, unless
+    // the user opted out, rather than ending up inside extracted string values
+    fn line(segment: Vec<u8>, strip_cr: bool) -> Result<String, IoError> {
+        let mut line = String::from_utf8(segment).map_err(|error| IoError::new(ErrorKind::InvalidData, error))?;
+
+        if strip_cr && line.ends_with('\r') {
+            line.pop();
+        }
+
+        line.ok()
+    }
+
+    // NOTE: segments are written as they arrive, before any carriage return is stripped, so that the capture file holds
+    // the input as it was piped in
+    async fn capture(capture_file: &mut File, segment: &[u8]) -> Result<(), IoError> {
+        capture_file.write_all(segment).await?;
+        capture_file.write_all(b"\n").await?;
+
+        ().ok()
     }
 
-    async fn read_lines<B: AsyncBufReadExt + Unpin>(buf_reader: B, sender: UnboundedSender<Result<String, IoError>>) {
-        let mut lines = buf_reader.lines();
+    async fn send_lines<B: AsyncBufReadExt + Unpin>(
+        buf_reader: B,
+        sender: Sender<Result<String, IoError>>,
+        strip_cr: bool,
+        mut capture_file: Option<File>,
+    ) {
+        let mut segments = buf_reader.split(b'\n');
+
+        while let Some(segment_res) = segments.next_segment().await.transpose() {
+            if let (Some(capture_file), Ok(segment)) = (&mut capture_file, &segment_res) {
+                Self::capture(capture_file, segment).await.log_if_error();
+            }
+
+            let line_res = segment_res.and_then(|segment| Self::line(segment, strip_cr));
 
-        while let Some(line_res) = lines.next_line().await.transpose() {
             // NOTE: we don't want to end early for send errors (we don't hold onto the spawned read_lines() task, so
             // retrieving a returned error from the task is not possible), but we do want to terminate for io reading next
-            // line errors, so we log and ignore any errors forwarding along string results
-            sender.send(line_res).log_if_error();
+            // line errors, so we log and ignore any errors forwarding along string results; awaiting capacity in the
+            // bounded channel applies backpressure to the reader
+            sender.send(line_res).await.log_if_error();
         }
     }
 
-    pub async fn next_lines(&mut self) -> Result<VecDeque<String>, Error> {
-        loop {
-            match self.channel.receiver.try_recv() {
-                Ok(line_res) => self.lines.push_back(line_res?),
-                Err(TryRecvError::Empty) => break,
-                Err(err) => return err.err(),
-            }
+    async fn read_lines<B: AsyncBufReadExt + Unpin>(
+        buf_reader: B,
+        sender: Sender<Result<String, IoError>>,
+        strip_cr: bool,
+        capture_file: Option<File>,
+        cancellation_token: CancellationToken,
+    ) {
+        tokio::select! {
+            () = Self::send_lines(buf_reader, sender, strip_cr, capture_file) => {}
+            () = cancellation_token.cancelled() => {}
         }
+    }
+
+    // NOTE: waits until either the first line arrives, which is kept for the next call to next_lines(), or the input
+    // ends without any
+    pub async fn has_lines(&mut self) -> Result<bool, IoError> {
+        if !self.lines.is_empty() {
+            return true.ok();
+        }
+
+        let Some(line_res) = self.receiver.recv().await else {
+            return false.ok();
+        };
+
+        self.push_line(line_res?);
+
+        true.ok()
+    }
 
+    // NOTE: the input is complete once the reader has dropped its sender and every line it sent has been handed out
+    pub fn is_complete(&self) -> bool {
+        self.receiver.is_closed() && self.receiver.is_empty() && self.lines.is_empty()
+    }
+
+    fn push_line(&mut self, line: String) {
         if self.lines.is_empty() {
-            std::future::pending().await
-        } else {
-            self.lines.mem_take().ok()
+            self.batch_deadline = Instant::now() + self.batch_window;
         }
+
+        self.lines.push_back(line);
     }
-}
 
-impl<B: 'static + AsyncBufReadExt + Send + Unpin> From<B> for Input {
-    fn from(buf_reader: B) -> Self {
-        let input = Input::empty();
+    // NOTE: lines are batched until either the batch window elapses or the batch is full so that a fast producer
+    // doesn't trigger a jq run for every few lines; this is cancel safe bc pending lines are kept in self.lines
+    pub async fn next_lines(&mut self) -> Result<VecDeque<String>, Error> {
+        while let Ok(line_res) = self.receiver.try_recv() {
+            self.push_line(line_res?);
+        }
+
+        if self.lines.is_empty() {
+            return std::future::pending().await;
+        }
 
-        Self::read_lines(buf_reader, input.channel.sender.clone()).spawn_task();
+        // NOTE: the first batch is returned as soon as any lines are available so that the first page of a large input
+        // is shown right away instead of after the batch window
+        if !self.first_batch.mem_take() && self.lines.len() < self.batch_max_lines.get() {
+            tokio::time::sleep_until(self.batch_deadline).await;
+        }
 
-        input
+        self.lines.mem_take().ok()
     }
 }
@@ -0,0 +1,110 @@
+use crate::{any::Any, cli_flags::CliFlags, input::InputFormat, jq_process::JqOutput};
+use std::collections::HashSet;
+use tokio_util::sync::CancellationToken;
+
+// NOTE: tracks which records of an ndjson input produce output for the current filter by running it a second time
+// once per record through `foreach inputs`, which reports the index of every record with at least one result; the
+// indices are mapped back to the non-empty input lines since ndjson has exactly one record per line
+pub struct InputMatches {
+    enabled: bool,
+    cancellation_token: CancellationToken,
+    lines: Option<HashSet<usize>>,
+}
+
+impl InputMatches {
+    const DOC_VARIABLE: &'static str = "$__rq_doc";
+    const MATCH_CLI_FLAGS: &'static str = "--null-input --compact-output";
+    // NOTE: slurping, reading raw lines, or ignoring the input all break the one record per line correspondence
+    const RECORD_BREAKING_CLI_FLAGS: &'static [(&'static str, char)] =
+        &[("--slurp", 's'), ("--raw-input", 'R'), ("--null-input", 'n')];
+
+    pub fn new() -> Self {
+        let enabled = false;
+        let cancellation_token = CancellationToken::new();
+        let lines = None;
+
+        Self {
+            enabled,
+            cancellation_token,
+            lines,
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    pub fn toggle(&mut self) {
+        self.enabled = !self.enabled;
+        self.reset();
+    }
+
+    pub fn lines(&self) -> Option<&HashSet<usize>> {
+        self.lines.as_ref()
+    }
+
+    // NOTE: cancels the previous run and returns the token for the next one
+    pub fn restart(&mut self, parent_token: &CancellationToken) -> CancellationToken {
+        self.reset();
+        self.cancellation_token = parent_token.child_token();
+
+        self.cancellation_token.clone()
+    }
+
+    pub fn reset(&mut self) {
+        self.cancellation_token.cancel();
+        self.lines = None;
+    }
+
+    pub fn cli_flags(cli_flags: &str, input: &str) -> Option<String> {
+        if !matches!(InputFormat::detect(input), Some(InputFormat::Ndjson)) {
+            return None;
+        }
+
+        if Self::RECORD_BREAKING_CLI_FLAGS
+            .iter()
+            .any(|(long, short)| CliFlags::contains(cli_flags, long, *short))
+        {
+            return None;
+        }
+
+        format!("{cli_flags} {}", Self::MATCH_CLI_FLAGS).some()
+    }
+
+    // NOTE: errors are suppressed per record so that one failing record doesn't end the run for the ones after it
+    pub fn filter(filter: &str, pre_filter: Option<&str>) -> String {
+        let doc = Self::DOC_VARIABLE;
+        let doc = match pre_filter {
+            Some(pre_filter) => format!("{doc} | {pre_filter}"),
+            None => doc.to_owned(),
+        };
+
+        format!(
+            "foreach inputs as {} (-1; . + 1; select([{doc} | try (\n{filter}\n)] | length > 0))",
+            Self::DOC_VARIABLE
+        )
+    }
+
+    pub fn set_output(&mut self, jq_output: &JqOutput, input: &str) {
+        if jq_output.error().is_some() {
+            self.lines = None;
+
+            return;
+        }
+
+        let scroll_view = jq_output.scroll_view();
+        let record_indices = (0..scroll_view.len_lines())
+            .filter_map(|index| scroll_view.line(index)?.trim().parse::<usize>().ok())
+            .collect::<HashSet<_>>();
+        let lines: HashSet<usize> = input
+            .lines()
+            .enumerate()
+            .filter(|(_index, line)| !line.trim().is_empty())
+            .enumerate()
+            .filter(|(record_index, _line)| record_indices.contains(record_index))
+            .map(|(_record_index, (index, _line))| index)
+            .collect();
+
+        self.lines = lines.some();
+    }
+}
@@ -0,0 +1,368 @@
+use crate::{
+    any::Any,
+    input::{Input, InputConfig},
+    pretty::PrettyPrinter,
+    receive_rate::ReceiveRate,
+    scroll::ScrollView,
+};
+use anyhow::Error;
+use crossterm::event::KeyEvent;
+use std::{
+    borrow::Cow,
+    collections::VecDeque,
+    io::Error as IoError,
+    num::NonZeroUsize,
+    path::{Path, PathBuf},
+};
+use tokio_util::sync::CancellationToken;
+use tui_textarea::{CursorMove, TextArea};
+
+struct PrettyView {
+    printer: PrettyPrinter,
+    scroll_view: ScrollView,
+}
+
+// NOTE: the raw scroll view holds exactly what is fed to jq, while the optional pretty view is only used for display;
+// the revision changes whenever the content changes other than by appending lines, e.g. on clearing, editing, or
+// dropping the oldest lines
+pub struct InputTab {
+    input: Input,
+    scroll_view: ScrollView,
+    pretty_view: Option<PrettyView>,
+    pretty_toggled: bool,
+    editor: Option<TextArea<'static>>,
+    receive_rate: ReceiveRate,
+    source: String,
+    revision: usize,
+}
+
+impl InputTab {
+    // NOTE: a single line this long (e.g. minified json) is unusable in the raw view, so the pretty view is enabled
+    // automatically
+    const PRETTY_LINE_LEN: usize = 256 * 1024;
+
+    fn new(input: Input, source: String) -> Self {
+        let scroll_view = ScrollView::new();
+
+        Self {
+            input,
+            scroll_view,
+            pretty_view: None,
+            pretty_toggled: false,
+            editor: None,
+            receive_rate: ReceiveRate::new(),
+            source,
+            revision: 0,
+        }
+    }
+
+    pub fn content(&self) -> &str {
+        self.scroll_view.content()
+    }
+
+    pub fn revision(&self) -> usize {
+        self.revision
+    }
+
+    pub fn len_lines(&self) -> usize {
+        self.scroll_view.len_lines()
+    }
+
+    pub fn is_pretty(&self) -> bool {
+        self.pretty_view.is_some()
+    }
+
+    fn enable_pretty(&mut self) {
+        let mut printer = PrettyPrinter::new();
+        let mut scroll_view = ScrollView::new();
+
+        for index in 0..self.scroll_view.len_lines() {
+            if let Some(line) = self.scroll_view.line(index) {
+                scroll_view.extend(printer.push_line(line));
+            }
+        }
+
+        self.pretty_view = PrettyView { printer, scroll_view }.some();
+    }
+
+    // NOTE: once the user has chosen a view, it is no longer switched automatically
+    pub fn toggle_pretty(&mut self) {
+        self.pretty_toggled = true;
+
+        if self.pretty_view.take().is_none() {
+            self.enable_pretty();
+        }
+    }
+
+    pub fn scroll_view(&self) -> &ScrollView {
+        match &self.pretty_view {
+            Some(pretty_view) => &pretty_view.scroll_view,
+            None => &self.scroll_view,
+        }
+    }
+
+    pub fn scroll_view_mut(&mut self) -> &mut ScrollView {
+        match &mut self.pretty_view {
+            Some(pretty_view) => &mut pretty_view.scroll_view,
+            None => &mut self.scroll_view,
+        }
+    }
+
+    pub fn editor_mut(&mut self) -> Option<&mut TextArea<'static>> {
+        self.editor.as_mut()
+    }
+
+    pub fn is_editing(&self) -> bool {
+        self.editor.is_some()
+    }
+
+    // NOTE: streaming input keeps arriving after a clear, so that only what arrives from then on is fed to jq
+    pub fn clear(&mut self) {
+        self.scroll_view = ScrollView::new();
+        self.revision += 1;
+
+        if let Some(editor) = &mut self.editor {
+            *editor = TextArea::default();
+        }
+
+        if self.pretty_view.is_some() {
+            self.enable_pretty();
+        }
+    }
+
+    // NOTE: edits are applied as they are made, so leaving the edit mode only drops the editor
+    pub fn toggle_editing(&mut self) {
+        if self.editor.take().is_some() {
+            return;
+        }
+
+        let lines = (0..self.scroll_view.len_lines())
+            .filter_map(|index| self.scroll_view.line(index))
+            .map(str::to_owned)
+            .collect();
+
+        self.editor = TextArea::new(lines).some();
+    }
+
+    fn apply_edits(&mut self) {
+        let Some(editor) = &self.editor else {
+            return;
+        };
+        let offset = self.scroll_view.offset();
+
+        self.scroll_view = editor.lines().iter().collect();
+        self.scroll_view.set_offset(offset);
+        self.revision += 1;
+
+        if self.pretty_view.is_some() {
+            self.enable_pretty();
+        }
+    }
+
+    // NOTE: returns if the content changed
+    pub fn handle_key_event(&mut self, key_event: KeyEvent) -> bool {
+        let Some(editor) = &mut self.editor else {
+            return false;
+        };
+        let changed = editor.input(key_event);
+
+        if changed {
+            self.apply_edits();
+        }
+
+        changed
+    }
+
+    // NOTE: lines that arrive while editing are appended to the editor without moving its cursor
+    fn extend_editor(&mut self, lines: &VecDeque<String>) {
+        let Some(editor) = &mut self.editor else {
+            return;
+        };
+        let (row, column) = editor.cursor();
+
+        editor.move_cursor(CursorMove::Bottom);
+        editor.move_cursor(CursorMove::End);
+
+        for line in lines {
+            editor.insert_newline();
+            editor.insert_str(line);
+        }
+
+        editor.move_cursor(CursorMove::Jump(row.cast(), column.cast()));
+    }
+
+    fn extend(&mut self, lines: &VecDeque<String>, keep_last: Option<NonZeroUsize>) {
+        self.receive_rate.record(lines.iter().map(|line| line.len() + 1).sum());
+        self.scroll_view.extend(lines);
+        self.extend_editor(lines);
+
+        if let Some(pretty_view) = &mut self.pretty_view {
+            for line in lines {
+                pretty_view.scroll_view.extend(pretty_view.printer.push_line(line));
+            }
+        } else if !self.pretty_toggled && lines.iter().any(|line| Self::PRETTY_LINE_LEN < line.len()) {
+            self.enable_pretty();
+        }
+
+        // NOTE: endless streams would otherwise grow without bound, so only the most recent lines are retained; the
+        // pretty view is trimmed to the same number of its own lines, which is a rough but cheap approximation
+        if let Some(keep_last) = keep_last {
+            if keep_last.get() < self.scroll_view.len_lines() {
+                self.revision += 1;
+            }
+
+            self.scroll_view.retain_last(keep_last);
+
+            if let Some(pretty_view) = &mut self.pretty_view {
+                pretty_view.scroll_view.retain_last(keep_last);
+            }
+        }
+    }
+
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    pub fn is_loading(&self) -> bool {
+        !self.input.is_complete()
+    }
+
+    pub fn receive_rate(&self) -> &ReceiveRate {
+        &self.receive_rate
+    }
+}
+
+pub struct InputSet {
+    tabs: Vec<InputTab>,
+    selected_idx: usize,
+    feed_all: bool,
+    keep_last: Option<NonZeroUsize>,
+}
+
+impl InputSet {
+    const STDIN_SOURCE: &'static str = "stdin";
+
+    pub async fn new(
+        input_filepaths: &[PathBuf],
+        config: InputConfig,
+        capture_filepath: Option<&Path>,
+        cancellation_token: &CancellationToken,
+    ) -> Result<Self, IoError> {
+        let mut tabs = Vec::new();
+
+        // NOTE:
+        // - if both an input filepath and `--null-input` are supplied, let `jq` determine what the output should be
+        //   by supplying both stdin and the --null-input flag
+        // - otherwise, if no input filepath is supplied, but `--null-input` is, definitely do not read from stdin
+        // - only stdin is captured since input files can already be read again after the session
+        for input_filepath in input_filepaths {
+            let input = Input::from_filepath(input_filepath, config, cancellation_token.child_token()).await?;
+
+            InputTab::new(input, input_filepath.display().to_string()).push_to(&mut tabs);
+        }
+
+        if tabs.is_empty() {
+            let input = Input::from_stdin(config, capture_filepath, cancellation_token.child_token()).await?;
+
+            InputTab::new(input, Self::STDIN_SOURCE.to_owned()).push_to(&mut tabs);
+        }
+
+        Self {
+            tabs,
+            selected_idx: 0,
+            feed_all: false,
+            keep_last: config.keep_last,
+        }
+        .ok()
+    }
+
+    pub async fn is_empty(&mut self) -> Result<bool, IoError> {
+        for tab in &mut self.tabs {
+            if tab.input.has_lines().await? {
+                return false.ok();
+            }
+        }
+
+        true.ok()
+    }
+
+    pub fn tabs(&self) -> &[InputTab] {
+        &self.tabs
+    }
+
+    pub fn selected_idx(&self) -> usize {
+        self.selected_idx
+    }
+
+    pub fn selected(&self) -> &InputTab {
+        &self.tabs[self.selected_idx]
+    }
+
+    pub fn selected_mut(&mut self) -> &mut InputTab {
+        &mut self.tabs[self.selected_idx]
+    }
+
+    pub fn feed_all(&self) -> bool {
+        self.feed_all
+    }
+
+    pub fn toggle_feed_all(&mut self) {
+        self.feed_all = !self.feed_all;
+    }
+
+    pub fn select_next(&mut self) {
+        self.selected_idx = (self.selected_idx + 1) % self.tabs.len();
+    }
+
+    pub fn select_previous(&mut self) {
+        self.selected_idx = (self.selected_idx + self.tabs.len() - 1) % self.tabs.len();
+    }
+
+    pub fn is_fed(&self, idx: usize) -> bool {
+        self.feed_all || idx == self.selected_idx
+    }
+
+    pub fn is_loading(&self) -> bool {
+        self.tabs
+            .iter()
+            .enumerate()
+            .any(|(idx, tab)| self.is_fed(idx) && tab.is_loading())
+    }
+
+    // NOTE: the content fed to jq is either that of the selected input or the concatenation of all inputs
+    pub fn content(&self) -> Cow<'_, str> {
+        if self.feed_all {
+            self.tabs.iter().map(InputTab::content).collect::<String>().into()
+        } else {
+            self.selected().content().into()
+        }
+    }
+
+    // NOTE: an empty placeholder tab, e.g. stdin when rq was started without any input, is replaced rather than kept
+    pub fn add(&mut self, input: Input, source: String) {
+        let input_tab = InputTab::new(input, source);
+
+        if let [tab] = self.tabs.as_slice() {
+            if tab.content().is_empty() {
+                self.tabs[0] = input_tab;
+
+                return;
+            }
+        }
+
+        input_tab.push_to(&mut self.tabs);
+        self.selected_idx = self.tabs.len() - 1;
+    }
+
+    pub fn extend(&mut self, idx: usize, lines: &VecDeque<String>) {
+        self.tabs[idx].extend(lines, self.keep_last);
+    }
+
+    // NOTE: this is cancel safe bc Input::next_lines() is
+    pub async fn next_lines(&mut self) -> (usize, Result<VecDeque<String>, Error>) {
+        let next_lines_futures = self.tabs.iter_mut().map(|tab| Box::pin(tab.input.next_lines()));
+        let (lines_res, idx, _next_lines_futures) = futures::future::select_all(next_lines_futures).await;
+
+        (idx, lines_res)
+    }
+}
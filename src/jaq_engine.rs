@@ -0,0 +1,260 @@
+use crate::{any::Any, jq_process::JsonArg};
+use anyhow::Error;
+use jaq_interpret::{Ctx, FilterT, ParseCtx, RcIter, Val};
+use serde_json::{Deserializer, Value};
+use tokio_util::sync::CancellationToken;
+
+#[derive(Default)]
+#[allow(clippy::struct_excessive_bools)]
+struct JaqFlags {
+    compact_output: bool,
+    join_output: bool,
+    null_input: bool,
+    raw_output: bool,
+    slurp: bool,
+}
+
+impl JaqFlags {
+    fn set_long(&mut self, flag: &str) -> Result<(), Error> {
+        match flag {
+            "--compact-output" => self.compact_output = true,
+            "--join-output" => {
+                self.join_output = true;
+                self.raw_output = true;
+            }
+            "--null-input" => self.null_input = true,
+            "--raw-output" => self.raw_output = true,
+            "--slurp" => self.slurp = true,
+            flag => anyhow::bail!("{flag} is not supported by the jaq engine"),
+        }
+
+        ().ok()
+    }
+
+    fn set_short(&mut self, flag: char) -> Result<(), Error> {
+        let long_flag = match flag {
+            'c' => "--compact-output",
+            'j' => "--join-output",
+            'n' => "--null-input",
+            'r' => "--raw-output",
+            's' => "--slurp",
+            flag => anyhow::bail!("-{flag} is not supported by the jaq engine"),
+        };
+
+        self.set_long(long_flag)
+    }
+
+    fn new(args: &[String]) -> Result<Self, Error> {
+        let mut flags = Self::default();
+
+        for arg in args {
+            if arg.starts_with("--") {
+                flags.set_long(arg)?;
+            } else if let Some(cluster) = arg.strip_prefix('-') {
+                for flag in cluster.chars() {
+                    flags.set_short(flag)?;
+                }
+            } else {
+                anyhow::bail!("{arg} is not supported by the jaq engine");
+            }
+        }
+
+        flags.ok()
+    }
+}
+
+// NOTE: evaluates filters in-process with jaq so that rq works where no jq executable is installed; only the cli flags
+// that change how the input is read and the output is printed are supported
+pub struct JaqEngine {
+    flags: JaqFlags,
+    filter: String,
+    json_args: Vec<(String, String)>,
+    input: String,
+}
+
+impl JaqEngine {
+    pub fn new(args: &[String], filter: &str, json_args: &[JsonArg], input: &[u8]) -> Result<Self, Error> {
+        let flags = JaqFlags::new(args)?;
+        let filter = filter.to_owned();
+        let json_args = json_args
+            .iter()
+            .map(|json_arg| (json_arg.name.clone(), json_arg.json.clone()))
+            .collect();
+        let input = input.to_str()?.to_owned();
+
+        Self {
+            flags,
+            filter,
+            json_args,
+            input,
+        }
+        .ok()
+    }
+
+    fn parse_ctx(&self) -> ParseCtx {
+        let names = self.json_args.iter().map(|(name, _json)| name.clone()).collect();
+        let mut parse_ctx = ParseCtx::new(names);
+
+        parse_ctx.insert_natives(jaq_core::core());
+        parse_ctx.insert_defs(jaq_std::std());
+
+        parse_ctx
+    }
+
+    fn filter(&self) -> Result<jaq_interpret::Filter, Error> {
+        let mut parse_ctx = self.parse_ctx();
+        let (main, errors) = jaq_parse::parse(&self.filter, jaq_parse::main());
+        let main = match main {
+            Some(main) if errors.is_empty() => main,
+            _main => {
+                let errors = errors.iter().map(ToString::to_string).collect::<Vec<_>>().join(", ");
+
+                anyhow::bail!("unable to parse filter: {errors}");
+            }
+        };
+        let filter = parse_ctx.compile(main);
+
+        anyhow::ensure!(
+            parse_ctx.errs.is_empty(),
+            "unable to compile filter: {} undefined reference(s)",
+            parse_ctx.errs.len()
+        );
+
+        filter.ok()
+    }
+
+    fn vars(&self) -> Result<Vec<Val>, Error> {
+        self.json_args
+            .iter()
+            .map(|(_name, json)| serde_json::from_str::<Value>(json).map(Val::from).map_err(Error::from))
+            .collect()
+    }
+
+    fn inputs(&self) -> Result<Vec<Val>, Error> {
+        if self.flags.null_input {
+            return vec![Val::Null].ok();
+        }
+
+        let values = Deserializer::from_str(&self.input)
+            .into_iter::<Value>()
+            .map(|value_res| value_res.map(Val::from))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        if self.flags.slurp {
+            return vec![Val::Arr(values.into())].ok();
+        }
+
+        values.ok()
+    }
+
+    // NOTE: true once the output exceeds max_output_bytes, in which case only its complete lines are kept like when
+    // the output of a jq child is truncated
+    fn push_val(&self, val: Val, output: &mut String, max_output_bytes: Option<usize>) -> Result<bool, Error> {
+        match val {
+            Val::Str(string) if self.flags.raw_output => output.push_str(&string),
+            val if self.flags.compact_output => output.push_str(&serde_json::to_string(&Value::from(val))?),
+            val => output.push_str(&serde_json::to_string_pretty(&Value::from(val))?),
+        }
+
+        if !self.flags.join_output {
+            output.push('\n');
+        }
+
+        let Some(max_output_bytes) = max_output_bytes else {
+            return false.ok();
+        };
+
+        if output.len() <= max_output_bytes {
+            return false.ok();
+        }
+
+        let len = output.as_bytes()[..max_output_bytes]
+            .iter()
+            .rposition(|byte| *byte == b'\n')
+            .map_or(0, |index| index + 1);
+
+        output.truncate(len);
+
+        true.ok()
+    }
+
+    fn evaluate(
+        &self,
+        output: &mut String,
+        max_output_bytes: Option<usize>,
+        stop_token: &CancellationToken,
+    ) -> Result<bool, Error> {
+        let filter = self.filter()?;
+        let vars = self.vars()?;
+        let inputs = RcIter::new(std::iter::empty());
+
+        for input in self.inputs()? {
+            for val_res in filter.run((Ctx::new(vars.clone(), &inputs), input)) {
+                anyhow::ensure!(!stop_token.is_cancelled(), "jaq run was stopped");
+
+                let val = val_res.map_err(|error| anyhow::anyhow!("{error}"))?;
+
+                if self.push_val(val, output, max_output_bytes)? {
+                    return true.ok();
+                }
+            }
+        }
+
+        false.ok()
+    }
+
+    // NOTE: like jq, results produced before an error are kept alongside it; the bool tells whether the output was
+    // truncated at max_output_bytes
+    pub fn run(
+        &self,
+        max_output_bytes: Option<usize>,
+        stop_token: &CancellationToken,
+    ) -> (String, bool, Option<String>) {
+        let mut output = String::new();
+
+        match self.evaluate(&mut output, max_output_bytes, stop_token) {
+            Ok(truncated) => (output, truncated, None),
+            Err(error) => (output, false, error.to_string().some()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::JaqEngine;
+    use tokio_util::sync::CancellationToken;
+
+    fn run(
+        filter: &str,
+        max_output_bytes: Option<usize>,
+        stop_token: &CancellationToken,
+    ) -> (String, bool, Option<String>) {
+        let args = ["--null-input".to_owned(), "--compact-output".to_owned()];
+
+        JaqEngine::new(&args, filter, &[], &[])
+            .expect("flags are supported")
+            .run(max_output_bytes, stop_token)
+    }
+
+    #[test]
+    fn run_truncates_endless_output_at_the_limit() {
+        let (output, truncated, error) = run("range(1e9)", 10.into(), &CancellationToken::new());
+
+        assert_eq!(output, "0\n1\n2\n3\n4\n");
+        assert!(truncated);
+        assert_eq!(error, None);
+    }
+
+    #[test]
+    fn run_stops_once_cancelled() {
+        let stop_token = CancellationToken::new();
+
+        stop_token.cancel();
+
+        let (output, truncated, error) = run("repeat(.)", None, &stop_token);
+
+        assert_eq!(output, "");
+        assert!(!truncated);
+        assert_eq!(error, "jaq run was stopped".to_owned().into());
+    }
+}
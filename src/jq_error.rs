@@ -0,0 +1,77 @@
+use crate::any::Any;
+use regex::Regex;
+
+// NOTE: maps jq error messages to the fragments of the filter that most likely raised them; every candidate is tried
+// in order and the first one found in the filter wins
+pub struct JqError;
+
+impl JqError {
+    const INDEX_PATTERN: &'static str = r#"Cannot index \w+ with "([^"]*)""#;
+    const UNDEFINED_PATTERN: &'static str = r"(\w+)/\d+ is not defined";
+    const POSITION_PATTERN: &'static str = r"line (\d+), column (\d+)";
+    const ITERATE_MESSAGE: &'static str = "Cannot iterate over";
+    const OPERATORS: &'static [(&'static str, &'static str)] = &[
+        ("cannot be added", "+"),
+        ("cannot be subtracted", "-"),
+        ("cannot be multiplied", "*"),
+        ("cannot be divided", "/"),
+    ];
+
+    fn captured(pattern: &str, error: &str) -> Option<String> {
+        let regex = Regex::new(pattern).log_if_error()?;
+
+        regex.captures(error)?.get(1)?.as_str().to_owned().some()
+    }
+
+    fn candidates(error: &str) -> Vec<String> {
+        if let Some(key) = Self::captured(Self::INDEX_PATTERN, error) {
+            return vec![format!(".{key}"), format!("\"{key}\"")];
+        }
+
+        if let Some(name) = Self::captured(Self::UNDEFINED_PATTERN, error) {
+            return vec![name];
+        }
+
+        if error.contains(Self::ITERATE_MESSAGE) {
+            return vec!["[]".to_owned()];
+        }
+
+        Self::OPERATORS
+            .iter()
+            .filter(|(message, _operator)| error.contains(message))
+            .map(|(_message, operator)| (*operator).to_owned())
+            .collect()
+    }
+
+    pub fn fragment(error: &str, filter: &str) -> Option<String> {
+        Self::candidates(error)
+            .into_iter()
+            .find(|candidate| filter.contains(candidate.as_str()))
+    }
+
+    // NOTE: prefers the (1-based) line and column jq reports for compile errors and otherwise falls back to where the
+    // fragment that most likely raised the error starts; the returned row and column are 0-based and the column is
+    // counted in chars; `first_line` is the number of lines run ahead of the filter, e.g. by a pre-filter
+    pub fn position(error: &str, filter: &str, first_line: usize) -> Option<(usize, usize)> {
+        if let Some((line, column)) = Self::reported_position(error) {
+            return (line.saturating_sub(1 + first_line), column.saturating_sub(1)).some();
+        }
+
+        let fragment = Self::fragment(error, filter)?;
+        let index = filter.find(fragment.as_str())?;
+        let before = &filter[..index];
+        let row = before.matches('\n').count();
+        let line_begin = before.rfind('\n').map_or(0, |line_break| line_break + 1);
+
+        (row, before[line_begin..].chars().count()).some()
+    }
+
+    fn reported_position(error: &str) -> Option<(usize, usize)> {
+        let regex = Regex::new(Self::POSITION_PATTERN).log_if_error()?;
+        let captures = regex.captures(error)?;
+        let row = captures.get(1)?.as_str().parse().ok()?;
+        let column = captures.get(2)?.as_str().parse().ok()?;
+
+        (row, column).some()
+    }
+}
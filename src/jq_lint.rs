@@ -0,0 +1,254 @@
+use crate::{any::Any, jq_syntax::JqSyntax};
+
+enum Token {
+    Field(String),
+    Variable(String),
+    Identifier(String),
+    Operator(&'static str),
+    Open(char),
+    Close(char),
+    Other,
+}
+
+#[derive(Default)]
+struct Scope {
+    in_select: bool,
+    in_condition: bool,
+    variables: Vec<String>,
+    num_parameters: usize,
+}
+
+// NOTE: flags a few common mistakes in the filter as it is typed; like the highlighting, it only tokenizes the filter,
+// so scopes are approximated by brackets and the warnings are hints that never keep the filter from running
+pub struct JqLint;
+
+impl JqLint {
+    // NOTE: longest first so that e.g. `==` isn't read as two assignments
+    const OPERATORS: &'static [&'static str] = &[
+        "?//=", "?//", "//=", "|=", "+=", "-=", "*=", "/=", "%=", "==", "!=", "<=", ">=", "//", "=", "|", ";", "?",
+    ];
+    const OPERATOR_KEYWORDS: &'static [&'static str] = &["and", "elif", "else", "if", "or", "then"];
+    const SELECT: &'static str = "select";
+
+    fn tokens(filter: &str) -> Vec<Token> {
+        let chars = filter.chars().collect::<Vec<_>>();
+        let mut tokens = Vec::new();
+        let mut index = 0;
+
+        while index < chars.len() {
+            let chr = chars[index];
+            let (end, token) = match chr {
+                chr if chr.is_whitespace() => {
+                    index += 1;
+
+                    continue;
+                }
+                '"' => match JqSyntax::string_end(&chars, index) {
+                    Some(end) => (end + 1, Token::Other),
+                    None => break,
+                },
+                '#' => {
+                    index = JqSyntax::line_end(&chars, index);
+
+                    continue;
+                }
+                '$' => {
+                    let end = JqSyntax::identifier_end(&chars, index + 1);
+
+                    (end, Token::Variable(chars[index..end].iter().collect()))
+                }
+                '.' => match chars.get(index + 1) {
+                    Some('"') => match JqSyntax::string_end(&chars, index + 1) {
+                        Some(end) => (end + 1, Token::Field(chars[index..=end].iter().collect())),
+                        None => break,
+                    },
+                    Some(chr) if chr.is_alphabetic() || *chr == '_' => {
+                        let end = JqSyntax::identifier_end(&chars, index + 1);
+
+                        (end, Token::Field(chars[index..end].iter().collect()))
+                    }
+                    Some('.') => (index + 2, Token::Other),
+                    _chr => (index + 1, Token::Other),
+                },
+                chr if chr.is_alphabetic() || chr == '_' => {
+                    let end = JqSyntax::identifier_end(&chars, index);
+
+                    (end, Token::Identifier(chars[index..end].iter().collect()))
+                }
+                '(' | '[' | '{' => (index + 1, Token::Open(chr)),
+                ')' | ']' | '}' => (index + 1, Token::Close(chr)),
+                _chr => match Self::OPERATORS
+                    .iter()
+                    .find(|operator| Self::starts_with(&chars[index..], operator))
+                {
+                    Some(operator) => (index + operator.len(), Token::Operator(operator)),
+                    None => (index + 1, Token::Other),
+                },
+            };
+
+            token.push_to(&mut tokens);
+            index = end;
+        }
+
+        tokens
+    }
+
+    // NOTE: the operators are ascii, so their length in bytes is their length in chars
+    fn starts_with(chars: &[char], operator: &str) -> bool {
+        chars.len() >= operator.len() && operator.chars().zip(chars).all(|(lhs, rhs)| lhs == *rhs)
+    }
+
+    fn take_pattern(pattern: &mut Option<(usize, Vec<String>)>, depth: usize) -> Option<Vec<String>> {
+        if pattern
+            .as_ref()
+            .is_none_or(|(pattern_depth, _variables)| *pattern_depth != depth)
+        {
+            return None;
+        }
+
+        pattern.take().map(|(_depth, variables)| variables)
+    }
+
+    fn is_bound(scopes: &[Scope], variable: &str) -> bool {
+        scopes
+            .iter()
+            .any(|scope| scope.variables.iter().any(|bound| bound == variable))
+    }
+
+    // NOTE: the variables of an `as` pattern are bound once the pattern is followed by `|`, or by the `(` of a
+    // `reduce`/`foreach` in which case they only live inside its parentheses; `?//` alternatives may repeat them
+    pub fn warnings(filter: &str) -> Vec<String> {
+        let tokens = Self::tokens(filter);
+        let mut warnings = Vec::<String>::new();
+        let mut scopes = vec![Scope::default()];
+        let mut pattern: Option<(usize, Vec<String>)> = None;
+
+        for (index, token) in tokens.iter().enumerate() {
+            let previous = index.checked_sub(1).and_then(|index| tokens.get(index));
+            let depth = scopes.len() - 1;
+
+            match token {
+                Token::Open(chr) => {
+                    // NOTE: plain parentheses stay part of the condition they are in, unlike the arguments of a call
+                    let is_call = matches!(previous, Some(Token::Identifier(name)) if !Self::OPERATOR_KEYWORDS.contains(&name.as_str()));
+                    let is_group = *chr == '(' && !is_call;
+                    let is_select =
+                        *chr == '(' && matches!(previous, Some(Token::Identifier(name)) if name == Self::SELECT);
+                    let mut scope = Scope {
+                        in_select: is_select || (is_group && scopes[depth].in_select),
+                        in_condition: is_group && scopes[depth].in_condition,
+                        ..Scope::default()
+                    };
+
+                    if *chr == '(' {
+                        if let Some(variables) = Self::take_pattern(&mut pattern, depth) {
+                            scope.num_parameters = variables.len();
+                            scope.variables = variables;
+                        }
+                    }
+
+                    if let (Some(Token::Field(field)), Some(Token::Close(']')), next) =
+                        (previous, tokens.get(index + 1), tokens.get(index + 2))
+                    {
+                        if *chr == '[' && !matches!(next, Some(Token::Operator("?"))) {
+                            format!("`{field}[]` fails when {field} is null, `{field}[]?` skips it")
+                                .push_to(&mut warnings);
+                        }
+                    }
+
+                    scope.push_to(&mut scopes);
+                }
+                Token::Close(_chr) if scopes.len() > 1 => {
+                    scopes.pop();
+                }
+                Token::Operator("|") => {
+                    if let Some(variables) = Self::take_pattern(&mut pattern, depth) {
+                        scopes[depth].variables.extend(variables);
+                    }
+                }
+                Token::Operator(";") => {
+                    let scope = &mut scopes[depth];
+
+                    scope.variables.truncate(scope.num_parameters);
+                    scope.in_condition = false;
+                }
+                Token::Operator("=") => {
+                    let scope = &scopes[depth];
+
+                    if scope.in_select || scope.in_condition {
+                        "`=` assigns rather than compares, did you mean `==`?"
+                            .to_owned()
+                            .push_to(&mut warnings);
+                    }
+                }
+                Token::Identifier(identifier) => match identifier.as_str() {
+                    "if" | "elif" => scopes[depth].in_condition = true,
+                    "then" => scopes[depth].in_condition = false,
+                    "as" => pattern = (depth, Vec::new()).some(),
+                    _identifier => {}
+                },
+                Token::Variable(variable) => {
+                    if let Some((_depth, variables)) = &mut pattern {
+                        if variables.contains(variable) {
+                            continue;
+                        }
+
+                        if Self::is_bound(&scopes, variable) {
+                            format!("`{variable}` shadows an earlier binding").push_to(&mut warnings);
+                        }
+
+                        variable.clone().push_to(variables);
+                    }
+                }
+                _token => {}
+            }
+        }
+
+        warnings.dedup();
+
+        warnings
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::JqLint;
+
+    #[test]
+    fn warnings_flag_assignments_in_conditions() {
+        let warning = "`=` assigns rather than compares, did you mean `==`?";
+
+        assert_eq!(JqLint::warnings("select(.a = 1)"), [warning]);
+        assert_eq!(JqLint::warnings("select((.a = 1) and .b)"), [warning]);
+        assert_eq!(JqLint::warnings("if .a = 1 then . else empty end"), [warning]);
+        assert!(JqLint::warnings("select(.a == 1)").is_empty());
+        assert!(JqLint::warnings(".a = 1 | select(.b)").is_empty());
+        assert!(JqLint::warnings("select(any(.a = 1; .b))").is_empty());
+    }
+
+    #[test]
+    fn warnings_flag_iterating_a_field_that_may_be_null() {
+        assert_eq!(
+            JqLint::warnings(".items[] | .name"),
+            ["`.items[]` fails when .items is null, `.items[]?` skips it"]
+        );
+        assert!(JqLint::warnings(".items[]? | .name").is_empty());
+        assert!(JqLint::warnings(".items[0]").is_empty());
+    }
+
+    #[test]
+    fn warnings_flag_shadowed_bindings() {
+        assert_eq!(
+            JqLint::warnings(". as $x | .a as $x | $x"),
+            ["`$x` shadows an earlier binding"]
+        );
+        assert!(JqLint::warnings(". as $x | .a as $y | $x + $y").is_empty());
+        assert!(JqLint::warnings(". as [$a] ?// $a | $a").is_empty());
+        assert!(JqLint::warnings("reduce .[] as $x (0; . + $x) | . as $x | $x").is_empty());
+    }
+
+    #[test]
+    fn warnings_ignore_strings_and_comments() {
+        assert!(JqLint::warnings(r#"select(.a == "b = c") # select(.a = 1)"#).is_empty());
+    }
+}
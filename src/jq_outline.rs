@@ -0,0 +1,408 @@
+use crate::{any::Any, jq_syntax::JqSyntax};
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    layout::{Constraint, Flex, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Clear, List, ListState},
+    Frame,
+};
+use std::path::{Path, PathBuf};
+
+// NOTE: where a function is defined, either in the filter itself or in a module it imports; rows and columns count
+// chars from the start of the filter or module, like the cursor of the filter editor
+#[derive(Clone)]
+pub struct Definition {
+    pub name: String,
+    pub arity: usize,
+    pub row: usize,
+    pub column: usize,
+    pub module: Option<PathBuf>,
+}
+
+impl Definition {
+    fn title(&self) -> String {
+        format!("{}/{}", self.name, self.arity)
+    }
+}
+
+enum Token {
+    Identifier(String),
+    String(String),
+    Other(char),
+}
+
+struct Module {
+    path: String,
+    alias: Option<String>,
+}
+
+// NOTE: finds the `def`s of a jq program by tokenizing it like the highlighting does, so definitions nested inside
+// other definitions are found too but their scope is not tracked
+pub struct JqOutline;
+
+impl JqOutline {
+    const DEF: &'static str = "def";
+    const IMPORT: &'static str = "import";
+    const INCLUDE: &'static str = "include";
+    const AS: &'static str = "as";
+    const MODULE_SEPARATOR: &'static str = "::";
+    const MODULE_EXTENSION: &'static str = "jq";
+    const HOME_VAR: &'static str = "HOME";
+    const HOME_LIBRARY: &'static str = ".jq";
+
+    // NOTE: every token along with the row and column of its first char, skipping whitespace and comments
+    fn tokens(program: &str) -> Vec<(usize, usize, Token)> {
+        let chars = program.chars().collect::<Vec<_>>();
+        let mut tokens = Vec::new();
+        let mut index = 0;
+        let mut row = 0;
+        let mut line_begin = 0;
+
+        while index < chars.len() {
+            let chr = chars[index];
+            let column = index - line_begin;
+            let (end, token) = match chr {
+                '\n' => {
+                    index += 1;
+                    row += 1;
+                    line_begin = index;
+
+                    continue;
+                }
+                chr if chr.is_whitespace() => {
+                    index += 1;
+
+                    continue;
+                }
+                '#' => {
+                    index = JqSyntax::line_end(&chars, index);
+
+                    continue;
+                }
+                '"' => match JqSyntax::string_end(&chars, index) {
+                    Some(end) => (end + 1, Token::String(chars[index + 1..end].iter().collect())),
+                    None => break,
+                },
+                chr if chr.is_alphabetic() || chr == '_' => {
+                    let mut end = JqSyntax::identifier_end(&chars, index);
+
+                    // NOTE: `module::name` is a single identifier
+                    while chars.get(end..end + 2) == Some(&[':', ':'][..])
+                        && chars.get(end + 2).is_some_and(|chr| chr.is_alphabetic() || *chr == '_')
+                    {
+                        end = JqSyntax::identifier_end(&chars, end + 2);
+                    }
+
+                    (end, Token::Identifier(chars[index..end].iter().collect()))
+                }
+                chr => (index + 1, Token::Other(chr)),
+            };
+
+            (row, column, token).push_to(&mut tokens);
+            index = end;
+        }
+
+        tokens
+    }
+
+    // NOTE: `def name(f; $x):` has an arity of 2, the number of parameters separated by `;`
+    fn arity(tokens: &[(usize, usize, Token)]) -> usize {
+        let Some((_row, _column, Token::Other('('))) = tokens.first() else {
+            return 0;
+        };
+        let mut depth = 0;
+        let mut arity = 1;
+
+        for (_row, _column, token) in tokens {
+            match token {
+                Token::Other('(' | '[' | '{') => depth += 1,
+                Token::Other(')' | ']' | '}') if depth == 1 => break,
+                Token::Other(')' | ']' | '}') => depth -= 1,
+                Token::Other(';') if depth == 1 => arity += 1,
+                _token => {}
+            }
+        }
+
+        arity
+    }
+
+    pub fn definitions(program: &str, module: Option<&Path>) -> Vec<Definition> {
+        let tokens = Self::tokens(program);
+        let mut definitions = Vec::new();
+
+        for (index, (_row, _column, token)) in tokens.iter().enumerate() {
+            let Token::Identifier(identifier) = token else {
+                continue;
+            };
+            let Some((row, column, Token::Identifier(name))) = tokens.get(index + 1) else {
+                continue;
+            };
+
+            if identifier != Self::DEF {
+                continue;
+            }
+
+            Definition {
+                name: name.clone(),
+                arity: Self::arity(&tokens[index + 2..]),
+                row: *row,
+                column: *column,
+                module: module.map(Path::to_path_buf),
+            }
+            .push_to(&mut definitions);
+        }
+
+        definitions
+    }
+
+    // NOTE: `import "path" as name;` and `include "path";`, while `import "path" as $name;` imports data
+    fn modules(program: &str) -> Vec<Module> {
+        let tokens = Self::tokens(program);
+        let mut modules = Vec::new();
+
+        for (index, (_row, _column, token)) in tokens.iter().enumerate() {
+            let Token::Identifier(directive) = token else {
+                continue;
+            };
+            let Some((_row, _column, Token::String(path))) = tokens.get(index + 1) else {
+                continue;
+            };
+            let alias = match (directive.as_str(), tokens.get(index + 2), tokens.get(index + 3)) {
+                (Self::INCLUDE, _as_keyword, _alias) => None,
+                (
+                    Self::IMPORT,
+                    Some((_as_row, _as_column, Token::Identifier(as_keyword))),
+                    Some((_row, _column, Token::Identifier(alias))),
+                ) if as_keyword == Self::AS => alias.clone().some(),
+                _directive => continue,
+            };
+
+            Module {
+                path: path.clone(),
+                alias,
+            }
+            .push_to(&mut modules);
+        }
+
+        modules
+    }
+
+    // NOTE: jq looks for `path.jq` and then `path/<last component>.jq` in each directory of the search path
+    fn module_filepath(library_paths: &[PathBuf], path: &str) -> Option<PathBuf> {
+        let last_component = path.rsplit('/').next().unwrap_or(path);
+
+        library_paths.iter().find_map(|library_path| {
+            [
+                library_path.join(format!("{path}.{}", Self::MODULE_EXTENSION)),
+                library_path
+                    .join(path)
+                    .join(format!("{last_component}.{}", Self::MODULE_EXTENSION)),
+            ]
+            .into_iter()
+            .find(|filepath| filepath.is_file())
+        })
+    }
+
+    // NOTE: the `-L` directories followed by `~/.jq`, which is the part of jq's default search path that doesn't
+    // depend on where the jq binary is installed
+    pub fn library_paths(mut library_paths: Vec<PathBuf>) -> Vec<PathBuf> {
+        if let Some(home) = std::env::var_os(Self::HOME_VAR) {
+            PathBuf::from(home).join(Self::HOME_LIBRARY).push_to(&mut library_paths);
+        }
+
+        library_paths
+    }
+
+    // NOTE: the definitions of imported modules are named the way they are called, e.g. `name::func`; modules that
+    // can't be found or read are left out since jq itself reports them when the filter runs
+    pub async fn module_definitions(program: &str, library_paths: &[PathBuf]) -> Vec<Definition> {
+        let mut definitions = Vec::new();
+
+        for module in Self::modules(program) {
+            let Some(filepath) = Self::module_filepath(library_paths, &module.path) else {
+                continue;
+            };
+            let Some(content) = tokio::fs::read_to_string(&filepath).await.log_if_error() else {
+                continue;
+            };
+
+            for mut definition in Self::definitions(&content, filepath.as_path().some()) {
+                if let Some(alias) = &module.alias {
+                    definition.name = format!("{alias}{}{}", Self::MODULE_SEPARATOR, definition.name);
+                }
+
+                definition.push_to(&mut definitions);
+            }
+        }
+
+        definitions
+    }
+
+    // NOTE: the identifier under the cursor, including a `module::` prefix
+    pub fn identifier_at(program: &str, (row, column): (usize, usize)) -> Option<String> {
+        let line = program.split('\n').nth(row)?.chars().collect::<Vec<_>>();
+        let is_identifier_char = |chr: &char| chr.is_alphanumeric() || *chr == '_' || *chr == ':';
+        let column = if line.get(column).is_some_and(is_identifier_char) {
+            column
+        } else {
+            column
+                .checked_sub(1)
+                .filter(|column| line.get(*column).is_some_and(is_identifier_char))?
+        };
+        let begin = line[..column]
+            .iter()
+            .rposition(|chr| !is_identifier_char(chr))
+            .map_or(0, |index| index + 1);
+        let end = line[column..]
+            .iter()
+            .position(|chr| !is_identifier_char(chr))
+            .map_or(line.len(), |index| column + index);
+        let identifier = line[begin..end].iter().collect::<String>();
+        let identifier = identifier.trim_matches(':');
+
+        (!identifier.is_empty()).then(|| identifier.to_owned())
+    }
+
+    // NOTE: a later definition of the same name shadows an earlier one, so the closest definition before the call site
+    // is preferred, then any definition in the filter, and then one from a module
+    pub fn find<'a>(
+        definitions: &'a [Definition],
+        name: &str,
+        (row, column): (usize, usize),
+    ) -> Option<&'a Definition> {
+        let mut candidates = definitions.iter().filter(|definition| definition.name == name);
+        let is_before = |definition: &&Definition| (definition.row, definition.column) < (row, column);
+
+        candidates
+            .clone()
+            .filter(|definition| definition.module.is_none())
+            .rfind(is_before)
+            .or_else(|| candidates.clone().find(|definition| definition.module.is_none()))
+            .or_else(|| candidates.next())
+    }
+}
+
+pub enum OutlineEvent {
+    Selected(Definition),
+    Cancelled,
+}
+
+// NOTE: lists the definitions of the filter followed by those of its modules
+pub struct Outline {
+    definitions: Vec<Definition>,
+    selected: usize,
+}
+
+impl Outline {
+    const PERCENTAGE: u16 = 60;
+    const STYLE_SELECTED: Style = Style::new().add_modifier(Modifier::REVERSED);
+    const STYLE_LOCATION: Style = Style::new().add_modifier(Modifier::DIM);
+    const TITLE: &'static str = "OUTLINE (enter to jump, esc to cancel)";
+
+    pub fn new(definitions: Vec<Definition>) -> Self {
+        let selected = 0;
+
+        Self { definitions, selected }
+    }
+
+    pub fn handle_key_event(&mut self, key_event: KeyEvent) -> Option<OutlineEvent> {
+        match key_event.code {
+            KeyCode::Esc => OutlineEvent::Cancelled.some(),
+            KeyCode::Enter => OutlineEvent::Selected(self.definitions.get(self.selected)?.clone()).some(),
+            KeyCode::Up => {
+                self.selected = self.selected.saturating_sub(1);
+
+                None
+            }
+            KeyCode::Down => {
+                if self.selected + 1 < self.definitions.len() {
+                    self.selected += 1;
+                }
+
+                None
+            }
+            _code => None,
+        }
+    }
+
+    fn rect(area: Rect) -> Rect {
+        let [rect] = Layout::vertical([Constraint::Percentage(Self::PERCENTAGE)])
+            .flex(Flex::Center)
+            .areas(area);
+        let [rect] = Layout::horizontal([Constraint::Percentage(Self::PERCENTAGE)])
+            .flex(Flex::Center)
+            .areas(rect);
+
+        rect
+    }
+
+    fn line(definition: &Definition) -> Line<'static> {
+        let location = match &definition.module {
+            Some(module) => format!(" {}:{}", module.display(), definition.row + 1),
+            None => format!(" line {}", definition.row + 1),
+        };
+
+        Line::from(vec![
+            Span::raw(definition.title()),
+            Span::styled(location, Self::STYLE_LOCATION),
+        ])
+    }
+
+    pub fn render(&self, frame: &mut Frame) {
+        let rect = Self::rect(frame.area());
+        let mut list_state = ListState::default().with_selected(self.selected.some());
+        let list = List::new(self.definitions.iter().map(Self::line))
+            .block(Self::TITLE.block())
+            .highlight_style(Self::STYLE_SELECTED);
+
+        Clear.render_to(frame, rect);
+        frame.render_stateful_widget(list, rect, &mut list_state);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::JqOutline;
+
+    #[test]
+    fn definitions_finds_nested_defs_with_their_arity_and_position() {
+        let program = "def f: 1;\n  def g(a; $b): def h: [a, $b]; h;\n# def commented: 0;\n\"def in_string\" | g(f; 2)";
+        let definitions = JqOutline::definitions(program, None)
+            .into_iter()
+            .map(|definition| (definition.name, definition.arity, definition.row, definition.column))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            definitions,
+            [
+                ("f".to_owned(), 0, 0, 4),
+                ("g".to_owned(), 2, 1, 6),
+                ("h".to_owned(), 0, 1, 20),
+            ]
+        );
+    }
+
+    #[test]
+    fn identifier_at_includes_the_module_prefix() {
+        let program = "import \"lib\" as lib;\nlib::func(.a) | length";
+
+        assert_eq!(JqOutline::identifier_at(program, (1, 6)), "lib::func".to_owned().into());
+        assert_eq!(JqOutline::identifier_at(program, (1, 9)), "lib::func".to_owned().into());
+        assert_eq!(JqOutline::identifier_at(program, (1, 16)), "length".to_owned().into());
+        assert_eq!(JqOutline::identifier_at(program, (1, 14)), None);
+    }
+
+    #[test]
+    fn find_prefers_the_closest_preceding_definition() {
+        let definitions = JqOutline::definitions("def f: 1;\ndef f: 2;\nf | def f: 3; f", None);
+        let row_of = |position| JqOutline::find(&definitions, "f", position).map(|definition| definition.row);
+
+        assert_eq!(row_of((2, 0)), 1.into());
+        assert_eq!(row_of((0, 0)), 0.into());
+        assert_eq!(
+            JqOutline::find(&definitions, "g", (2, 0)).map(|definition| definition.row),
+            None
+        );
+    }
+}
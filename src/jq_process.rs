@@ -1,53 +1,301 @@
-use crate::{any::Any, scroll::ScrollView};
+#[cfg(feature = "jaq")]
+use crate::jaq_engine::JaqEngine;
+use crate::{
+    any::Any, channel::BroadcastSender, cli_args::Engine, cli_flags::CliFlags, scroll::ScrollView,
+    scroll_memory::ScrollMemory,
+};
 use anyhow::Error;
-use std::{process::Stdio, time::Instant};
-use tokio::{process::Command, sync::mpsc::UnboundedSender};
+use serde::Serialize;
+#[cfg(feature = "jaq")]
+use std::sync::Arc;
+use std::{
+    borrow::Cow,
+    fmt::Display,
+    io::Error as IoError,
+    num::NonZeroUsize,
+    process::{ExitStatus, Stdio},
+    sync::atomic::{AtomicU64, Ordering},
+    time::{Duration, Instant},
+};
+use tokio::{
+    io::AsyncReadExt,
+    process::{Child, ChildStderr, ChildStdout, Command},
+};
+use tokio_util::sync::CancellationToken;
 
+// NOTE: every built process gets the next run id so that logs, errors, and the status line can tell apart the runs
+// of fast successive edits
+static NEXT_RUN_ID: AtomicU64 = AtomicU64::new(1);
+
+#[derive(Clone)]
 pub struct JqOutput {
+    run_id: Option<u64>,
     instant: Instant,
+    cli_flags: String,
+    filter: String,
     scroll_view: ScrollView,
+    error: Option<String>,
+    duration: Duration,
+    exit_code: Option<i32>,
+    preview_bytes: Option<usize>,
+    skipped_errors: Vec<String>,
+}
+
+#[derive(Serialize)]
+pub struct JqOutputMeta {
+    filter: String,
+    flags: String,
+    duration_ms: u128,
+    exit_status: Option<i32>,
+    output: String,
 }
 
 impl JqOutput {
-    pub fn new(instant: Instant, content: &str) -> Self {
+    pub fn new(instant: Instant, cli_flags: String, filter: String, content: &str) -> Self {
         let scroll_view = content.lines().collect();
 
-        Self { instant, scroll_view }
+        Self {
+            run_id: None,
+            instant,
+            cli_flags,
+            filter,
+            scroll_view,
+            error: None,
+            duration: Duration::ZERO,
+            exit_code: None,
+            preview_bytes: None,
+            skipped_errors: Vec::new(),
+        }
+    }
+
+    fn with_run_id(mut self, run_id: u64) -> Self {
+        self.run_id = run_id.some();
+
+        self
+    }
+
+    // NOTE: none for outputs that weren't produced by a jq run, e.g. the initial empty one
+    pub fn run_id(&self) -> Option<u64> {
+        self.run_id
+    }
+
+    fn with_preview_bytes(mut self, preview_bytes: Option<usize>) -> Self {
+        self.preview_bytes = preview_bytes;
+
+        self
+    }
+
+    // NOTE: some(bytes) when the content is only a preview that was cut off at the given size
+    pub fn preview_bytes(&self) -> Option<usize> {
+        self.preview_bytes
+    }
+
+    fn with_elapsed(mut self) -> Self {
+        self.duration = self.instant.elapsed();
+
+        self
+    }
+
+    fn with_exit_status(mut self, exit_status: ExitStatus) -> Self {
+        self.exit_code = exit_status.code();
+
+        self.with_elapsed()
+    }
+
+    fn with_skipped_errors(mut self, skipped_errors: Vec<String>) -> Self {
+        self.skipped_errors = skipped_errors;
+
+        self
+    }
+
+    // NOTE: the runtime errors of the records that were skipped rather than failing the run, one per record
+    pub fn skipped_errors(&self) -> &[String] {
+        &self.skipped_errors
+    }
+
+    pub fn with_error(mut self, error: String) -> Self {
+        self.error = error.some();
+
+        self
     }
 
     pub fn empty() -> Self {
-        Self::new(Instant::now(), "")
+        Self::new(Instant::now(), String::new(), String::new(), "")
     }
 
     pub fn instant(&self) -> Instant {
         self.instant
     }
 
+    pub fn cli_flags(&self) -> &str {
+        &self.cli_flags
+    }
+
+    pub fn filter(&self) -> &str {
+        &self.filter
+    }
+
+    pub fn error(&self) -> Option<&str> {
+        self.error.as_deref()
+    }
+
+    pub fn scroll_view(&self) -> &ScrollView {
+        &self.scroll_view
+    }
+
     pub fn scroll_view_mut(&mut self) -> &mut ScrollView {
         &mut self.scroll_view
     }
 
     pub fn with_scroll_view_offset(mut self, other: &Self) -> Self {
         self.scroll_view.set_offset(other.scroll_view.offset());
+        self.scroll_view.set_cursor(other.scroll_view.cursor());
 
         self
     }
+
+    // NOTE: outputs of the same filter keep the current scroll position, while switching to another filter restores
+    // where that filter's output was last scrolled to, falling back to the current scroll position
+    pub fn with_remembered_scroll_view_offset(
+        mut self,
+        other: &Self,
+        scroll_memory: &mut ScrollMemory<String>,
+    ) -> Self {
+        // NOTE: the search carries over to every new output so that its matches are found there as well
+        self.scroll_view
+            .set_search(other.scroll_view.search().map(str::to_owned));
+
+        if self.filter == other.filter {
+            return self.with_scroll_view_offset(other);
+        }
+
+        scroll_memory.save(other.filter.clone(), &other.scroll_view);
+
+        // NOTE: the cursor only exists while the output pane is focused, so only keep a restored cursor in that case
+        if scroll_memory.restore(&self.filter, &mut self.scroll_view) {
+            let cursor = other
+                .scroll_view
+                .cursor()
+                .map(|cursor| self.scroll_view.cursor().unwrap_or(cursor));
+
+            self.scroll_view.set_cursor(cursor);
+
+            return self;
+        }
+
+        self.with_scroll_view_offset(other)
+    }
+
+    pub fn into_content(mut self) -> String {
+        self.scroll_view.take_content()
+    }
+
+    pub fn into_meta(mut self) -> JqOutputMeta {
+        JqOutputMeta {
+            filter: self.filter.mem_take(),
+            flags: self.cli_flags.trim().to_owned(),
+            duration_ms: self.duration.as_millis(),
+            exit_status: self.exit_code,
+            output: self.into_content(),
+        }
+    }
+}
+
+pub struct JsonArg {
+    pub name: String,
+    pub json: String,
+}
+
+// NOTE: how the app runs every jq process, where the precise executable takes over once the input has integers that
+// jq would round
+pub struct JqConfig {
+    pub engine: Engine,
+    pub executable: String,
+    pub precise_executable: Option<String>,
+    pub niceness: Option<i32>,
+    pub memory_limit_kb: Option<u64>,
+    pub max_output_bytes: Option<usize>,
+    pub timeout: Option<Duration>,
 }
 
 pub struct JqProcessBuilder<'a> {
+    #[cfg_attr(not(feature = "jaq"), allow(dead_code))]
+    pub engine: Engine,
+    pub executable: &'a str,
     pub cli_flags: &'a str,
     pub filter: &'a str,
+    pub pre_filter: Option<&'a str>,
+    pub json_args: &'a [JsonArg],
     pub input: &'a [u8],
-    pub jq_outputs_sender: UnboundedSender<Result<JqOutput, Error>>,
+    pub niceness: Option<i32>,
+    pub memory_limit_kb: Option<u64>,
+    pub max_output_bytes: Option<usize>,
+    pub preview_bytes: Option<usize>,
+    pub timeout: Option<Duration>,
+    pub skip_record_errors: bool,
+    pub clear_env: bool,
+    pub jq_outputs_sender: BroadcastSender<JqOutput>,
+    pub cancellation_token: CancellationToken,
 }
 
-impl<'a> JqProcessBuilder<'a> {
-    const JQ_EXECUTABLE_NAME: &'static str = "jq";
+impl JqProcessBuilder<'_> {
+    const NICE_EXECUTABLE_NAME: &'static str = "nice";
+    const SHELL_EXECUTABLE_NAME: &'static str = "sh";
+    const MEMORY_LIMIT_SCRIPT: &'static str = r#"ulimit -v "$0" && exec "$@""#;
     const DEFAULT_FILTER: &'static str = ".";
+    const JSON_ARG_FLAG: &'static str = "--argjson";
+    const PATH_ENV_VAR: &'static str = "PATH";
+
+    // NOTE: run jq through `nice` and a `ulimit` shell rather than adjusting the priority and resource limits of the
+    // child ourselves so that no unsafe pre-exec hook is needed
+    fn command(&self) -> Command {
+        let executable = self.executable;
+        let mut args = Vec::new();
+
+        if let Some(niceness) = self.niceness {
+            args.extend([
+                Self::NICE_EXECUTABLE_NAME.to_owned(),
+                "-n".to_owned(),
+                niceness.to_string(),
+            ]);
+        }
+
+        if let Some(memory_limit_kb) = self.memory_limit_kb {
+            args.extend([
+                Self::SHELL_EXECUTABLE_NAME.to_owned(),
+                "-c".to_owned(),
+                Self::MEMORY_LIMIT_SCRIPT.to_owned(),
+                memory_limit_kb.to_string(),
+            ]);
+        }
+
+        let mut command = Command::new(args.first().map_or(executable, String::as_str));
+
+        command.args(args.iter().skip(1));
+
+        if !args.is_empty() {
+            command.arg(executable);
+        }
+
+        // NOTE: jq exposes its environment through `$ENV` and `env`, so only PATH is kept for runs whose filters come
+        // from untrusted clients
+        if self.clear_env {
+            command.env_clear();
+
+            if let Some(path) = std::env::var_os(Self::PATH_ENV_VAR) {
+                command.env(Self::PATH_ENV_VAR, path);
+            }
+        }
+
+        command
+    }
 
     // TODO-d9feca: figure out why ok_or_error requires turbofish
     pub fn build(self) -> Result<JqProcess, Error> {
+        let run_id = NEXT_RUN_ID.fetch_add(1, Ordering::Relaxed);
         let instant = Instant::now();
+        let cli_flags = self.cli_flags.to_owned();
+        let filter_content = self.filter.to_owned();
         let args =
             shlex::split(self.cli_flags).ok_or_error::<Vec<String>>("unable to split cli-flags for the shell")?;
         let filter = if self.filter.is_empty() {
@@ -55,55 +303,374 @@ impl<'a> JqProcessBuilder<'a> {
         } else {
             self.filter
         };
-        let mut command = Command::new(Self::JQ_EXECUTABLE_NAME);
+        // NOTE: the filter is placed on a line of its own so that the columns jq reports for compile errors still line
+        // up with the filter editor and so that a trailing comment doesn't swallow the closing paren; outputs keep the
+        // filter as it was given
+        let filter = match self.pre_filter {
+            Some(pre_filter) => Cow::Owned(format!("{pre_filter} | (\n{filter}\n)")),
+            None => Cow::Borrowed(filter),
+        };
+        #[cfg(feature = "jaq")]
+        let jaq_engine = match self.engine {
+            Engine::Jq => None,
+            Engine::Jaq => Arc::new(JaqEngine::new(&args, &filter, self.json_args, self.input)?).some(),
+        };
+        let mut command = self.command();
+        let max_output_bytes = self.max_output_bytes;
+        let preview_bytes = self.preview_bytes;
+        let timeout = self.timeout;
+        let skip_record_errors = self.skip_record_errors;
         let jq_outputs_sender = self.jq_outputs_sender;
+        let cancellation_token = self.cancellation_token;
+
+        for json_arg in self.json_args {
+            command.arg(Self::JSON_ARG_FLAG).arg(&json_arg.name).arg(&json_arg.json);
+        }
+
+        // NOTE:
+        // - jq takes the first argument that isn't a flag as the filter even after --args/--jsonargs, so the filter has
+        //   to come before the positional values
+        // - kill_on_drop ensures the child is killed when the jq_output() future is dropped on cancellation
+        let positional_idx = args
+            .iter()
+            .position(|arg| CliFlags::is_positional_flag(arg))
+            .unwrap_or(args.len());
 
         command
-            .args(args)
-            .arg(filter)
+            .args(&args[..positional_idx])
+            .arg(filter.as_ref())
+            .args(&args[positional_idx..])
             .stdin(self.input.tempfile()?)
             .stdout(Stdio::piped())
-            .stderr(Stdio::piped());
+            .stderr(Stdio::piped())
+            .kill_on_drop(true);
+
+        tracing::debug!(run_id, filter = filter_content, "built jq run");
 
         JqProcess {
+            run_id,
             instant,
+            cli_flags,
+            filter: filter_content,
             command,
+            #[cfg(feature = "jaq")]
+            jaq_engine,
+            max_output_bytes,
+            preview_bytes,
+            timeout,
+            skip_record_errors,
             jq_outputs_sender,
+            cancellation_token,
         }
         .ok()
     }
 }
 
 pub struct JqProcess {
+    run_id: u64,
     instant: Instant,
+    cli_flags: String,
+    filter: String,
     command: Command,
-    jq_outputs_sender: UnboundedSender<Result<JqOutput, Error>>,
+    #[cfg(feature = "jaq")]
+    jaq_engine: Option<Arc<JaqEngine>>,
+    max_output_bytes: Option<usize>,
+    preview_bytes: Option<usize>,
+    timeout: Option<Duration>,
+    skip_record_errors: bool,
+    jq_outputs_sender: BroadcastSender<JqOutput>,
+    cancellation_token: CancellationToken,
 }
 
 impl JqProcess {
+    const RUNTIME_ERROR_EXIT_CODE: i32 = 5;
+    const RECORD_ERROR_PREFIX: &'static str = "jq: error (at ";
+
+    pub fn run_id(&self) -> u64 {
+        self.run_id
+    }
+
+    fn run_error(run_id: u64, error: impl Display) -> String {
+        format!("run #{run_id} {error}")
+    }
+
+    async fn timed_out(timeout: Option<Duration>) {
+        match timeout {
+            Some(timeout) => tokio::time::sleep(timeout).await,
+            None => std::future::pending().await,
+        }
+    }
+
+    // NOTE: some(bytes) when the preview limit is the one that applies, in which case exceeding it is not an error
+    fn preview_limit(&self) -> Option<usize> {
+        match (self.preview_bytes, self.max_output_bytes) {
+            (Some(preview_bytes), Some(max_output_bytes)) if max_output_bytes <= preview_bytes => None,
+            (preview_bytes, _max_output_bytes) => preview_bytes,
+        }
+    }
+
+    fn read_limit(&self) -> Option<usize> {
+        self.preview_limit().or(self.max_output_bytes)
+    }
+
+    // NOTE: once the output exceeds max_output_bytes, stop reading and kill jq, which would otherwise block on the
+    // full pipe; only complete lines of the truncated output are kept
+    async fn read_stdout(
+        mut stdout: ChildStdout,
+        child: &mut Child,
+        max_output_bytes: Option<usize>,
+    ) -> Result<(Vec<u8>, bool), IoError> {
+        let mut bytes = Vec::new();
+        let Some(max_output_bytes) = max_output_bytes else {
+            stdout.read_to_end(&mut bytes).await?;
+
+            return (bytes, false).ok();
+        };
+
+        stdout
+            .take(max_output_bytes.saturating_add(1).cast())
+            .read_to_end(&mut bytes)
+            .await?;
+
+        if bytes.len() <= max_output_bytes {
+            return (bytes, false).ok();
+        }
+
+        child.start_kill()?;
+
+        let len = bytes[..max_output_bytes]
+            .iter()
+            .rposition(|byte| *byte == b'\n')
+            .map_or(0, |index| index + 1);
+
+        bytes.truncate(len);
+
+        (bytes, true).ok()
+    }
+
     // TODO:
     // - TODO-d9feca
-    // - determine if this is useful: [https://docs.rs/tokio/latest/tokio/process/index.html#droppingcancellation]
-    // - figure out how to cancel previously started processes
-    //   - some join!(command, other) type thing where other can be set or told to cancel on updates/new calls to
-    //     this function
+    // NOTE: jq can write partial output to stdout before failing, so a failed run still produces a JqOutput that
+    // carries both its stdout and its error
     #[tracing::instrument(skip(self), fields(command = ?self.command), err)]
     async fn jq_output(&mut self) -> Result<JqOutput, Error> {
-        let output = self.command.output().await?;
+        #[cfg(feature = "jaq")]
+        if let Some(jaq_engine) = self.jaq_engine.clone() {
+            return self.jaq_output(jaq_engine).await;
+        }
+
+        let mut child = self.command.spawn()?;
+        let stdout = child
+            .stdout
+            .take()
+            .ok_or_error::<ChildStdout>("jq stdout is not piped")?;
+        let mut stderr = child
+            .stderr
+            .take()
+            .ok_or_error::<ChildStderr>("jq stderr is not piped")?;
+        let mut stderr_bytes = Vec::new();
+        let (stdout_res, stderr_res) = tokio::join!(
+            Self::read_stdout(stdout, &mut child, self.read_limit()),
+            stderr.read_to_end(&mut stderr_bytes),
+        );
+        let (stdout_bytes, truncated) = stdout_res?;
+        let status = child.wait().await?;
+        let jq_output = JqOutput::new(
+            self.instant,
+            self.cli_flags.clone(),
+            self.filter.clone(),
+            stdout_bytes.to_str()?,
+        )
+        .with_run_id(self.run_id)
+        .with_exit_status(status);
+
+        stderr_res?;
+
+        if truncated {
+            return self.truncated_output(jq_output).ok();
+        }
+
+        if status.success() {
+            return jq_output.ok();
+        }
 
-        anyhow::ensure!(
-            output.status.success(),
-            "[{status}] {stderr:?}",
-            status = output.status,
-            stderr = output.stderr.to_str()?
+        if let Some(skipped_errors) = self.record_errors(status, stderr_bytes.to_str()?) {
+            return jq_output.with_skipped_errors(skipped_errors).ok();
+        }
+
+        let stderr = stderr_bytes.to_str()?.lines().collect::<Vec<_>>().join(" ");
+        let error = format!("[{status}] {stderr}");
+
+        jq_output.with_error(Self::run_error(self.run_id, error)).ok()
+    }
+
+    fn truncated_output(&self, jq_output: JqOutput) -> JqOutput {
+        if let Some(preview_bytes) = self.preview_limit() {
+            return jq_output.with_preview_bytes(preview_bytes.some());
+        }
+
+        let error = format!(
+            "output exceeded {} and jq was stopped",
+            self.max_output_bytes.unwrap_or_default().human_bytes()
         );
 
-        JqOutput::new(self.instant, output.stdout.to_str()?).ok()
+        jq_output.with_error(Self::run_error(self.run_id, error))
+    }
+
+    // NOTE: jq moves on to the next input after a runtime error and only reports it on stderr and in its exit code, so
+    // a run whose only errors are those of single records is kept as successful when they are to be skipped; anything
+    // else on stderr, e.g. a compile error or invalid json, still fails the run
+    fn record_errors(&self, status: ExitStatus, stderr: &str) -> Option<Vec<String>> {
+        if !self.skip_record_errors || status.code() != Self::RUNTIME_ERROR_EXIT_CODE.some() {
+            return None;
+        }
+
+        let record_errors = stderr
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| line.starts_with(Self::RECORD_ERROR_PREFIX).then(|| line.to_owned()))
+            .collect::<Option<Vec<_>>>()?;
+
+        (!record_errors.is_empty()).then_some(record_errors)
     }
 
+    // NOTE: jaq runs on a blocking thread, so the resource limits of a child process don't apply; instead the
+    // evaluation stops at its next result once the output exceeds the read limit or once this future is dropped on
+    // cancellation or timeout, which cancels the stop token through the drop guard
+    #[cfg(feature = "jaq")]
+    async fn jaq_output(&self, jaq_engine: Arc<JaqEngine>) -> Result<JqOutput, Error> {
+        let stop_token = self.cancellation_token.child_token();
+        let _stop_guard = stop_token.clone().drop_guard();
+        let max_output_bytes = self.read_limit();
+        let (content, truncated, error) =
+            tokio::task::spawn_blocking(move || jaq_engine.run(max_output_bytes, &stop_token)).await?;
+        let jq_output = JqOutput::new(self.instant, self.cli_flags.clone(), self.filter.clone(), &content)
+            .with_run_id(self.run_id)
+            .with_elapsed();
+
+        if truncated {
+            return self.truncated_output(jq_output).ok();
+        }
+
+        match error {
+            Some(error) => jq_output.with_error(Self::run_error(self.run_id, error)).ok(),
+            None => jq_output.ok(),
+        }
+    }
+
+    #[tracing::instrument(skip_all, fields(run_id = self.run_id))]
     pub async fn run(mut self) {
-        let jq_output_res = self.jq_output().await;
+        let run_id = self.run_id;
+        let cancellation_token = self.cancellation_token.clone();
+        let timeout = self.timeout;
+
+        // NOTE: dropping the jq_output() future on timeout kills the child bc of kill_on_drop
+        let jq_output_res = tokio::select! {
+            jq_output_res = self.jq_output() => jq_output_res,
+            () = Self::timed_out(timeout) => {
+                Err(anyhow::anyhow!("jq timed out after {}ms", timeout.unwrap_or_default().as_millis()))
+            }
+            () = cancellation_token.cancelled() => return tracing::debug!("jq run cancelled"),
+        };
+        // NOTE: runs that fail without producing an output (e.g. on timeout) still send one carrying the error so that
+        // every result can be ordered by its run id
+        let jq_output = match jq_output_res {
+            Ok(jq_output) => jq_output,
+            Err(error) => JqOutput::new(self.instant, self.cli_flags.clone(), self.filter.clone(), "")
+                .with_run_id(run_id)
+                .with_elapsed()
+                .with_error(Self::run_error(run_id, format!("{error:#}"))),
+        };
 
-        self.jq_outputs_sender.send(jq_output_res).log_if_error();
+        // NOTE: mark the process as finished before its output is received so that JqProcessQueue no longer counts it
+        // as running by then
+        cancellation_token.cancel();
+
+        self.jq_outputs_sender.send(jq_output);
+    }
+}
+
+struct RunningJqProcess {
+    run_id: u64,
+    cli_flags: String,
+    filter: String,
+    cancellation_token: CancellationToken,
+}
+
+impl RunningJqProcess {
+    fn is_superseded_by(&self, jq_process: &JqProcess) -> bool {
+        self.cli_flags != jq_process.cli_flags || self.filter != jq_process.filter
+    }
+}
+
+// NOTE: only the newest pending process is kept bc the outputs of older ones would be discarded anyways; a process
+// counts as running until its cancellation token is cancelled, which happens once it finishes or is killed
+pub struct JqProcessQueue {
+    max_running: NonZeroUsize,
+    running: Vec<RunningJqProcess>,
+    pending: Option<JqProcess>,
+}
+
+impl JqProcessQueue {
+    pub fn new(max_running: NonZeroUsize) -> Self {
+        Self {
+            max_running,
+            running: Vec::new(),
+            pending: None,
+        }
+    }
+
+    fn spawn(&mut self, jq_process: JqProcess) {
+        RunningJqProcess {
+            run_id: jq_process.run_id,
+            cli_flags: jq_process.cli_flags.clone(),
+            filter: jq_process.filter.clone(),
+            cancellation_token: jq_process.cancellation_token.clone(),
+        }
+        .push_to(&mut self.running);
+
+        jq_process.run().spawn_task().unit();
+    }
+
+    fn retain_running(&mut self) {
+        self.running
+            .retain(|running_jq_process| !running_jq_process.cancellation_token.is_cancelled());
+    }
+
+    // NOTE: runs of a filter or cli-flags that have since been edited can only produce stale output, so they are killed
+    // rather than left to pile up behind slow filters; runs of the same filter on older input are kept so that
+    // streaming input still shows intermediate results
+    pub fn push(&mut self, jq_process: JqProcess) {
+        for running_jq_process in &self.running {
+            if running_jq_process.is_superseded_by(&jq_process) {
+                tracing::debug!(
+                    run_id = running_jq_process.run_id,
+                    superseded_by = jq_process.run_id,
+                    "killing superseded jq run"
+                );
+
+                running_jq_process.cancellation_token.cancel();
+            }
+        }
+
+        self.retain_running();
+        self.pending = None;
+
+        if self.running.len() < self.max_running.get() {
+            self.spawn(jq_process);
+        } else {
+            self.pending = jq_process.some();
+        }
+    }
+
+    // NOTE: should be called once per received output so that a pending process is spawned once there is room
+    pub fn on_finished(&mut self) {
+        self.retain_running();
+
+        if self.running.len() < self.max_running.get() {
+            if let Some(jq_process) = self.pending.take() {
+                self.spawn(jq_process);
+            }
+        }
     }
 }
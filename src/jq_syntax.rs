@@ -0,0 +1,313 @@
+use crate::{any::Any, theme::Theme};
+use ratatui::{
+    buffer::Buffer,
+    layout::{Position, Rect},
+    style::{Modifier, Style},
+};
+
+// NOTE: a lightweight jq tokenizer used to highlight the filter as it is typed; it doesn't parse the filter, so it
+// only recognizes what can be told apart locally (strings, variables, pipes, brackets, keywords, and builtins) and
+// marks unterminated strings and unbalanced brackets as invalid
+pub struct JqSyntax;
+
+impl JqSyntax {
+    pub const BUILTINS: &'static [&'static str] = &[
+        "add",
+        "all",
+        "any",
+        "arrays",
+        "ascii",
+        "ascii_downcase",
+        "ascii_upcase",
+        "booleans",
+        "bsearch",
+        "builtins",
+        "capture",
+        "combinations",
+        "contains",
+        "debug",
+        "del",
+        "delpaths",
+        "empty",
+        "endswith",
+        "env",
+        "error",
+        "explode",
+        "first",
+        "flatten",
+        "floor",
+        "from_entries",
+        "fromdate",
+        "fromdateiso8601",
+        "fromjson",
+        "getpath",
+        "gmtime",
+        "group_by",
+        "gsub",
+        "halt",
+        "halt_error",
+        "has",
+        "implode",
+        "in",
+        "index",
+        "indices",
+        "input",
+        "inputs",
+        "inside",
+        "isempty",
+        "isinfinite",
+        "isnan",
+        "isnormal",
+        "isvalid",
+        "iterables",
+        "join",
+        "keys",
+        "keys_unsorted",
+        "last",
+        "leaf_paths",
+        "length",
+        "limit",
+        "ltrimstr",
+        "map",
+        "map_values",
+        "match",
+        "max",
+        "max_by",
+        "min",
+        "min_by",
+        "mktime",
+        "nan",
+        "not",
+        "now",
+        "nth",
+        "nulls",
+        "numbers",
+        "objects",
+        "path",
+        "paths",
+        "pick",
+        "range",
+        "recurse",
+        "reverse",
+        "rindex",
+        "rtrimstr",
+        "scalars",
+        "scan",
+        "select",
+        "setpath",
+        "sort",
+        "sort_by",
+        "split",
+        "splits",
+        "sqrt",
+        "startswith",
+        "stderr",
+        "strftime",
+        "strings",
+        "strptime",
+        "sub",
+        "test",
+        "to_entries",
+        "toarray",
+        "todate",
+        "todateiso8601",
+        "tojson",
+        "tonumber",
+        "tostring",
+        "transpose",
+        "trim",
+        "ltrim",
+        "rtrim",
+        "type",
+        "unique",
+        "unique_by",
+        "until",
+        "utf8bytelength",
+        "values",
+        "walk",
+        "while",
+        "with_entries",
+    ];
+    const KEYWORDS: &'static [&'static str] = &[
+        "and", "as", "catch", "def", "elif", "else", "end", "foreach", "if", "import", "include", "label", "or",
+        "reduce", "then", "try",
+    ];
+
+    fn is_identifier_char(chr: char) -> bool {
+        chr.is_alphanumeric() || chr == '_'
+    }
+
+    pub fn string_end(chars: &[char], begin: usize) -> Option<usize> {
+        let mut index = begin + 1;
+
+        while index < chars.len() {
+            match chars[index] {
+                '\\' => index += 2,
+                '"' => return index.some(),
+                _ => index += 1,
+            }
+        }
+
+        None
+    }
+
+    pub fn identifier_end(chars: &[char], begin: usize) -> usize {
+        chars[begin..]
+            .iter()
+            .position(|chr| !Self::is_identifier_char(*chr))
+            .map_or(chars.len(), |length| begin + length)
+    }
+
+    // NOTE: comments end at the line break so that the lines after them in a multi-line filter are still highlighted
+    pub fn line_end(chars: &[char], begin: usize) -> usize {
+        chars[begin..]
+            .iter()
+            .position(|chr| *chr == '\n')
+            .map_or(chars.len(), |length| begin + length)
+    }
+
+    fn is_matching_bracket(open: char, close: char) -> bool {
+        matches!((open, close), ('(', ')') | ('[', ']') | ('{', '}'))
+    }
+
+    fn identifier_style(chars: &[char], begin: usize, identifier: &str, theme: &Theme) -> Option<Style> {
+        // NOTE: `.keys` is an object key rather than a call to the builtin
+        let is_key = begin > 0 && chars[begin - 1] == '.';
+
+        if Self::KEYWORDS.contains(&identifier) {
+            theme.syntax_keyword.some()
+        } else if !is_key && Self::BUILTINS.contains(&identifier) {
+            theme.syntax_builtin.some()
+        } else {
+            None
+        }
+    }
+
+    // NOTE: returns the style of every char of the filter
+    pub fn styles(filter: &str, theme: &Theme) -> Vec<Option<Style>> {
+        let invalid_style = theme.error.add_modifier(Modifier::BOLD);
+        let chars = filter.chars().collect::<Vec<_>>();
+        let mut styles = vec![None; chars.len()];
+        let mut open_brackets = Vec::<usize>::new();
+        let mut index = 0;
+
+        while index < chars.len() {
+            let chr = chars[index];
+            let (end, style) = match chr {
+                '"' => match Self::string_end(&chars, index) {
+                    Some(end) => (end + 1, theme.syntax_string.some()),
+                    None => (chars.len(), invalid_style.some()),
+                },
+                '#' => (Self::line_end(&chars, index), theme.syntax_comment.some()),
+                '$' => (Self::identifier_end(&chars, index + 1), theme.syntax_variable.some()),
+                '|' => (index + 1, theme.syntax_pipe.some()),
+                '(' | '[' | '{' => {
+                    open_brackets.push(index);
+
+                    (index + 1, theme.syntax_bracket.some())
+                }
+                ')' | ']' | '}' => match open_brackets.pop() {
+                    Some(open) if Self::is_matching_bracket(chars[open], chr) => {
+                        (index + 1, theme.syntax_bracket.some())
+                    }
+                    Some(open) => {
+                        styles[open] = invalid_style.some();
+
+                        (index + 1, invalid_style.some())
+                    }
+                    None => (index + 1, invalid_style.some()),
+                },
+                chr if chr.is_alphabetic() || chr == '_' => {
+                    let end = Self::identifier_end(&chars, index);
+                    let identifier = chars[index..end].iter().collect::<String>();
+
+                    (end, Self::identifier_style(&chars, index, &identifier, theme))
+                }
+                _ => (index + 1, None),
+            };
+
+            styles[index..end].fill(style);
+            index = end;
+        }
+
+        for open in open_brackets {
+            styles[open] = invalid_style.some();
+        }
+
+        styles
+    }
+
+    // NOTE: the text area doesn't expose its horizontal scroll, so the visible part of the filter is located by
+    // matching what was rendered against the filter, preferring the match that contains the cursor
+    fn visible_offset(filter: &[char], visible: &[char], cursor_column: usize) -> Option<usize> {
+        let max_offset = filter.len().checked_sub(visible.len())?;
+        let mut offsets = (0..=max_offset).filter(|offset| filter[*offset..*offset + visible.len()] == *visible);
+        let first = offsets.next()?;
+
+        offsets
+            .chain(first.some())
+            .find(|offset| (*offset..=*offset + visible.len()).contains(&cursor_column))
+            .unwrap_or(first)
+            .some()
+    }
+
+    // NOTE: all lines share the horizontal scroll of the text area, so it is located on the cursor line and then the
+    // first vertical scroll under which every other visible row matches its line is taken
+    fn viewport(lines: &[Vec<char>], rows: &[Vec<char>], cursor: (usize, usize)) -> Option<(usize, usize)> {
+        let (cursor_row, cursor_column) = cursor;
+        let cursor_line = lines.get(cursor_row)?;
+
+        (cursor_row.saturating_sub(rows.len().saturating_sub(1))..=cursor_row).find_map(|top| {
+            let left = Self::visible_offset(cursor_line, rows.get(cursor_row - top)?, cursor_column)?;
+            let matches = rows
+                .iter()
+                .enumerate()
+                .all(|(index, row)| match lines.get(top + index) {
+                    Some(line) => line.get(left..).unwrap_or_default().starts_with(row),
+                    None => row.is_empty(),
+                });
+
+            matches.then_some((top, left))
+        })
+    }
+
+    fn visible_row(buffer: &Buffer, rect: Rect, y: u16) -> Vec<char> {
+        let visible = (rect.left()..rect.right())
+            .map(|x| {
+                buffer
+                    .cell(Position::new(x, y))
+                    .and_then(|cell| cell.symbol().chars().next())
+                    .unwrap_or(' ')
+            })
+            .collect::<String>();
+
+        visible.trim_end().chars().collect()
+    }
+
+    pub fn highlight(buffer: &mut Buffer, rect: Rect, filter: &str, cursor: (usize, usize), theme: &Theme) {
+        let lines = filter
+            .split('\n')
+            .map(|line| line.chars().collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        let rows = (rect.top()..rect.bottom())
+            .map(|y| Self::visible_row(buffer, rect, y))
+            .collect::<Vec<_>>();
+        let Some((top, left)) = Self::viewport(&lines, &rows, cursor) else {
+            return;
+        };
+        let styles = Self::styles(filter, theme);
+        let mut line_begin = lines[..top].iter().map(|line| line.len() + 1).sum::<usize>();
+
+        for ((y, row), line) in (rect.top()..).zip(&rows).zip(&lines[top..]) {
+            let begin = line_begin + left;
+
+            for (x, style) in (rect.left()..).zip(styles.get(begin..begin + row.len()).unwrap_or_default()) {
+                if let (Some(cell), Some(style)) = (buffer.cell_mut(Position::new(x, y)), style) {
+                    cell.set_style(*style);
+                }
+            }
+
+            line_begin += line.len() + 1;
+        }
+    }
+}
@@ -0,0 +1,130 @@
+use serde_json::{Deserializer, Value};
+use std::collections::BTreeMap;
+
+// NOTE: the object keys found in the input arranged as a tree of paths, where arrays are looked through so that e.g.
+// `.items[].name` and `.items.name` both lead to the keys of the objects within `items`; only a bounded prefix of the
+// input is indexed so that building it stays cheap for large inputs, and the root remembers how much of it was indexed
+// so that streaming input is indexed as it is appended
+#[derive(Default)]
+pub struct KeyIndex {
+    children: BTreeMap<String, KeyIndex>,
+    indexed_len: usize,
+    finished: bool,
+}
+
+impl KeyIndex {
+    const MAX_BYTES: usize = 4 * 1024 * 1024;
+
+    // NOTE: a prefix of an oversized input is cut at a line break so that ndjson records are never split
+    fn indexed_prefix(content: &str) -> &str {
+        if content.len() <= Self::MAX_BYTES {
+            return content;
+        }
+
+        let end = content.as_bytes()[..Self::MAX_BYTES]
+            .iter()
+            .rposition(|byte| *byte == b'\n')
+            .unwrap_or_default();
+
+        &content[..end]
+    }
+
+    // NOTE: the content is expected to only have grown since the last call; a trailing document that is still
+    // incomplete is parsed again once more of it has arrived, while one that fails to parse otherwise ends the indexing
+    pub fn extend(&mut self, content: &str) {
+        if self.finished || content.len() == self.indexed_len {
+            return;
+        }
+
+        let prefix = Self::indexed_prefix(content);
+        let Some(appended) = prefix.get(self.indexed_len..) else {
+            self.finished = true;
+
+            return;
+        };
+        let mut values = Deserializer::from_str(appended).into_iter::<Value>();
+
+        loop {
+            match values.next() {
+                Some(Ok(value)) => self.insert(&value),
+                Some(Err(error)) if !error.is_eof() => {
+                    self.finished = true;
+
+                    break;
+                }
+                _value_res => break,
+            }
+        }
+
+        self.indexed_len += values.byte_offset();
+        self.finished |= prefix.len() < content.len();
+    }
+
+    fn insert(&mut self, value: &Value) {
+        match value {
+            Value::Object(object) => {
+                for (key, value) in object {
+                    self.children.entry(key.clone()).or_default().insert(value);
+                }
+            }
+            Value::Array(values) => {
+                for value in values {
+                    self.insert(value);
+                }
+            }
+            _value => {}
+        }
+    }
+
+    pub fn keys(&self, path: &[String]) -> Vec<&str> {
+        path.iter()
+            .try_fold(self, |key_index, key| key_index.children.get(key))
+            .map(|key_index| key_index.children.keys().map(String::as_str).collect())
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::KeyIndex;
+
+    #[test]
+    fn extend_indexes_appended_documents() {
+        let mut key_index = KeyIndex::default();
+        let mut content = r#"{"a":{"b":1}}"#.to_owned();
+
+        key_index.extend(&content);
+        content.push_str("\n{\"c\":2}");
+        key_index.extend(&content);
+
+        assert_eq!(key_index.keys(&[]), ["a", "c"]);
+        assert_eq!(key_index.keys(&["a".to_owned()]), ["b"]);
+    }
+
+    #[test]
+    fn extend_waits_for_an_incomplete_document() {
+        let mut key_index = KeyIndex::default();
+        let mut content = "{\n  \"a\": 1,".to_owned();
+
+        key_index.extend(&content);
+
+        assert!(key_index.keys(&[]).is_empty());
+
+        content.push_str("\n  \"b\": 2\n}");
+        key_index.extend(&content);
+
+        assert_eq!(key_index.keys(&[]), ["a", "b"]);
+    }
+
+    #[test]
+    fn extend_stops_at_invalid_json() {
+        let mut key_index = KeyIndex::default();
+        let mut content = "{\"a\":1}\nnot json".to_owned();
+
+        key_index.extend(&content);
+        content.push_str("\n{\"b\":2}");
+        key_index.extend(&content);
+
+        assert_eq!(key_index.keys(&[]), ["a"]);
+    }
+}
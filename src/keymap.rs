@@ -0,0 +1,491 @@
+use crate::{any::Any, paths::Paths};
+use anyhow::Error;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use std::{io::ErrorKind, path::PathBuf};
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Action {
+    Accept,
+    ClearInput,
+    CommandPalette,
+    CopyFilter,
+    CopyLine,
+    CopyOutput,
+    CopyScreen,
+    CursorDown,
+    CursorUp,
+    CycleFlagVariant,
+    CycleLayout,
+    EnableSlurp,
+    ExpandFilter,
+    FocusOutput,
+    GotoDefinition,
+    GotoLine,
+    LoadFullOutput,
+    NextInput,
+    NextMatch,
+    OpenInput,
+    PageDown,
+    PageUp,
+    PauseInput,
+    PreviousInput,
+    PreviousMatch,
+    Quit,
+    SaveOutput,
+    ScrollDown,
+    ScrollLeft,
+    ScrollOutputToLeft,
+    ScrollOutputToRight,
+    ScrollRight,
+    ScrollToBottom,
+    ScrollToLeft,
+    ScrollToRight,
+    ScrollToTop,
+    ScrollUp,
+    Search,
+    SearchHistory,
+    SearchPanes,
+    SetTimeWindow,
+    SetVariable,
+    ShowError,
+    ShowOutline,
+    ToggleEditing,
+    ToggleFeedAll,
+    ToggleFlag(&'static str, char),
+    ToggleFocus,
+    ToggleInputMatches,
+    ToggleLineNumbers,
+    TogglePretty,
+    ToggleSkipRecordErrors,
+    UnfocusOutput,
+}
+
+impl Action {
+    const NAMES: &'static [(&'static str, Self)] = &[
+        ("accept", Self::Accept),
+        ("clear-input", Self::ClearInput),
+        ("command-palette", Self::CommandPalette),
+        ("copy-filter", Self::CopyFilter),
+        ("copy-line", Self::CopyLine),
+        ("copy-output", Self::CopyOutput),
+        ("copy-screen", Self::CopyScreen),
+        ("cursor-down", Self::CursorDown),
+        ("cursor-up", Self::CursorUp),
+        ("cycle-flag-variant", Self::CycleFlagVariant),
+        ("cycle-layout", Self::CycleLayout),
+        ("enable-slurp", Self::EnableSlurp),
+        ("expand-filter", Self::ExpandFilter),
+        ("focus-output", Self::FocusOutput),
+        ("goto-definition", Self::GotoDefinition),
+        ("goto-line", Self::GotoLine),
+        ("load-full-output", Self::LoadFullOutput),
+        ("next-input", Self::NextInput),
+        ("next-match", Self::NextMatch),
+        ("open-input", Self::OpenInput),
+        ("page-down", Self::PageDown),
+        ("page-up", Self::PageUp),
+        ("pause-input", Self::PauseInput),
+        ("previous-input", Self::PreviousInput),
+        ("previous-match", Self::PreviousMatch),
+        ("quit", Self::Quit),
+        ("save-output", Self::SaveOutput),
+        ("scroll-down", Self::ScrollDown),
+        ("scroll-left", Self::ScrollLeft),
+        ("scroll-output-to-left", Self::ScrollOutputToLeft),
+        ("scroll-output-to-right", Self::ScrollOutputToRight),
+        ("scroll-right", Self::ScrollRight),
+        ("scroll-to-bottom", Self::ScrollToBottom),
+        ("scroll-to-left", Self::ScrollToLeft),
+        ("scroll-to-right", Self::ScrollToRight),
+        ("scroll-to-top", Self::ScrollToTop),
+        ("scroll-up", Self::ScrollUp),
+        ("search", Self::Search),
+        ("search-history", Self::SearchHistory),
+        ("search-panes", Self::SearchPanes),
+        ("set-time-window", Self::SetTimeWindow),
+        ("set-variable", Self::SetVariable),
+        ("show-error", Self::ShowError),
+        ("show-outline", Self::ShowOutline),
+        ("toggle-compact-output", Self::ToggleFlag("--compact-output", 'c')),
+        ("toggle-editing", Self::ToggleEditing),
+        ("toggle-feed-all", Self::ToggleFeedAll),
+        ("toggle-focus", Self::ToggleFocus),
+        ("toggle-input-matches", Self::ToggleInputMatches),
+        ("toggle-line-numbers", Self::ToggleLineNumbers),
+        ("toggle-pretty", Self::TogglePretty),
+        ("toggle-raw-input", Self::ToggleFlag("--raw-input", 'R')),
+        ("toggle-raw-output", Self::ToggleFlag("--raw-output", 'r')),
+        ("toggle-slurp", Self::ToggleFlag("--slurp", 's')),
+        ("toggle-skip-record-errors", Self::ToggleSkipRecordErrors),
+        ("toggle-sort-keys", Self::ToggleFlag("--sort-keys", 'S')),
+        ("unfocus-output", Self::UnfocusOutput),
+    ];
+
+    fn from_name(name: &str) -> Option<Self> {
+        Self::NAMES
+            .iter()
+            .find(|(action_name, _action)| *action_name == name)
+            .map(|(_action_name, action)| *action)
+    }
+
+    // NOTE: while the selected input is being edited every other key goes to the input editor
+    pub fn is_global(self) -> bool {
+        matches!(
+            self,
+            Self::Quit
+                | Self::ToggleEditing
+                | Self::CommandPalette
+                | Self::SetVariable
+                | Self::CycleLayout
+                | Self::LoadFullOutput
+                | Self::CycleFlagVariant
+                | Self::ShowError
+                | Self::OpenInput
+                | Self::SaveOutput
+        )
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+struct KeyBinding {
+    code: KeyCode,
+    modifiers: KeyModifiers,
+}
+
+impl KeyBinding {
+    const SEPARATOR: char = '+';
+    const NAMED_KEYS: &'static [(&'static str, KeyCode)] = &[
+        ("backspace", KeyCode::Backspace),
+        ("backtab", KeyCode::BackTab),
+        ("delete", KeyCode::Delete),
+        ("down", KeyCode::Down),
+        ("end", KeyCode::End),
+        ("enter", KeyCode::Enter),
+        ("esc", KeyCode::Esc),
+        ("home", KeyCode::Home),
+        ("insert", KeyCode::Insert),
+        ("left", KeyCode::Left),
+        ("pagedown", KeyCode::PageDown),
+        ("pageup", KeyCode::PageUp),
+        ("right", KeyCode::Right),
+        ("space", KeyCode::Char(' ')),
+        ("tab", KeyCode::Tab),
+        ("up", KeyCode::Up),
+    ];
+    const MODIFIERS: &'static [(&'static str, KeyModifiers)] = &[
+        ("alt", KeyModifiers::ALT),
+        ("ctrl", KeyModifiers::CONTROL),
+        ("shift", KeyModifiers::SHIFT),
+    ];
+
+    // NOTE: shifted chars already arrive as their uppercase or symbol variant, so shift is only kept for other keys
+    fn new(code: KeyCode, modifiers: KeyModifiers) -> Self {
+        let modifiers = if let KeyCode::Char(_chr) = code {
+            modifiers.difference(KeyModifiers::SHIFT)
+        } else {
+            modifiers
+        };
+
+        Self { code, modifiers }
+    }
+
+    fn parse_code(name: &str) -> Option<KeyCode> {
+        if let Some((_name, code)) = Self::NAMED_KEYS.iter().find(|(key_name, _code)| *key_name == name) {
+            return (*code).some();
+        }
+
+        if let Some(number) = name.strip_prefix('f').and_then(|number| number.parse::<u8>().ok()) {
+            return KeyCode::F(number).some();
+        }
+
+        let mut chars = name.chars();
+
+        match (chars.next(), chars.next()) {
+            (Some(chr), None) => KeyCode::Char(chr).some(),
+            _chars => None,
+        }
+    }
+
+    // NOTE: e.g. `ctrl+r`, `f5`, or `alt+shift+up`; a lone `+` is the plus key rather than a separator
+    fn parse(spec: &str) -> Option<Self> {
+        let (modifier_names, name) = match spec.rsplit_once(Self::SEPARATOR) {
+            Some(("", "")) => ("", "+"),
+            Some((modifier_names, "")) => (modifier_names.strip_suffix(Self::SEPARATOR)?, "+"),
+            Some((modifier_names, name)) => (modifier_names, name),
+            None => ("", spec),
+        };
+        let mut modifiers = KeyModifiers::NONE;
+
+        for modifier_name in modifier_names.split(Self::SEPARATOR).filter(|name| !name.is_empty()) {
+            let (_name, modifier) = Self::MODIFIERS
+                .iter()
+                .find(|(name, _modifier)| *name == modifier_name)?;
+
+            modifiers |= *modifier;
+        }
+
+        Self::new(Self::parse_code(name)?, modifiers).some()
+    }
+
+    fn describe(self) -> String {
+        let mut description = String::new();
+
+        for (name, modifier) in Self::MODIFIERS {
+            if self.modifiers.contains(*modifier) {
+                description.push_str(name);
+                description.push(Self::SEPARATOR);
+            }
+        }
+
+        match self.code {
+            KeyCode::Char(' ') => description.push_str("space"),
+            KeyCode::Char(chr) => description.push(chr),
+            KeyCode::F(number) => {
+                description.push('f');
+                description.push_str(&number.to_string());
+            }
+            code => {
+                let name = Self::NAMED_KEYS
+                    .iter()
+                    .find(|(_name, named_code)| *named_code == code)
+                    .map_or("?", |(name, _named_code)| name);
+
+                description.push_str(name);
+            }
+        }
+
+        description
+    }
+}
+
+// NOTE: maps keys to actions, starting from the default bindings and then applying the keymap file on top of them,
+// one `<key> = <action>` per line where `#` starts a comment and the action `none` unbinds the key
+pub struct Keymap {
+    bindings: Vec<(KeyBinding, Action)>,
+}
+
+impl Keymap {
+    const FILE_NAME: &'static str = "keymap";
+    const COMMENT_PREFIX: char = '#';
+    const UNBOUND_ACTION: &'static str = "none";
+    const DEFAULT_BINDINGS: &'static str = "
+        ctrl+c = quit
+        enter = accept
+        tab = toggle-focus
+        f1 = command-palette
+        f2 = toggle-feed-all
+        f3 = enable-slurp
+        f4 = toggle-pretty
+        f5 = toggle-editing
+        f6 = set-variable
+        f7 = cycle-layout
+        f8 = load-full-output
+        f9 = cycle-flag-variant
+        f10 = show-error
+        f11 = open-input
+        f12 = save-output
+        ctrl+pageup = previous-input
+        ctrl+pagedown = next-input
+        ctrl+o = focus-output
+        ctrl+p = pause-input
+        ctrl+l = toggle-input-matches
+        alt+l = toggle-line-numbers
+        ctrl+r = search-history
+        ctrl+f = search-panes
+        ctrl+t = set-time-window
+        ctrl+e = expand-filter
+        ctrl+g = goto-definition
+        alt+g = show-outline
+        / = search
+        n = next-match
+        N = previous-match
+        : = goto-line
+        y = copy-line
+        Y = copy-screen
+        j = cursor-down
+        down = cursor-down
+        k = cursor-up
+        up = cursor-up
+        home = scroll-output-to-left
+        end = scroll-output-to-right
+        esc = unfocus-output
+        shift+up = scroll-up
+        shift+down = scroll-down
+        shift+left = scroll-left
+        shift+right = scroll-right
+        pageup = page-up
+        pagedown = page-down
+        ctrl+home = scroll-to-top
+        ctrl+end = scroll-to-bottom
+        shift+home = scroll-to-left
+        shift+end = scroll-to-right
+    ";
+
+    pub fn default_filepath() -> Option<PathBuf> {
+        Paths::config_dir().map(|directory| directory.join(Self::FILE_NAME))
+    }
+
+    fn apply_line(&mut self, line: &str) -> Result<(), Error> {
+        let Some((spec, action_name)) = line.rsplit_once('=') else {
+            anyhow::bail!("expected `<key> = <action>`, found {line:?}");
+        };
+        let (spec, action_name) = (spec.trim(), action_name.trim());
+        let Some(key_binding) = KeyBinding::parse(spec) else {
+            anyhow::bail!("unknown key {spec:?}");
+        };
+
+        self.bindings
+            .retain(|(bound_key_binding, _action)| *bound_key_binding != key_binding);
+
+        if action_name == Self::UNBOUND_ACTION {
+            return ().ok();
+        }
+
+        let Some(action) = Action::from_name(action_name) else {
+            anyhow::bail!("unknown action {action_name:?}");
+        };
+
+        (key_binding, action).push_to(&mut self.bindings).ok()
+    }
+
+    fn apply(&mut self, content: &str) -> Result<(), Error> {
+        for (index, line) in content.lines().enumerate() {
+            let line = line.split(Self::COMMENT_PREFIX).next().unwrap_or_default().trim();
+
+            if line.is_empty() {
+                continue;
+            }
+
+            if let Err(error) = self.apply_line(line) {
+                anyhow::bail!("line {}: {error}", index + 1);
+            }
+        }
+
+        ().ok()
+    }
+
+    // NOTE: a missing keymap file leaves the default bindings as they are, while an invalid one is reported rather
+    // than silently ignored so that a typo doesn't leave a key unexpectedly unbound; the bindings given with --bind
+    // are applied last
+    pub async fn load(filepath: Option<PathBuf>, key_bindings: &[String]) -> Result<Self, Error> {
+        let mut keymap = Self { bindings: Vec::new() };
+
+        keymap.apply(Self::DEFAULT_BINDINGS)?;
+
+        if let Some(filepath) = filepath {
+            match tokio::fs::read_to_string(&filepath).await {
+                Ok(content) => {
+                    if let Err(error) = keymap.apply(&content) {
+                        anyhow::bail!("invalid keymap {}: {error}", filepath.display());
+                    }
+                }
+                Err(error) if error.kind() == ErrorKind::NotFound => {}
+                Err(error) => anyhow::bail!("unable to read keymap {}: {error}", filepath.display()),
+            }
+        }
+
+        for key_binding in key_bindings {
+            if let Err(error) = keymap.apply_line(key_binding) {
+                anyhow::bail!("invalid --bind {key_binding:?}: {error}");
+            }
+        }
+
+        keymap.ok()
+    }
+
+    pub fn action(&self, key_event: &KeyEvent) -> Option<Action> {
+        let key_binding = KeyBinding::new(key_event.code, key_event.modifiers);
+
+        self.bindings
+            .iter()
+            .find(|(bound_key_binding, _action)| *bound_key_binding == key_binding)
+            .map(|(_key_binding, action)| *action)
+    }
+
+    // NOTE: every key bound to the action, e.g. for the hints in the command palette
+    pub fn keys(&self, action: Action) -> String {
+        self.bindings
+            .iter()
+            .filter(|(_key_binding, bound_action)| *bound_action == action)
+            .map(|(key_binding, _action)| key_binding.describe())
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    // NOTE: e.g. `ctrl+p to resume` for the hints in titles and notices, which are left out once the action is unbound
+    pub fn hint(&self, action: Action, description: &str) -> Option<String> {
+        let keys = self.keys(action);
+
+        (!keys.is_empty()).then(|| format!("{keys} {description}"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Action, KeyBinding, Keymap};
+    use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+    fn parse(spec: &str) -> Option<(KeyCode, KeyModifiers)> {
+        KeyBinding::parse(spec).map(|key_binding| (key_binding.code, key_binding.modifiers))
+    }
+
+    #[test]
+    fn parse_reads_modifiers_and_named_keys() {
+        assert_eq!(parse("ctrl+r"), (KeyCode::Char('r'), KeyModifiers::CONTROL).into());
+        assert_eq!(
+            parse("alt+shift+up"),
+            (KeyCode::Up, KeyModifiers::ALT | KeyModifiers::SHIFT).into()
+        );
+        assert_eq!(parse("f12"), (KeyCode::F(12), KeyModifiers::NONE).into());
+        assert_eq!(parse("space"), (KeyCode::Char(' '), KeyModifiers::NONE).into());
+    }
+
+    #[test]
+    fn parse_treats_a_trailing_plus_as_the_plus_key() {
+        assert_eq!(parse("+"), (KeyCode::Char('+'), KeyModifiers::NONE).into());
+        assert_eq!(parse("ctrl++"), (KeyCode::Char('+'), KeyModifiers::CONTROL).into());
+    }
+
+    #[test]
+    fn parse_drops_shift_from_chars() {
+        assert_eq!(parse("shift+N"), (KeyCode::Char('N'), KeyModifiers::NONE).into());
+    }
+
+    #[test]
+    fn parse_rejects_unknown_keys_and_modifiers() {
+        assert_eq!(parse("hyper+a"), None);
+        assert_eq!(parse("ctrl+nope"), None);
+        assert_eq!(parse("f256"), None);
+        assert_eq!(parse(""), None);
+    }
+
+    fn default_action(code: KeyCode, modifiers: KeyModifiers) -> Option<Action> {
+        let mut keymap = Keymap { bindings: Vec::new() };
+
+        keymap.apply(Keymap::DEFAULT_BINDINGS).ok()?;
+        keymap.action(&KeyEvent::new(code, modifiers))
+    }
+
+    #[test]
+    fn default_bindings_cover_the_pane_keys() {
+        assert!(default_action(KeyCode::Char('/'), KeyModifiers::NONE) == Action::Search.into());
+        assert!(default_action(KeyCode::Char('n'), KeyModifiers::NONE) == Action::NextMatch.into());
+        assert!(default_action(KeyCode::Char('N'), KeyModifiers::SHIFT) == Action::PreviousMatch.into());
+        assert!(default_action(KeyCode::Char(':'), KeyModifiers::SHIFT) == Action::GotoLine.into());
+        assert!(default_action(KeyCode::Char('Y'), KeyModifiers::SHIFT) == Action::CopyScreen.into());
+        assert!(default_action(KeyCode::Down, KeyModifiers::NONE) == Action::CursorDown.into());
+        assert!(default_action(KeyCode::Down, KeyModifiers::SHIFT) == Action::ScrollDown.into());
+        assert!(default_action(KeyCode::Home, KeyModifiers::NONE) == Action::ScrollOutputToLeft.into());
+        assert!(default_action(KeyCode::Esc, KeyModifiers::NONE) == Action::UnfocusOutput.into());
+    }
+
+    #[test]
+    fn describe_round_trips_through_parse() {
+        for spec in ["ctrl+r", "alt+shift+up", "f5", "space", "ctrl++", "pagedown"] {
+            assert_eq!(
+                KeyBinding::parse(spec).map(KeyBinding::describe),
+                spec.to_owned().into()
+            );
+        }
+    }
+}
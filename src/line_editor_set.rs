@@ -1,32 +1,56 @@
-use crate::{any::Any, cli_args::JqCliArgs};
-use crossterm::event::{KeyCode, KeyEvent};
-use ratatui::style::{Modifier, Style};
+use crate::{
+    any::Any, cli_args::JqCliArgs, cli_flags::CliFlags, completion::Completion, jq_error::JqError, jq_lint::JqLint,
+    key_index::KeyIndex, theme::Theme,
+};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers, MouseEvent, MouseEventKind};
+use ratatui::{
+    style::{Modifier, Style},
+    text::Line,
+};
+use std::borrow::Cow;
 use tui_textarea::{CursorMove, TextArea};
 
 pub struct LineEditor {
+    title: &'static str,
     text_area: TextArea<'static>,
+    focused: bool,
+    theme: Theme,
+    error: Option<String>,
+    warning: Option<String>,
 }
 
 impl LineEditor {
-    const STYLE_FOCUSED: Style = Style::new().add_modifier(Modifier::REVERSED);
     const STYLE_UNFOCUSED: Style = Style::new();
     const MAX_HISTORIES: usize = 2048;
+    const SCROLL_COLUMNS: usize = 1;
+    const FAST_SCROLL_COLUMNS: usize = 8;
 
-    pub fn new(title: &'static str, focused: bool, value: String) -> Self {
+    pub fn new(title: &'static str, focused: bool, value: String, theme: &Theme) -> Self {
         let mut text_area = value.some().convert::<TextArea>();
-        let cursor_style = if focused {
-            Self::STYLE_FOCUSED
-        } else {
-            Self::STYLE_UNFOCUSED
-        };
+        let cursor_style = if focused { theme.cursor } else { Self::STYLE_UNFOCUSED };
 
-        text_area.set_block(title.block());
+        text_area.set_block(title.block().border_style(theme.border));
         text_area.set_cursor_style(cursor_style);
         text_area.set_cursor_line_style(Self::STYLE_UNFOCUSED);
         text_area.set_max_histories(Self::MAX_HISTORIES);
         text_area.move_cursor(CursorMove::End);
+        text_area.set_search_style(Self::invalid_style(theme));
+
+        let error = None;
+        let warning = None;
+
+        Self {
+            title,
+            text_area,
+            focused,
+            theme: *theme,
+            error,
+            warning,
+        }
+    }
 
-        Self { text_area }
+    fn invalid_style(theme: &Theme) -> Style {
+        theme.error.add_modifier(Modifier::UNDERLINED)
     }
 
     pub fn text_area(&self) -> &TextArea<'static> {
@@ -34,48 +58,162 @@ impl LineEditor {
     }
 
     pub fn is_focused(&self) -> bool {
-        self.text_area.cursor_style() == Self::STYLE_FOCUSED
+        self.focused
     }
 
-    pub fn toggle_focus(&mut self) {
-        let cursor_style = if self.is_focused() {
-            Self::STYLE_UNFOCUSED
+    fn refresh_cursor_style(&mut self) {
+        let cursor_style = if self.focused {
+            self.theme.cursor
         } else {
-            Self::STYLE_FOCUSED
+            Self::STYLE_UNFOCUSED
         };
 
         self.text_area.set_cursor_style(cursor_style);
     }
 
-    pub fn content(&self) -> &str {
-        &self.text_area.lines()[0]
+    pub fn toggle_focus(&mut self) {
+        self.focused = !self.focused;
+        self.refresh_cursor_style();
+    }
+
+    // NOTE: e.g. when switching to a layout profile with its own theme entries
+    pub fn set_theme(&mut self, theme: &Theme) {
+        self.theme = *theme;
+        self.text_area.set_search_style(Self::invalid_style(theme));
+        self.refresh_cursor_style();
+        self.refresh_block();
+    }
+
+    // NOTE: the lines are joined with line breaks rather than spaces, since a `#` comment runs to the end of its line
+    pub fn content(&self) -> Cow<'_, str> {
+        match self.text_area.lines() {
+            [line] => line.as_str().into(),
+            lines => lines.join("\n").into(),
+        }
+    }
+
+    pub fn cursor(&self) -> (usize, usize) {
+        self.text_area.cursor()
+    }
+
+    pub fn cursor_line(&self) -> &str {
+        &self.text_area.lines()[self.text_area.cursor().0]
+    }
+
+    pub fn cursor_column(&self) -> usize {
+        self.text_area.cursor().1
+    }
+
+    // NOTE: the editors are usually a single line, so every wheel direction moves the cursor (and with it the viewport)
+    // horizontally
+    pub fn handle_mouse_event(&mut self, mouse_event: MouseEvent) {
+        let columns = if mouse_event
+            .modifiers
+            .intersects(KeyModifiers::ALT | KeyModifiers::CONTROL)
+        {
+            Self::FAST_SCROLL_COLUMNS
+        } else {
+            Self::SCROLL_COLUMNS
+        };
+        let (row, column) = self.text_area.cursor();
+        let column = match mouse_event.kind {
+            MouseEventKind::ScrollDown | MouseEventKind::ScrollRight => column.saturating_add(columns),
+            MouseEventKind::ScrollUp | MouseEventKind::ScrollLeft => column.saturating_sub(columns),
+            ignored_mouse_event_kind => return tracing::debug!(?ignored_mouse_event_kind),
+        };
+
+        self.text_area.move_cursor(CursorMove::Jump(row.cast(), column.cast()));
+    }
+
+    // NOTE: a warning is only shown while there is no error, which takes its place at the bottom of the block
+    fn refresh_block(&mut self) {
+        let mut block = self.title.block().border_style(self.theme.border);
+
+        if let Some(error) = &self.error {
+            block = block
+                .border_style(self.theme.error)
+                .title_bottom(Line::styled(error.clone(), self.theme.error));
+        } else if let Some(warning) = &self.warning {
+            block = block.title_bottom(Line::styled(warning.clone(), self.theme.warning));
+        }
+
+        self.text_area.set_block(block);
+    }
+
+    // NOTE: the search highlight of the text area is reused to underline the invalid parts of the content
+    pub fn set_error(&mut self, error: Option<(&str, &[String])>) {
+        let pattern = error.map_or_else(String::new, |(_message, invalid_parts)| {
+            invalid_parts
+                .iter()
+                .map(|invalid_part| regex::escape(invalid_part))
+                .collect::<Vec<_>>()
+                .join("|")
+        });
+
+        self.error = error.map(|(message, _invalid_parts)| message.to_owned());
+        self.refresh_block();
+        self.text_area.set_search_pattern(pattern).log_if_error();
+    }
+
+    pub fn set_warning(&mut self, warning: Option<String>) {
+        if self.warning == warning {
+            return;
+        }
+
+        self.warning = warning;
+        self.refresh_block();
     }
 }
 
 pub struct LineEditorSet {
     cli_flags: LineEditor,
     filter: LineEditor,
+    completion: Option<Completion>,
+    filter_expanded: bool,
 }
 
 impl LineEditorSet {
     const BLOCK_TITLE_FILTER: &'static str = "FILTER";
     const BLOCK_TITLE_CLI_FLAGS: &'static str = "CLI-FLAGS";
+    const FILTER_ERROR_MESSAGE: &'static str = "jq error raised here";
     const FOCUSED_FILTER: bool = true;
     const FOCUSED_CLI_FLAGS: bool = false;
 
-    pub fn new(jq_cli_args: &JqCliArgs, initial_filter: Option<String>) -> Self {
+    pub fn new(jq_cli_args: &JqCliArgs, initial_filter: Option<String>, theme: &Theme) -> Self {
         let cli_flags = LineEditor::new(
             Self::BLOCK_TITLE_CLI_FLAGS,
             Self::FOCUSED_CLI_FLAGS,
             jq_cli_args.to_string(),
+            theme,
         );
         let filter = LineEditor::new(
             Self::BLOCK_TITLE_FILTER,
             Self::FOCUSED_FILTER,
             initial_filter.unwrap_or_default(),
+            theme,
         );
 
-        Self { cli_flags, filter }
+        let completion = None;
+        let filter_expanded = false;
+        let mut line_editor_set = Self {
+            cli_flags,
+            filter,
+            completion,
+            filter_expanded,
+        };
+
+        line_editor_set.validate_cli_flags();
+
+        line_editor_set
+    }
+
+    fn validate_cli_flags(&mut self) {
+        match CliFlags::validate(&self.cli_flags.content()) {
+            Ok(()) => self.cli_flags.set_error(None),
+            Err(error) => self
+                .cli_flags
+                .set_error((error.message(), error.invalid_flags()).some()),
+        }
     }
 
     pub fn cli_flags(&self) -> &LineEditor {
@@ -86,11 +224,150 @@ impl LineEditorSet {
         &self.filter
     }
 
-    fn toggle_focus(&mut self) {
+    pub fn cli_flags_mut(&mut self) -> &mut LineEditor {
+        &mut self.cli_flags
+    }
+
+    pub fn filter_mut(&mut self) -> &mut LineEditor {
+        &mut self.filter
+    }
+
+    pub fn set_filter_error(&mut self, error: Option<&str>) {
+        let fragment = error.and_then(|error| JqError::fragment(error, &self.filter.content()));
+
+        match fragment {
+            Some(fragment) => self
+                .filter
+                .set_error((Self::FILTER_ERROR_MESSAGE, [fragment].as_slice()).some()),
+            None => self.filter.set_error(None),
+        }
+    }
+
+    // NOTE: the first warning is shown along with how many others there are
+    pub fn lint_filter(&mut self) {
+        let warnings = JqLint::warnings(&self.filter.content());
+        let warning = match warnings.as_slice() {
+            [] => None,
+            [warning] => warning.clone().some(),
+            [warning, others @ ..] => format!("{warning} (+{} more)", others.len()).some(),
+        };
+
+        self.filter.set_warning(warning);
+    }
+
+    pub fn append_cli_flag(&mut self, cli_flag: &str) {
+        let separator = if self.cli_flags.content().is_empty() || self.cli_flags.content().ends_with(' ') {
+            ""
+        } else {
+            " "
+        };
+
+        self.cli_flags.text_area.move_cursor(CursorMove::End);
+        self.cli_flags.text_area.insert_str(format!("{separator}{cli_flag}"));
+        self.validate_cli_flags();
+    }
+
+    // NOTE: replaces the cli-flags as an edit so that it can still be undone
+    pub fn set_cli_flags(&mut self, cli_flags: &str) {
+        self.cli_flags.text_area.select_all();
+        self.cli_flags.text_area.delete_char();
+        self.cli_flags.text_area.insert_str(cli_flags);
+        self.validate_cli_flags();
+    }
+
+    pub fn insert_into_filter(&mut self, text: &str) -> bool {
+        self.completion = None;
+        self.filter.text_area.insert_str(text)
+    }
+
+    // NOTE: replaces the filter as an edit so that it can still be undone
+    pub fn set_filter(&mut self, filter: &str) {
+        self.completion = None;
+
+        if !self.filter.is_focused() {
+            self.toggle_focus();
+        }
+
+        self.filter.text_area.select_all();
+        self.filter.text_area.delete_char();
+        self.filter.text_area.insert_str(filter);
+    }
+
+    pub fn is_filter_expanded(&self) -> bool {
+        self.filter_expanded
+    }
+
+    // NOTE: collapsing keeps the lines of the filter, the collapsed editor then only shows the one with the cursor
+    pub fn toggle_filter_expanded(&mut self) {
+        self.completion = None;
+        self.filter_expanded = !self.filter_expanded;
+    }
+
+    // NOTE: while editing the expanded filter, enter breaks the line and up/down move between lines instead of going
+    // through the history
+    pub fn is_editing_multi_line(&self) -> bool {
+        self.filter_expanded && self.filter.is_focused()
+    }
+
+    pub fn completion(&self) -> Option<&Completion> {
+        self.completion.as_ref()
+    }
+
+    // NOTE: clears the completion whenever the cursor may have moved away from the word it completes, e.g. on a click
+    pub fn dismiss_completion(&mut self) {
+        self.completion = None;
+    }
+
+    fn refresh_completion(&mut self, key_index: &KeyIndex) {
+        self.completion = if self.filter.is_focused() {
+            Completion::new(self.filter.cursor_line(), self.filter.cursor_column(), key_index)
+        } else {
+            None
+        };
+    }
+
+    // NOTE: returns whether the key event was consumed by the completion popup and if so whether the filter changed
+    fn handle_completion_key_event(&mut self, key_event: KeyEvent) -> Option<bool> {
+        let completion = self.completion.as_mut()?;
+        let changed = match key_event.code {
+            KeyCode::Up => completion.select_previous().with(false),
+            KeyCode::Down => completion.select_next().with(false),
+            KeyCode::Esc => self.dismiss_completion().with(false),
+            KeyCode::Tab => {
+                let suffix = completion.suffix().to_owned();
+
+                self.completion = None;
+                self.filter.text_area.insert_str(suffix)
+            }
+            _code => return None,
+        };
+
+        changed.some()
+    }
+
+    pub fn set_theme(&mut self, theme: &Theme) {
+        self.cli_flags.set_theme(theme);
+        self.filter.set_theme(theme);
+    }
+
+    pub fn toggle_focus(&mut self) {
+        self.completion = None;
         self.cli_flags.toggle_focus();
         self.filter.toggle_focus();
     }
 
+    pub fn focus_filter_at(&mut self, row: usize, column: usize) {
+        self.completion = None;
+
+        if !self.filter.is_focused() {
+            self.toggle_focus();
+        }
+
+        self.filter
+            .text_area
+            .move_cursor(CursorMove::Jump(row.cast(), column.cast()));
+    }
+
     fn active_mut(&mut self) -> &mut LineEditor {
         if self.filter.is_focused() {
             &mut self.filter
@@ -99,18 +376,41 @@ impl LineEditorSet {
         }
     }
 
-    pub fn handle_key_event(&mut self, key_event: KeyEvent) -> bool {
+    pub fn handle_key_event(&mut self, key_event: KeyEvent, key_index: &KeyIndex) -> bool {
         // NOTE: returns if the content changed:
         // - [https://docs.rs/tui-textarea/latest/tui_textarea/struct.TextArea.html#method.undo]
         // - [https://docs.rs/tui-textarea/latest/tui_textarea/struct.TextArea.html#method.redo]
         // - [https://docs.rs/tui-textarea/latest/tui_textarea/struct.TextArea.html#method.input]
-        match key_event {
-            KeyEvent { code: KeyCode::Tab, .. } => self.toggle_focus().with(false),
+        if let Some(changed) = self.handle_completion_key_event(key_event) {
+            return changed;
+        }
+
+        let changed = match key_event {
+            KeyEvent {
+                code: KeyCode::Up | KeyCode::Down,
+                ..
+            } if self.is_editing_multi_line() => self.filter.text_area.input(key_event),
             KeyEvent { code: KeyCode::Up, .. } => self.active_mut().text_area.undo(),
             KeyEvent {
                 code: KeyCode::Down, ..
             } => self.active_mut().text_area.redo(),
             _key_event => self.active_mut().text_area.input(key_event),
+        };
+
+        if changed && self.cli_flags.is_focused() {
+            self.validate_cli_flags();
         }
+
+        // NOTE: only typing opens the completion popup so that going through the history with up/down is never
+        // interrupted by it
+        match key_event.code {
+            KeyCode::Char(_chr) if !key_event.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.refresh_completion(key_index);
+            }
+            KeyCode::Backspace => self.refresh_completion(key_index),
+            _code => self.completion = None,
+        }
+
+        changed
     }
 }
@@ -1,13 +1,53 @@
+mod ansi;
 mod any;
 mod app;
 mod channel;
 mod cli_args;
+mod cli_flags;
+mod command_palette;
+mod completion;
+mod config;
+mod decoder;
+mod doctor;
+mod file_picker;
+mod filter_history;
+mod flag_variant;
+mod flash;
+mod global_search;
+mod goto_line;
+mod history_search;
 mod input;
+mod input_matches;
+mod input_set;
+#[cfg(feature = "jaq")]
+mod jaq_engine;
+mod jq_error;
+mod jq_lint;
+mod jq_outline;
 mod jq_process;
+mod jq_syntax;
+mod key_index;
+mod keymap;
 mod line_editor_set;
+mod navigation;
+mod output_status;
+mod path_prompt;
+mod paths;
+mod popup;
+mod precision;
+mod pretty;
+mod receive_rate;
 mod rect_set;
+mod scratch;
 mod scroll;
+mod scroll_memory;
+mod search_prompt;
+mod server;
+mod shutdown_signal;
 mod terminal;
+mod theme;
+mod time_window;
+mod transcript;
 
 use crate::cli_args::CliArgs;
 use anyhow::Error;
@@ -15,5 +55,5 @@ use clap::Parser;
 
 #[tokio::main]
 async fn main() -> Result<(), Error> {
-    CliArgs::parse().run().await
+    Box::pin(CliArgs::parse().run()).await
 }
@@ -0,0 +1,55 @@
+use crate::any::Any;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+#[derive(Clone, Copy)]
+pub enum Motion {
+    Left,
+    Down,
+    Up,
+    Right,
+    Top,
+    Bottom,
+    HalfPageDown,
+    HalfPageUp,
+    FarLeft,
+    FarRight,
+}
+
+pub enum NavigationEvent {
+    Motion(Motion),
+    Insert,
+}
+
+// NOTE: the normal mode entered with esc from the editors, where vim keys move through the focused pane instead of
+// being typed; `g` is remembered until the next key so that `gg` can be told apart from a lone `g`
+#[derive(Default)]
+pub struct Navigation {
+    pending_g: bool,
+}
+
+impl Navigation {
+    pub fn handle_key_event(&mut self, key_event: &KeyEvent) -> Option<NavigationEvent> {
+        let pending_g = self.pending_g.mem_take();
+        let motion = match (key_event.code, key_event.modifiers.contains(KeyModifiers::CONTROL)) {
+            (KeyCode::Char('i'), false) => return NavigationEvent::Insert.some(),
+            (KeyCode::Char('g'), false) if pending_g => Motion::Top,
+            (KeyCode::Char('g'), false) => {
+                self.pending_g = true;
+
+                return None;
+            }
+            (KeyCode::Char('h') | KeyCode::Left, false) => Motion::Left,
+            (KeyCode::Char('j') | KeyCode::Down, false) => Motion::Down,
+            (KeyCode::Char('k') | KeyCode::Up, false) => Motion::Up,
+            (KeyCode::Char('l') | KeyCode::Right, false) => Motion::Right,
+            (KeyCode::Char('G'), false) => Motion::Bottom,
+            (KeyCode::Char('0') | KeyCode::Home, false) => Motion::FarLeft,
+            (KeyCode::Char('$') | KeyCode::End, false) => Motion::FarRight,
+            (KeyCode::Char('d'), true) => Motion::HalfPageDown,
+            (KeyCode::Char('u'), true) => Motion::HalfPageUp,
+            _key => return None,
+        };
+
+        NavigationEvent::Motion(motion).some()
+    }
+}
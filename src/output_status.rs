@@ -0,0 +1,34 @@
+use crate::jq_process::JqOutput;
+
+// NOTE: the success or failure of the newest run whose result was applied, tagged with its run id so that results of
+// older runs can be told apart and ignored; a failed run without any output leaves the previous output visible, in
+// which case the status still describes the failed run alongside the stale marker in the output title
+#[derive(Default)]
+pub struct OutputStatus {
+    run_id: Option<u64>,
+    error: Option<String>,
+}
+
+impl OutputStatus {
+    pub fn error(&self) -> Option<&str> {
+        self.error.as_deref()
+    }
+
+    pub fn is_error(&self) -> bool {
+        self.error.is_some()
+    }
+
+    pub fn is_superseded_by(&self, jq_output: &JqOutput) -> bool {
+        self.run_id < jq_output.run_id()
+    }
+
+    // NOTE: returns whether the status turned from success into an error
+    pub fn set(&mut self, jq_output: &JqOutput) -> bool {
+        let was_error = self.is_error();
+
+        self.run_id = jq_output.run_id();
+        self.error = jq_output.error().map(str::to_owned);
+
+        !was_error && self.is_error()
+    }
+}
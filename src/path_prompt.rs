@@ -0,0 +1,149 @@
+use crate::{
+    any::Any,
+    file_picker::{FilePicker, FilePickerEvent},
+    theme::Theme,
+};
+use anyhow::Error;
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    layout::{Constraint, Flex, Layout, Rect},
+    text::Line,
+    widgets::Clear,
+    Frame,
+};
+use std::path::Path;
+use tui_textarea::{CursorMove, TextArea};
+
+pub enum PathPromptEvent {
+    Submitted(String),
+    Cancelled,
+}
+
+// NOTE: a single line overlay for entering a path, e.g. to open another input or to save the output, with a file
+// picker on tab for browsing to the path instead of typing it
+pub struct PathPrompt {
+    title: String,
+    text_area: TextArea<'static>,
+    error: Option<String>,
+    file_picker: Option<FilePicker>,
+}
+
+impl PathPrompt {
+    const CURRENT_DIRECTORY: &'static str = ".";
+    const HEIGHT: u16 = 3;
+    const PERCENTAGE: u16 = 60;
+    const TITLE_SUFFIX: &'static str = "(enter to confirm, tab to browse, esc to cancel)";
+
+    pub fn new(title: &str) -> Self {
+        let title = format!("{title} {}", Self::TITLE_SUFFIX);
+        let text_area = TextArea::default();
+        let error = None;
+        let file_picker = None;
+
+        Self {
+            title,
+            text_area,
+            error,
+            file_picker,
+        }
+    }
+
+    pub fn set_error(&mut self, error: &Error) {
+        self.error = error.to_string().some();
+    }
+
+    fn set_path(&mut self, path: &Path) {
+        self.text_area = TextArea::new(vec![path.display().to_string()]);
+        self.text_area.move_cursor(CursorMove::End);
+        self.error = None;
+    }
+
+    // NOTE: browsing starts from the directory typed so far if there is one
+    async fn open_file_picker(&mut self) -> Result<(), Error> {
+        let text = self.text_area.lines()[0].trim();
+        let directory = if Path::new(text).is_dir() {
+            text
+        } else {
+            Self::CURRENT_DIRECTORY
+        };
+
+        self.file_picker = FilePicker::new(Path::new(directory)).await?.some();
+
+        ().ok()
+    }
+
+    async fn handle_file_picker_key_event(&mut self, key_event: KeyEvent) -> Result<(), Error> {
+        let Some(file_picker) = &mut self.file_picker else {
+            return ().ok();
+        };
+
+        match file_picker.handle_key_event(key_event).await? {
+            Some(FilePickerEvent::Picked(path)) => {
+                self.set_path(&path);
+                self.file_picker = None;
+            }
+            Some(FilePickerEvent::Cancelled) => self.file_picker = None,
+            None => {}
+        }
+
+        ().ok()
+    }
+
+    pub async fn handle_key_event(&mut self, key_event: KeyEvent) -> Option<PathPromptEvent> {
+        if self.file_picker.is_some() {
+            if let Err(error) = self.handle_file_picker_key_event(key_event).await {
+                self.set_error(&error);
+            }
+
+            return None;
+        }
+
+        match key_event.code {
+            KeyCode::Esc => PathPromptEvent::Cancelled.some(),
+            KeyCode::Enter => PathPromptEvent::Submitted(self.text_area.lines()[0].clone()).some(),
+            KeyCode::Tab => {
+                if let Err(error) = self.open_file_picker().await {
+                    self.set_error(&error);
+                }
+
+                None
+            }
+            _code => {
+                if self.text_area.input(key_event) {
+                    self.error = None;
+                }
+
+                None
+            }
+        }
+    }
+
+    fn rect(area: Rect) -> Rect {
+        let [rect] = Layout::vertical([Constraint::Length(Self::HEIGHT)])
+            .flex(Flex::Center)
+            .areas(area);
+        let [rect] = Layout::horizontal([Constraint::Percentage(Self::PERCENTAGE)])
+            .flex(Flex::Center)
+            .areas(rect);
+
+        rect
+    }
+
+    pub fn render(&mut self, frame: &mut Frame, theme: &Theme) {
+        if let Some(file_picker) = &mut self.file_picker {
+            return file_picker.render(frame);
+        }
+
+        let rect = Self::rect(frame.area());
+        let mut block = self.title.clone().block();
+
+        if let Some(error) = &self.error {
+            block = block.title_bottom(Line::styled(error.clone(), theme.error));
+        }
+
+        self.text_area.set_block(block);
+
+        Clear.render_to(frame, rect);
+        (&self.text_area).render_to(frame, rect);
+    }
+}
@@ -0,0 +1,34 @@
+use crate::any::Any;
+use std::path::PathBuf;
+
+// NOTE: follows the xdg base directory spec, where an unset or empty base directory variable falls back to its default
+// under $HOME
+pub struct Paths;
+
+impl Paths {
+    const DIRECTORY_NAME: &'static str = "rq";
+    const HOME_VAR: &'static str = "HOME";
+    const CONFIG_HOME_VAR: &'static str = "XDG_CONFIG_HOME";
+    const DEFAULT_CONFIG_HOME: &'static str = ".config";
+    const STATE_HOME_VAR: &'static str = "XDG_STATE_HOME";
+    const DEFAULT_STATE_HOME: &'static str = ".local/state";
+
+    fn base_dir(var: &str, default_dir: &str) -> Option<PathBuf> {
+        let base_dir = match std::env::var_os(var).filter(|base_dir| !base_dir.is_empty()) {
+            Some(base_dir) => PathBuf::from(base_dir),
+            None => PathBuf::from(std::env::var_os(Self::HOME_VAR)?).join(default_dir),
+        };
+
+        base_dir.join(Self::DIRECTORY_NAME).some()
+    }
+
+    // NOTE: $XDG_CONFIG_HOME/rq with a fallback to ~/.config/rq
+    pub fn config_dir() -> Option<PathBuf> {
+        Self::base_dir(Self::CONFIG_HOME_VAR, Self::DEFAULT_CONFIG_HOME)
+    }
+
+    // NOTE: $XDG_STATE_HOME/rq with a fallback to ~/.local/state/rq
+    pub fn state_dir() -> Option<PathBuf> {
+        Self::base_dir(Self::STATE_HOME_VAR, Self::DEFAULT_STATE_HOME)
+    }
+}
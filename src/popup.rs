@@ -0,0 +1,43 @@
+use crate::any::Any;
+use ratatui::{
+    layout::{Constraint, Flex, Layout, Rect},
+    widgets::{Clear, Wrap},
+    Frame,
+};
+
+pub struct Popup {
+    title: String,
+    content: String,
+}
+
+impl Popup {
+    const PERCENTAGE: u16 = 60;
+    const WRAP: Wrap = Wrap { trim: false };
+
+    pub fn new(title: String, content: String) -> Self {
+        Self { title, content }
+    }
+
+    fn rect(area: Rect) -> Rect {
+        let [rect] = Layout::vertical([Constraint::Percentage(Self::PERCENTAGE)])
+            .flex(Flex::Center)
+            .areas(area);
+        let [rect] = Layout::horizontal([Constraint::Percentage(Self::PERCENTAGE)])
+            .flex(Flex::Center)
+            .areas(rect);
+
+        rect
+    }
+
+    pub fn render(&self, frame: &mut Frame) {
+        let rect = Self::rect(frame.area());
+
+        Clear.render_to(frame, rect);
+        self.content
+            .as_str()
+            .paragraph()
+            .wrap(Self::WRAP)
+            .block(self.title.as_str().block())
+            .render_to(frame, rect);
+    }
+}
@@ -0,0 +1,62 @@
+pub struct Precision;
+
+impl Precision {
+    // NOTE: 2^53, the largest integer up to which every integer can be represented exactly as an f64
+    const MAX_SAFE_INTEGER: &'static str = "9007199254740992";
+    const STRING_DELIMITER: char = '"';
+    const ESCAPE: char = '\\';
+
+    fn is_float_char(chr: char) -> bool {
+        matches!(chr, '.' | 'e' | 'E' | '+' | '-')
+    }
+
+    fn is_unsafe_integer(digits: &str) -> bool {
+        let digits = digits.trim_start_matches('0');
+
+        match digits.len().cmp(&Self::MAX_SAFE_INTEGER.len()) {
+            std::cmp::Ordering::Less => false,
+            std::cmp::Ordering::Equal => Self::MAX_SAFE_INTEGER < digits,
+            std::cmp::Ordering::Greater => true,
+        }
+    }
+
+    // NOTE: json strings can't span lines, so string literals can be skipped by scanning each line on its own;
+    // numbers with a fraction or exponent are floats to begin with and are not considered
+    pub fn has_unsafe_integer(line: &str) -> bool {
+        let mut in_string = false;
+        let mut escaped = false;
+        let mut char_indices = line.char_indices().peekable();
+
+        while let Some((begin, chr)) = char_indices.next() {
+            if in_string {
+                match chr {
+                    _ if escaped => escaped = false,
+                    Self::ESCAPE => escaped = true,
+                    Self::STRING_DELIMITER => in_string = false,
+                    _chr => {}
+                }
+            } else if chr == Self::STRING_DELIMITER {
+                in_string = true;
+            } else if chr.is_ascii_digit() {
+                let mut end = begin + chr.len_utf8();
+
+                while let Some((idx, chr)) = char_indices.next_if(|(_idx, chr)| chr.is_ascii_digit()) {
+                    end = idx + chr.len_utf8();
+                }
+
+                let is_float = char_indices.peek().is_some_and(|(_idx, chr)| Self::is_float_char(*chr));
+
+                if is_float {
+                    while char_indices
+                        .next_if(|(_idx, chr)| chr.is_ascii_digit() || Self::is_float_char(*chr))
+                        .is_some()
+                    {}
+                } else if Self::is_unsafe_integer(&line[begin..end]) {
+                    return true;
+                }
+            }
+        }
+
+        false
+    }
+}
@@ -0,0 +1,152 @@
+use crate::any::Any;
+
+// NOTE: re-indents json at the token level rather than parsing it so that numbers, key order, and invalid fragments
+// are shown exactly as they appear in the input; state is kept across lines so that documents spanning several lines
+// are handled as well; several documents on one line, e.g. `1 2` or `}{`, are put on lines of their own
+#[allow(clippy::struct_excessive_bools)]
+pub struct PrettyPrinter {
+    depth: usize,
+    in_string: bool,
+    escaped: bool,
+    pending_open: bool,
+    value_ended: bool,
+    line: String,
+}
+
+impl PrettyPrinter {
+    const INDENT: &'static str = "  ";
+
+    pub fn new() -> Self {
+        Self {
+            depth: 0,
+            in_string: false,
+            escaped: false,
+            pending_open: false,
+            value_ended: false,
+            line: String::new(),
+        }
+    }
+
+    fn newline(&mut self, lines: &mut Vec<String>) {
+        self.line.mem_take().push_to(lines);
+        self.line.push_str(&Self::INDENT.repeat(self.depth));
+    }
+
+    fn push_string_char(&mut self, chr: char) {
+        self.line.push(chr);
+
+        if self.escaped {
+            self.escaped = false;
+        } else if chr == '\\' {
+            self.escaped = true;
+        } else if chr == '"' {
+            self.in_string = false;
+            self.value_ended = self.depth == 0;
+        }
+    }
+
+    fn push_char(&mut self, chr: char, lines: &mut Vec<String>) {
+        if self.in_string {
+            return self.push_string_char(chr);
+        }
+
+        // NOTE: whitespace at the top level ends a scalar, e.g. the `1` of `1 2`
+        if chr.is_whitespace() {
+            self.value_ended |= self.depth == 0 && !self.line.is_empty();
+
+            return;
+        }
+
+        if self.value_ended.mem_take() {
+            self.newline(lines);
+        }
+
+        // NOTE: keep empty objects and arrays on one line
+        if self.pending_open.mem_take() {
+            if matches!(chr, '}' | ']') {
+                self.depth = self.depth.saturating_sub(1);
+                self.value_ended = self.depth == 0;
+
+                return self.line.push(chr);
+            }
+
+            self.newline(lines);
+        }
+
+        match chr {
+            '{' | '[' => {
+                self.line.push(chr);
+                self.depth += 1;
+                self.pending_open = true;
+            }
+            '}' | ']' => {
+                self.depth = self.depth.saturating_sub(1);
+                self.newline(lines);
+                self.line.push(chr);
+                self.value_ended = self.depth == 0;
+            }
+            ',' => {
+                self.line.push(chr);
+                self.newline(lines);
+            }
+            ':' => self.line.push_str(": "),
+            '"' => {
+                self.line.push(chr);
+                self.in_string = true;
+            }
+            chr => self.line.push(chr),
+        }
+    }
+
+    pub fn push_line(&mut self, line: &str) -> Vec<String> {
+        let mut lines = Vec::new();
+
+        for chr in line.chars() {
+            self.push_char(chr, &mut lines);
+        }
+
+        // NOTE: a line that ends at the top level completes a document, e.g. every line of ndjson
+        if self.depth == 0 && !self.in_string && !self.pending_open && !self.line.is_empty() {
+            self.line.mem_take().push_to(&mut lines);
+            self.value_ended = false;
+        }
+
+        lines
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PrettyPrinter;
+
+    fn pretty(lines: &[&str]) -> Vec<String> {
+        let mut printer = PrettyPrinter::new();
+
+        lines.iter().flat_map(|line| printer.push_line(line)).collect()
+    }
+
+    #[test]
+    fn push_line_indents_nested_values() {
+        assert_eq!(
+            pretty(&[r#"{"a":[1,2],"b":{}}"#]),
+            ["{", r#"  "a": ["#, "    1,", "    2", "  ],", r#"  "b": {}"#, "}"]
+        );
+    }
+
+    #[test]
+    fn push_line_keeps_documents_spanning_lines_together() {
+        assert_eq!(pretty(&["[1,", "2]"]), ["[", "  1,", "  2", "]"]);
+    }
+
+    #[test]
+    fn push_line_splits_documents_on_one_line() {
+        assert_eq!(pretty(&["1 2"]), ["1", "2"]);
+        assert_eq!(pretty(&["true false"]), ["true", "false"]);
+        assert_eq!(pretty(&[r#""a" "b""#]), [r#""a""#, r#""b""#]);
+        assert_eq!(
+            pretty(&[r#"{"a":1}{"b":2}"#]),
+            ["{", r#"  "a": 1"#, "}", "{", r#"  "b": 2"#, "}"]
+        );
+        assert_eq!(pretty(&["[] {}"]), ["[]", "{}"]);
+    }
+}
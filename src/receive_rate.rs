@@ -0,0 +1,57 @@
+use crate::any::Any;
+use std::{
+    collections::VecDeque,
+    time::{Duration, Instant},
+};
+
+// NOTE: tracks how much input arrived over a sliding window so that a producer that is still sending data can be told
+// apart from one that has stalled
+pub struct ReceiveRate {
+    samples: VecDeque<(Instant, usize)>,
+}
+
+impl ReceiveRate {
+    const STALLED_DURATION: Duration = Duration::from_secs(2);
+    const WINDOW: Duration = Duration::from_secs(5);
+
+    pub fn new() -> Self {
+        let samples = VecDeque::new();
+
+        Self { samples }
+    }
+
+    pub fn record(&mut self, bytes: usize) {
+        let now = Instant::now();
+
+        while self
+            .samples
+            .front()
+            .is_some_and(|(instant, _bytes)| Self::WINDOW < now.duration_since(*instant))
+        {
+            self.samples.pop_front();
+        }
+
+        self.samples.push_back((now, bytes));
+    }
+
+    pub fn summary(&self) -> String {
+        let Some((last_instant, _bytes)) = self.samples.back() else {
+            return "waiting for data".to_owned();
+        };
+        let elapsed = last_instant.elapsed();
+
+        if Self::STALLED_DURATION <= elapsed {
+            return format!("stalled for {}s", elapsed.as_secs());
+        }
+
+        let bytes = self
+            .samples
+            .iter()
+            .filter(|(instant, _bytes)| instant.elapsed() <= Self::WINDOW)
+            .map(|(_instant, bytes)| bytes)
+            .sum::<usize>();
+        let bytes_per_sec = bytes / Self::WINDOW.as_secs().cast::<usize>();
+
+        format!("receiving {}/s", bytes_per_sec.human_bytes())
+    }
+}
@@ -1,29 +1,91 @@
+use crate::cli_args::LayoutProfile;
 use ratatui::layout::{Constraint, Layout, Rect};
 
 #[derive(Debug)]
 pub struct RectSet {
+    area: Rect,
+    layout_profile: LayoutProfile,
+    filter_expanded: bool,
     pub input: Rect,
     pub output: Rect,
     pub cli_flags: Rect,
     pub filter: Rect,
+    pub status: Rect,
 }
 
 impl RectSet {
-    pub fn new(rect: Rect) -> Self {
-        let layout = Layout::vertical([Constraint::Fill(1), Constraint::Length(3), Constraint::Length(3)]);
-        let [top_rect, cli_flags, filter] = layout.areas(rect);
-        let layout = Layout::horizontal([Constraint::Ratio(1, 2); 2]);
+    const LINE_EDITOR_HEIGHT: u16 = 3;
+    const EXPANDED_FILTER_HEIGHT: u16 = 12;
+
+    // NOTE: hidden panes get an empty rect, which renders nothing and contains no position
+    pub fn new(rect: Rect, layout_profile: LayoutProfile, filter_expanded: bool) -> Self {
+        if let LayoutProfile::Review = layout_profile {
+            let layout = Layout::vertical([Constraint::Fill(1), Constraint::Length(1)]);
+            let [output, status] = layout.areas(rect);
+
+            return Self {
+                area: rect,
+                layout_profile,
+                filter_expanded,
+                input: Rect::ZERO,
+                output,
+                cli_flags: Rect::ZERO,
+                filter: Rect::ZERO,
+                status,
+            };
+        }
+
+        let filter_height = if filter_expanded {
+            Self::EXPANDED_FILTER_HEIGHT
+        } else {
+            Self::LINE_EDITOR_HEIGHT
+        };
+        let layout = Layout::vertical([
+            Constraint::Fill(1),
+            Constraint::Length(Self::LINE_EDITOR_HEIGHT),
+            Constraint::Length(filter_height),
+            Constraint::Length(1),
+        ]);
+        let [top_rect, cli_flags, filter, status] = layout.areas(rect);
+        let layout = if let LayoutProfile::Stacked = layout_profile {
+            Layout::vertical([Constraint::Ratio(1, 2); 2])
+        } else {
+            Layout::horizontal([Constraint::Ratio(1, 2); 2])
+        };
         let [input, output] = layout.areas(top_rect);
 
         Self {
+            area: rect,
+            layout_profile,
+            filter_expanded,
             input,
             output,
             cli_flags,
             filter,
+            status,
         }
     }
 
-    pub fn empty() -> Self {
-        Self::new(Rect::ZERO)
+    pub fn empty(layout_profile: LayoutProfile) -> Self {
+        Self::new(Rect::ZERO, layout_profile, false)
+    }
+
+    pub fn shows_editors(&self) -> bool {
+        !matches!(self.layout_profile, LayoutProfile::Review)
+    }
+
+    pub fn set_layout_profile(&mut self, layout_profile: LayoutProfile) {
+        *self = Self::new(self.area, layout_profile, self.filter_expanded);
+    }
+
+    pub fn set_filter_expanded(&mut self, filter_expanded: bool) {
+        *self = Self::new(self.area, self.layout_profile, filter_expanded);
+    }
+
+    // NOTE: only recompute the layout when the area it was computed for changes
+    pub fn resize(&mut self, area: Rect) {
+        if self.area != area {
+            *self = Self::new(area, self.layout_profile, self.filter_expanded);
+        }
     }
 }
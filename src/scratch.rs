@@ -0,0 +1,97 @@
+use crate::{any::Any, jq_process::JsonArg, theme::Theme};
+use anyhow::Error;
+use crossterm::event::KeyEvent;
+use ratatui::{
+    layout::{Constraint, Flex, Layout, Rect},
+    text::Line,
+    widgets::Clear,
+    Frame,
+};
+use serde_json::Value;
+use tui_textarea::TextArea;
+
+// NOTE: an overlay for setting session-scoped variables of the form `name=json`, which are passed to every jq run via
+// `--argjson` so that values discovered along the way can be referenced as `$name` in later filters
+pub struct ScratchEditor {
+    text_area: TextArea<'static>,
+    error: Option<String>,
+}
+
+impl ScratchEditor {
+    const HEIGHT: u16 = 3;
+    const PERCENTAGE: u16 = 60;
+    const TITLE: &'static str = "SET VARIABLE name=json (enter to set, esc to cancel)";
+
+    pub fn new() -> Self {
+        let text_area = TextArea::default();
+        let error = None;
+
+        Self { text_area, error }
+    }
+
+    pub fn handle_key_event(&mut self, key_event: KeyEvent) {
+        if self.text_area.input(key_event) {
+            self.error = None;
+        }
+    }
+
+    pub fn set_error(&mut self, error: &Error) {
+        self.error = error.to_string().some();
+    }
+
+    fn is_name(name: &str) -> bool {
+        name.starts_with(|chr: char| chr.is_alphabetic() || chr == '_')
+            && name.chars().all(|chr| chr.is_alphanumeric() || chr == '_')
+    }
+
+    pub fn json_arg(&self) -> Result<JsonArg, Error> {
+        let (name, json) = self.text_area.lines()[0]
+            .split_once('=')
+            .ok_or_error::<(&str, &str)>("expected a variable of the form name=json")?;
+        let name = name.trim().trim_start_matches('$');
+        let json = json.trim();
+
+        anyhow::ensure!(Self::is_name(name), "{name:?} is not a valid variable name");
+
+        serde_json::from_str::<Value>(json)?;
+
+        JsonArg {
+            name: name.to_owned(),
+            json: json.to_owned(),
+        }
+        .ok()
+    }
+
+    fn rect(area: Rect) -> Rect {
+        let [rect] = Layout::vertical([Constraint::Length(Self::HEIGHT)])
+            .flex(Flex::Center)
+            .areas(area);
+        let [rect] = Layout::horizontal([Constraint::Percentage(Self::PERCENTAGE)])
+            .flex(Flex::Center)
+            .areas(rect);
+
+        rect
+    }
+
+    pub fn render(&mut self, frame: &mut Frame, theme: &Theme, json_args: &[JsonArg]) {
+        let rect = Self::rect(frame.area());
+        let mut block = Self::TITLE.block();
+
+        if let Some(error) = &self.error {
+            block = block.title_bottom(Line::styled(error.clone(), theme.error));
+        } else if !json_args.is_empty() {
+            let names = json_args
+                .iter()
+                .map(|json_arg| format!("${}", json_arg.name))
+                .collect::<Vec<_>>()
+                .join(", ");
+
+            block = block.title_bottom(format!("set: {names}"));
+        }
+
+        self.text_area.set_block(block);
+
+        Clear.render_to(frame, rect);
+        (&self.text_area).render_to(frame, rect);
+    }
+}
@@ -1,4 +1,4 @@
-use crate::any::Any;
+use crate::{ansi::Ansi, any::Any, navigation::Motion, theme::Theme};
 use crossterm::event::{KeyModifiers, MouseEvent, MouseEventKind};
 use ratatui::{
     layout::{Position, Rect, Size},
@@ -6,7 +6,8 @@ use ratatui::{
     text::Line,
     Frame,
 };
-use std::ops::Range;
+use std::{collections::HashSet, num::NonZeroUsize, ops::Range};
+use unicode_segmentation::UnicodeSegmentation;
 
 pub struct ScrollBar {
     bar: Rect,
@@ -14,12 +15,10 @@ pub struct ScrollBar {
 }
 
 impl ScrollBar {
-    const STYLE: Style = Style::new().add_modifier(Modifier::REVERSED);
-
-    fn render(&self, frame: &mut Frame) {
+    fn render(&self, frame: &mut Frame, style: Style) {
         for position in self.thumb.positions() {
             if let Some(cell) = frame.buffer_mut().cell_mut(position) {
-                cell.set_style(Self::STYLE);
+                cell.set_style(style);
             }
         }
     }
@@ -56,18 +55,32 @@ impl Transpose for ScrollBar {
     }
 }
 
+// NOTE: the number of lines/columns scrolled per mouse-wheel step without a modifier and with alt, while ctrl always
+// scrolls a full page
+#[derive(Clone, Copy)]
+pub struct ScrollSpeed {
+    pub normal: u16,
+    pub large: u16,
+}
+
+#[derive(Clone)]
 pub struct ScrollView {
     content: String,
     line_ranges: Vec<Range<usize>>,
     offset: Position,
     page_size: Size,
     content_width: u16,
+    cursor: Option<usize>,
+    gutter_width: u16,
+    search: Option<String>,
+    matches: Vec<Range<usize>>,
+    current_match: Option<usize>,
 }
 
 impl ScrollView {
     const LINE_SEPARATOR: &'static str = "\n";
-    const LARGE_SCROLL_COUNT: u16 = 5;
-    const NORMAL_SCROLL_COUNT: u16 = 1;
+    const RECORD_SEPARATOR: char = '\u{1e}';
+    const RECORD_SEPARATOR_SYMBOL: &'static str = "␞";
 
     pub fn new() -> Self {
         Self {
@@ -76,6 +89,11 @@ impl ScrollView {
             offset: Position::ORIGIN,
             page_size: Size::ZERO,
             content_width: 0,
+            cursor: None,
+            gutter_width: 0,
+            search: None,
+            matches: Vec::new(),
+            current_match: None,
         }
     }
 
@@ -87,6 +105,17 @@ impl ScrollView {
         (self.content_width, self.content_height()).into()
     }
 
+    // NOTE: RFC 7464 json text sequences (e.g. from `jq --seq`) begin every record with an RS control character, which
+    // is rendered as a visible symbol but kept as is in the content so that it is preserved when written out
+    fn display_line(line: &str) -> Line<'_> {
+        if line.contains(Self::RECORD_SEPARATOR) {
+            line.replace(Self::RECORD_SEPARATOR, Self::RECORD_SEPARATOR_SYMBOL)
+                .into()
+        } else {
+            line.into()
+        }
+    }
+
     fn render_content(&self, frame: &mut Frame, rect: Rect) {
         // NOTE:
         // - strings can only be indexed by Range<usize> not &Range<usize>
@@ -100,9 +129,13 @@ impl ScrollView {
             .take(rect.height.cast())
             .cloned()
             .map(|line_range| {
-                self.content[line_range]
-                    .substring(substring_range.clone())
-                    .convert::<Line>()
+                let line = &self.content[line_range];
+
+                if Ansi::contains(line) {
+                    Ansi::line(line, substring_range.clone())
+                } else {
+                    Self::display_line(line.substring(substring_range.clone()))
+                }
             })
             .collect::<Vec<_>>()
             .paragraph();
@@ -110,6 +143,101 @@ impl ScrollView {
         paragraph.render_to(frame, rect);
     }
 
+    // NOTE: the style is applied to the whole row rather than to the rendered line so that the highlight spans the full
+    // width of the pane
+    fn render_cursor(&self, frame: &mut Frame, rect: Rect, style: Style) {
+        let Some(cursor) = self.cursor.filter(|cursor| *cursor < self.len_lines()) else {
+            return;
+        };
+        let Some(row) = cursor.checked_sub(self.offset.y.cast()) else {
+            return;
+        };
+
+        if row < rect.height.cast() {
+            let cursor_rect = Rect::new(rect.x, rect.y.saturating_add(row.cast()), rect.width, 1);
+
+            frame.buffer_mut().set_style(cursor_rect, style);
+        }
+    }
+
+    // NOTE: one column per digit of the last line number plus a separating space
+    fn gutter_width(&self, line_numbers: bool) -> u16 {
+        if !line_numbers {
+            return 0;
+        }
+
+        self.len_lines().max(1).to_string().len().cast::<u16>() + 1
+    }
+
+    fn render_gutter(&self, frame: &mut Frame, rect: Rect, style: Style) {
+        let width = rect.width.saturating_sub(1).cast::<usize>();
+        let begin = self.offset.y.cast::<usize>();
+        let end = begin.saturating_add(rect.height.cast()).min(self.len_lines());
+        let paragraph = (begin..end)
+            .map(|index| Line::styled(format!("{:>width$}", index + 1), style))
+            .collect::<Vec<_>>()
+            .paragraph();
+
+        paragraph.render_to(frame, rect);
+    }
+
+    // NOTE: matches on lines with ansi escapes aren't highlighted since their byte offsets don't map onto the
+    // rendered columns
+    fn render_matches(&self, frame: &mut Frame, rect: Rect, style: Style) {
+        let begin = self.offset.y.cast::<usize>();
+        let end = begin.saturating_add(rect.height.cast()).min(self.len_lines());
+        let first_match = self.first_match_from(begin);
+
+        for (index, match_range) in self.matches.iter().enumerate().skip(first_match) {
+            let line_index = self.line_index(match_range.start);
+
+            if end <= line_index {
+                break;
+            }
+
+            let line_range = &self.line_ranges[line_index];
+            let line = &self.content[line_range.clone()];
+
+            if Ansi::contains(line) {
+                continue;
+            }
+
+            let column = line[..match_range.start - line_range.start].len_graphemes();
+            let width = self.content[match_range.clone()].len_graphemes();
+            let style = if self.current_match == index.some() {
+                style.add_modifier(Modifier::REVERSED)
+            } else {
+                style
+            };
+            let y = rect.y.saturating_add((line_index - begin).cast());
+
+            for x in column..column + width {
+                let Some(x) = x.checked_sub(self.offset.x.cast()).filter(|x| *x < rect.width.cast()) else {
+                    continue;
+                };
+
+                if let Some(cell) = frame
+                    .buffer_mut()
+                    .cell_mut(Position::new(rect.x.saturating_add(x.cast()), y))
+                {
+                    cell.set_style(style);
+                }
+            }
+        }
+    }
+
+    pub fn highlight_lines(&self, frame: &mut Frame, rect: Rect, lines: &HashSet<usize>, style: Style) {
+        let offset_y = self.offset.y.cast::<usize>();
+
+        for row in 0..rect.height.min(self.content_height().saturating_sub(self.offset.y)) {
+            if lines.contains(&(offset_y + row.cast::<usize>())) {
+                let line_rect = Rect::new(rect.x, rect.y.saturating_add(row), rect.width, 1);
+
+                frame.buffer_mut().set_style(line_rect, style);
+            }
+        }
+    }
+
     fn vertical_scroll_bar(rect: Rect, offset: Position, content_size: Size) -> ScrollBar {
         let scroll_thumb_height = rect
             .height
@@ -125,27 +253,27 @@ impl ScrollView {
         ScrollBar { bar, thumb }
     }
 
-    fn render_scroll_bars(&self, frame: &mut Frame, rect: Rect) {
+    fn render_scroll_bars(&self, frame: &mut Frame, rect: Rect, style: Style) {
         let content_size = self.content_size();
 
         if rect.height < content_size.height {
-            Self::vertical_scroll_bar(rect, self.offset, content_size).render(frame);
+            Self::vertical_scroll_bar(rect, self.offset, content_size).render(frame, style);
         }
 
         if rect.width < content_size.width {
             Self::vertical_scroll_bar(rect.transpose(), self.offset.transpose(), content_size.transpose())
                 .transpose()
-                .render(frame);
+                .render(frame, style);
         }
     }
 
-    fn scroll_count(key_modifiers: KeyModifiers, page_size: u16) -> u16 {
+    fn scroll_count(key_modifiers: KeyModifiers, page_size: u16, scroll_speed: ScrollSpeed) -> u16 {
         if key_modifiers.intersects(KeyModifiers::CONTROL) {
             page_size
         } else if key_modifiers.intersects(KeyModifiers::ALT) {
-            Self::LARGE_SCROLL_COUNT
+            scroll_speed.large
         } else {
-            Self::NORMAL_SCROLL_COUNT
+            scroll_speed.normal
         }
     }
 
@@ -157,32 +285,32 @@ impl ScrollView {
         self.content_width.saturating_sub(self.page_size.width)
     }
 
-    fn scroll_up(&mut self, key_modifiers: KeyModifiers) {
-        let scroll_count = Self::scroll_count(key_modifiers, self.page_size.height);
+    pub fn scroll_up(&mut self, key_modifiers: KeyModifiers, scroll_speed: ScrollSpeed) {
+        let scroll_count = Self::scroll_count(key_modifiers, self.page_size.height, scroll_speed);
 
         self.offset
             .y
             .saturating_sub_in_place_with_max(scroll_count, self.max_offset_y());
     }
 
-    fn scroll_down(&mut self, key_modifiers: KeyModifiers) {
-        let scroll_count = Self::scroll_count(key_modifiers, self.page_size.height);
+    pub fn scroll_down(&mut self, key_modifiers: KeyModifiers, scroll_speed: ScrollSpeed) {
+        let scroll_count = Self::scroll_count(key_modifiers, self.page_size.height, scroll_speed);
 
         self.offset
             .y
             .saturating_add_in_place_with_max(scroll_count, self.max_offset_y());
     }
 
-    fn scroll_left(&mut self, key_modifiers: KeyModifiers) {
-        let scroll_count = Self::scroll_count(key_modifiers, self.page_size.width);
+    pub fn scroll_left(&mut self, key_modifiers: KeyModifiers, scroll_speed: ScrollSpeed) {
+        let scroll_count = Self::scroll_count(key_modifiers, self.page_size.width, scroll_speed);
 
         self.offset
             .x
             .saturating_sub_in_place_with_max(scroll_count, self.max_offset_x());
     }
 
-    fn scroll_right(&mut self, key_modifiers: KeyModifiers) {
-        let scroll_count = Self::scroll_count(key_modifiers, self.page_size.width);
+    pub fn scroll_right(&mut self, key_modifiers: KeyModifiers, scroll_speed: ScrollSpeed) {
+        let scroll_count = Self::scroll_count(key_modifiers, self.page_size.width, scroll_speed);
 
         self.offset
             .x
@@ -193,6 +321,257 @@ impl ScrollView {
         &self.content
     }
 
+    pub fn len_lines(&self) -> usize {
+        self.line_ranges.len()
+    }
+
+    pub fn line(&self, index: usize) -> Option<&str> {
+        let line_range = self.line_ranges.get(index)?;
+
+        Some(&self.content[line_range.clone()])
+    }
+
+    fn is_word_char(chr: char) -> bool {
+        chr.is_alphanumeric() || matches!(chr, '_' | '-')
+    }
+
+    // NOTE: position is relative to the top-left corner of the visible page and its x coordinate counts graphemes, like
+    // the rendered content
+    pub fn word_at(&self, position: Position) -> Option<&str> {
+        let line = self.line(self.offset.y.saturating_add(position.y).cast())?;
+        let column = self
+            .offset
+            .x
+            .saturating_add(position.x.checked_sub(self.gutter_width)?)
+            .cast();
+        let (index, _grapheme) = line.grapheme_indices(true).nth(column)?;
+
+        line[index..].chars().next().filter(|chr| Self::is_word_char(*chr))?;
+
+        let begin = line[..index]
+            .char_indices()
+            .rev()
+            .find(|(_idx, chr)| !Self::is_word_char(*chr))
+            .map_or(0, |(idx, chr)| idx + chr.len_utf8());
+        let end = line[index..]
+            .find(|chr| !Self::is_word_char(chr))
+            .map_or(line.len(), |idx| index + idx);
+
+        Some(&line[begin..end])
+    }
+
+    pub fn cursor_line(&self) -> Option<&str> {
+        self.line(self.cursor?)
+    }
+
+    pub fn visible_lines(&self) -> Vec<&str> {
+        let begin = self.offset.y.cast::<usize>();
+        let end = begin.saturating_add(self.page_size.height.cast()).min(self.len_lines());
+
+        (begin..end).filter_map(|index| self.line(index)).collect()
+    }
+
+    pub fn cursor(&self) -> Option<usize> {
+        self.cursor
+    }
+
+    pub fn set_cursor(&mut self, cursor: Option<usize>) {
+        self.cursor = cursor.map(|cursor| cursor.min(self.len_lines().saturating_sub(1)));
+    }
+
+    // NOTE: keep the cursor within the visible page by scrolling the minimum amount necessary
+    fn scroll_to_cursor(&mut self) {
+        let Some(cursor) = self.cursor else {
+            return;
+        };
+        let cursor = cursor.cast::<u16>();
+        let page_height = self.page_size.height.max(1);
+
+        if cursor < self.offset.y {
+            self.offset.y = cursor;
+        } else if self.offset.y.saturating_add(page_height) <= cursor {
+            self.offset.y = cursor.saturating_add(1).saturating_sub(page_height);
+        }
+    }
+
+    pub fn move_cursor_up(&mut self) {
+        self.set_cursor(self.cursor.map(|cursor| cursor.saturating_sub(1)));
+        self.scroll_to_cursor();
+    }
+
+    pub fn move_cursor_down(&mut self) {
+        self.set_cursor(self.cursor.map(|cursor| cursor.saturating_add(1)));
+        self.scroll_to_cursor();
+    }
+
+    // NOTE: vertical motions move the cursor line when there is one, e.g. in the focused output pane, and the viewport
+    // otherwise
+    pub fn navigate(&mut self, motion: Motion) {
+        let half_page = (self.page_size.height / 2).max(1);
+        let max_offset_y = self.max_offset_y();
+        let max_offset_x = self.max_offset_x();
+
+        match (motion, self.cursor) {
+            (Motion::Left, _cursor) => self.offset.x.saturating_sub_in_place_with_max(1, max_offset_x),
+            (Motion::Right, _cursor) => self.offset.x.saturating_add_in_place_with_max(1, max_offset_x),
+            (Motion::Up, Some(_cursor)) => self.move_cursor_up(),
+            (Motion::Down, Some(_cursor)) => self.move_cursor_down(),
+            (Motion::HalfPageUp, Some(cursor)) => self.move_cursor_to(cursor.saturating_sub(half_page.cast())),
+            (Motion::HalfPageDown, Some(cursor)) => self.move_cursor_to(cursor.saturating_add(half_page.cast())),
+            (Motion::Up, None) => self.offset.y.saturating_sub_in_place_with_max(1, max_offset_y),
+            (Motion::Down, None) => self.offset.y.saturating_add_in_place_with_max(1, max_offset_y),
+            (Motion::HalfPageUp, None) => self.offset.y.saturating_sub_in_place_with_max(half_page, max_offset_y),
+            (Motion::HalfPageDown, None) => self.offset.y.saturating_add_in_place_with_max(half_page, max_offset_y),
+            (Motion::Top, _cursor) => self.scroll_to_top(),
+            (Motion::Bottom, _cursor) => self.scroll_to_bottom(),
+            (Motion::FarLeft, _cursor) => self.scroll_to_left(),
+            (Motion::FarRight, _cursor) => self.scroll_to_right(),
+        }
+    }
+
+    pub fn search(&self) -> Option<&str> {
+        self.search.as_deref()
+    }
+
+    pub fn num_matches(&self) -> usize {
+        self.matches.len()
+    }
+
+    pub fn current_match(&self) -> Option<usize> {
+        self.current_match
+    }
+
+    // NOTE: the line whose range contains the given byte offset into the content
+    fn line_index(&self, offset: usize) -> usize {
+        self.line_ranges.partition_point(|line_range| line_range.end < offset)
+    }
+
+    // NOTE: the index of the first match on the given line or after it
+    fn first_match_from(&self, line_index: usize) -> usize {
+        let begin = self
+            .line_ranges
+            .get(line_index)
+            .map_or(usize::MAX, |line_range| line_range.start);
+
+        self.matches.partition_point(|match_range| match_range.start < begin)
+    }
+
+    fn index_matches(&mut self, line_index: usize) {
+        let (Some(search), Some(line_range)) = (&self.search, self.line_ranges.get(line_index)) else {
+            return;
+        };
+        let line = &self.content[line_range.clone()];
+
+        for (begin, matched) in line.match_indices(search.as_str()) {
+            (line_range.start + begin)
+                .range(matched.len())
+                .push_to(&mut self.matches);
+        }
+    }
+
+    // NOTE: an empty search clears it; the matches are kept up to date as lines are pushed or dropped
+    pub fn set_search(&mut self, search: Option<String>) {
+        self.search = search.filter(|search| !search.is_empty());
+        self.matches.clear();
+        self.current_match = None;
+
+        for line_index in 0..self.len_lines() {
+            self.index_matches(line_index);
+        }
+    }
+
+    // NOTE: without a current match, the search starts from the cursor line or else from the top of the page, and it
+    // wraps around at either end
+    pub fn jump_to_match(&mut self, forward: bool, scroll_off: u16) -> bool {
+        let num_matches = self.matches.len();
+
+        if num_matches == 0 {
+            return false;
+        }
+
+        let current_match = match (self.current_match, forward) {
+            (Some(current_match), true) => (current_match + 1) % num_matches,
+            (Some(current_match), false) => (current_match + num_matches - 1) % num_matches,
+            (None, forward) => {
+                let next_match = self.first_match_from(self.cursor.unwrap_or(self.offset.y.cast()));
+
+                match (forward, next_match.checked_sub(1)) {
+                    (true, _previous_match) => next_match % num_matches,
+                    (false, Some(previous_match)) => previous_match,
+                    (false, None) => num_matches - 1,
+                }
+            }
+        };
+
+        self.current_match = current_match.some();
+        self.scroll_to_match(current_match, scroll_off);
+
+        true
+    }
+
+    fn scroll_to_match(&mut self, index: usize, scroll_off: u16) {
+        let match_range = self.matches[index].clone();
+        let line_index = self.line_index(match_range.start);
+        let line_range = self.line_ranges[line_index].clone();
+        let column = self.content[line_range.start..match_range.start]
+            .len_graphemes()
+            .cast::<u16>();
+        let width = self.content[match_range].len_graphemes().cast::<u16>();
+
+        self.jump_to_line(line_index, scroll_off);
+
+        if column < self.offset.x {
+            self.offset.x = column;
+        } else if self.offset.x.saturating_add(self.page_size.width) < column.saturating_add(width) {
+            self.offset.x = column
+                .saturating_add(width)
+                .saturating_sub(self.page_size.width)
+                .min(self.max_offset_x());
+        }
+    }
+
+    // NOTE: like the other vertical jumps, these move the cursor line along when there is one; either end of the
+    // content is as far as the page goes, so there is nothing to center
+    pub fn scroll_to_top(&mut self) {
+        self.jump_to_line(0, 0);
+    }
+
+    pub fn scroll_to_bottom(&mut self) {
+        self.jump_to_line(usize::MAX, 0);
+    }
+
+    pub fn scroll_to_left(&mut self) {
+        self.offset.x = 0;
+    }
+
+    pub fn scroll_to_right(&mut self) {
+        self.offset.x = self.max_offset_x();
+    }
+
+    // NOTE: moves the cursor line along when there is one; a line that is already on the page and at least scroll_off
+    // lines away from its top and bottom is left where it is, and otherwise the page is centered on it
+    pub fn jump_to_line(&mut self, line_index: usize, scroll_off: u16) {
+        let line_index = line_index.min(self.len_lines().saturating_sub(1));
+        let row = line_index.cast::<u16>();
+        let page_height = self.page_size.height;
+        let scroll_off = scroll_off.min(page_height.saturating_sub(1) / 2);
+        let begin = self.offset.y.saturating_add(scroll_off);
+        let end = self.offset.y.saturating_add(page_height).saturating_sub(scroll_off);
+
+        if self.cursor.is_some() {
+            self.set_cursor(line_index.some());
+        }
+
+        if !(begin..end).contains(&row) {
+            self.offset.y = row.saturating_sub(page_height / 2).min(self.max_offset_y());
+        }
+    }
+
+    fn move_cursor_to(&mut self, cursor: usize) {
+        self.set_cursor(cursor.some());
+        self.scroll_to_cursor();
+    }
+
     pub fn offset(&self) -> Position {
         self.offset
     }
@@ -201,12 +580,65 @@ impl ScrollView {
         self.offset = offset;
     }
 
+    fn clamp_offset(&mut self) {
+        self.offset.x = self.offset.x.min(self.max_offset_x());
+        self.offset.y = self.offset.y.min(self.max_offset_y());
+    }
+
+    pub fn resize(&mut self, page_size: Size) {
+        self.page_size = page_size;
+
+        self.clamp_offset();
+    }
+
     pub fn push_line(&mut self, line: &str) {
-        self.content_width = self.content_width.max(line.len_graphemes().cast());
+        let width = if Ansi::contains(line) {
+            Ansi::strip(line).len_graphemes()
+        } else {
+            line.len_graphemes()
+        };
+
+        self.content_width = self.content_width.max(width.cast());
 
         self.content.len().range(line.len()).push_to(&mut self.line_ranges);
         self.content.push_str(line);
         self.content.push_str(Self::LINE_SEPARATOR);
+        self.index_matches(self.len_lines() - 1);
+    }
+
+    // NOTE: drops the oldest lines so that at most max_lines remain and shifts the remaining line ranges, the scroll
+    // offset, and the cursor along with them
+    pub fn retain_last(&mut self, max_lines: NonZeroUsize) {
+        let num_dropped = self.len_lines().saturating_sub(max_lines.get());
+        let Some(first_line_range) = self.line_ranges.get(num_dropped).filter(|_| 0 < num_dropped) else {
+            return;
+        };
+        let num_dropped_bytes = first_line_range.start;
+
+        self.content.drain(..num_dropped_bytes);
+        self.line_ranges.drain(..num_dropped);
+
+        for line_range in &mut self.line_ranges {
+            line_range.start -= num_dropped_bytes;
+            line_range.end -= num_dropped_bytes;
+        }
+
+        let num_dropped_matches = self
+            .matches
+            .partition_point(|match_range| match_range.start < num_dropped_bytes);
+
+        self.matches.drain(..num_dropped_matches);
+
+        for match_range in &mut self.matches {
+            match_range.start -= num_dropped_bytes;
+            match_range.end -= num_dropped_bytes;
+        }
+
+        self.offset.y = self.offset.y.saturating_sub(num_dropped.cast());
+        self.cursor = self.cursor.map(|cursor| cursor.saturating_sub(num_dropped));
+        self.current_match = self
+            .current_match
+            .and_then(|current_match| current_match.checked_sub(num_dropped_matches));
     }
 
     pub fn extend<T: IntoIterator>(&mut self, lines: T)
@@ -218,11 +650,21 @@ impl ScrollView {
         }
     }
 
-    pub fn render(&mut self, frame: &mut Frame, rect: Rect) {
-        self.page_size = rect.as_size();
-
-        self.render_content(frame, rect);
-        self.render_scroll_bars(frame, rect);
+    // NOTE: the line numbers take their columns from the content, which is what scrolls horizontally and what the page
+    // size covers, while the cursor highlight still spans the full width of the pane
+    pub fn render(&mut self, frame: &mut Frame, rect: Rect, theme: &Theme, line_numbers: bool) {
+        let gutter_width = self.gutter_width(line_numbers).min(rect.width);
+        let gutter_rect = Rect::new(rect.x, rect.y, gutter_width, rect.height);
+        let content_rect = Rect::new(rect.x + gutter_width, rect.y, rect.width - gutter_width, rect.height);
+
+        self.gutter_width = gutter_width;
+        self.page_size = content_rect.as_size();
+
+        self.render_gutter(frame, gutter_rect, theme.gutter);
+        self.render_content(frame, content_rect);
+        self.render_matches(frame, content_rect, theme.matched);
+        self.render_cursor(frame, rect, theme.cursor);
+        self.render_scroll_bars(frame, content_rect, theme.scrollbar);
     }
 
     pub fn take_content(&mut self) -> String {
@@ -233,12 +675,12 @@ impl ScrollView {
         content
     }
 
-    pub fn handle_mouse_event(&mut self, mouse_event: MouseEvent) {
+    pub fn handle_mouse_event(&mut self, mouse_event: MouseEvent, scroll_speed: ScrollSpeed) {
         match mouse_event.kind {
-            MouseEventKind::ScrollDown => self.scroll_down(mouse_event.modifiers),
-            MouseEventKind::ScrollUp => self.scroll_up(mouse_event.modifiers),
-            MouseEventKind::ScrollLeft => self.scroll_left(mouse_event.modifiers),
-            MouseEventKind::ScrollRight => self.scroll_right(mouse_event.modifiers),
+            MouseEventKind::ScrollDown => self.scroll_down(mouse_event.modifiers, scroll_speed),
+            MouseEventKind::ScrollUp => self.scroll_up(mouse_event.modifiers, scroll_speed),
+            MouseEventKind::ScrollLeft => self.scroll_left(mouse_event.modifiers, scroll_speed),
+            MouseEventKind::ScrollRight => self.scroll_right(mouse_event.modifiers, scroll_speed),
             ignored_mouse_event_kind => tracing::debug!(?ignored_mouse_event_kind),
         }
     }
@@ -0,0 +1,60 @@
+use crate::scroll::ScrollView;
+use ratatui::layout::Position;
+use std::collections::VecDeque;
+
+#[derive(Clone, Copy)]
+struct ScrollPosition {
+    offset: Position,
+    cursor: Option<usize>,
+}
+
+// NOTE: remembers where each view was scrolled to so that returning to it (e.g. going back to an earlier filter through
+// the editor history) restores the position; only the most recently left views are kept
+pub struct ScrollMemory<K> {
+    positions: VecDeque<(K, ScrollPosition)>,
+}
+
+impl<K: PartialEq> ScrollMemory<K> {
+    const CAPACITY: usize = 64;
+
+    pub fn new() -> Self {
+        let positions = VecDeque::new();
+
+        Self { positions }
+    }
+
+    pub fn save(&mut self, key: K, scroll_view: &ScrollView) {
+        self.positions.retain(|(saved_key, _scroll_position)| *saved_key != key);
+
+        // NOTE: views at the origin without a cursor are what a fresh view looks like anyways
+        if scroll_view.offset() == Position::ORIGIN && scroll_view.cursor().is_none() {
+            return;
+        }
+
+        if self.positions.len() == Self::CAPACITY {
+            self.positions.pop_front();
+        }
+
+        let scroll_position = ScrollPosition {
+            offset: scroll_view.offset(),
+            cursor: scroll_view.cursor(),
+        };
+
+        self.positions.push_back((key, scroll_position));
+    }
+
+    pub fn restore(&self, key: &K, scroll_view: &mut ScrollView) -> bool {
+        let Some((_key, scroll_position)) = self
+            .positions
+            .iter()
+            .find(|(saved_key, _scroll_position)| saved_key == key)
+        else {
+            return false;
+        };
+
+        scroll_view.set_offset(scroll_position.offset);
+        scroll_view.set_cursor(scroll_position.cursor);
+
+        true
+    }
+}
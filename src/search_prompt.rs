@@ -0,0 +1,69 @@
+use crate::any::Any;
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    layout::{Constraint, Flex, Layout, Rect},
+    widgets::Clear,
+    Frame,
+};
+use tui_textarea::{CursorMove, TextArea};
+
+pub enum SearchPromptEvent {
+    Submitted(String),
+    Cancelled,
+}
+
+// NOTE: the single line overlay opened with `/` for searching the focused pane, which starts from the last search so
+// that it can be refined
+pub struct SearchPrompt {
+    text_area: TextArea<'static>,
+}
+
+impl SearchPrompt {
+    const HEIGHT: u16 = 3;
+    const PERCENTAGE: u16 = 60;
+    const TITLE: &'static str = "SEARCH";
+    const SUBMIT_HINT: &'static str = "enter to search";
+    const CLEAR_HINT: &'static str = "empty to clear";
+    const CANCEL_HINT: &'static str = "esc to cancel";
+
+    // NOTE: the match hints name the keys currently bound to jumping between matches
+    pub fn new(search: Option<&str>, match_hints: &[String]) -> Self {
+        let mut text_area = search.map(str::to_owned).convert::<TextArea>();
+        let hints = std::iter::once(Self::SUBMIT_HINT)
+            .chain(match_hints.iter().map(String::as_str))
+            .chain([Self::CLEAR_HINT, Self::CANCEL_HINT])
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        text_area.move_cursor(CursorMove::End);
+        text_area.set_block(format!("{} ({hints})", Self::TITLE).block());
+
+        Self { text_area }
+    }
+
+    pub fn handle_key_event(&mut self, key_event: KeyEvent) -> Option<SearchPromptEvent> {
+        match key_event.code {
+            KeyCode::Esc => SearchPromptEvent::Cancelled.some(),
+            KeyCode::Enter => SearchPromptEvent::Submitted(self.text_area.lines()[0].clone()).some(),
+            _code => self.text_area.input(key_event).with(None),
+        }
+    }
+
+    fn rect(area: Rect) -> Rect {
+        let [rect] = Layout::vertical([Constraint::Length(Self::HEIGHT)])
+            .flex(Flex::Center)
+            .areas(area);
+        let [rect] = Layout::horizontal([Constraint::Percentage(Self::PERCENTAGE)])
+            .flex(Flex::Center)
+            .areas(rect);
+
+        rect
+    }
+
+    pub fn render(&self, frame: &mut Frame) {
+        let rect = Self::rect(frame.area());
+
+        Clear.render_to(frame, rect);
+        (&self.text_area).render_to(frame, rect);
+    }
+}
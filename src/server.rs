@@ -0,0 +1,175 @@
+use crate::{
+    any::Any,
+    channel::Channel,
+    cli_args::Engine,
+    cli_flags::CliFlags,
+    jq_process::{JqOutput, JqProcessBuilder},
+};
+use anyhow::Error;
+use axum::{extract::State, http::StatusCode, routing::post, Json, Router};
+use clap::Args;
+use serde::{Deserialize, Serialize};
+use std::{num::NonZeroUsize, sync::Arc, time::Duration};
+use tokio::{net::TcpListener, sync::Semaphore};
+use tokio_util::sync::CancellationToken;
+
+#[derive(Args)]
+pub struct ServeCliArgs {
+    #[arg(long, default_value = "127.0.0.1")]
+    host: String,
+
+    #[arg(long, default_value_t = 8080)]
+    port: u16,
+
+    #[arg(long, default_value = "4")]
+    max_jq_processes: NonZeroUsize,
+
+    #[arg(long, default_value_t = 5000)]
+    timeout_ms: u64,
+
+    #[arg(long, default_value_t = 10)]
+    jq_max_output_mb: u64,
+
+    #[arg(long, default_value_t = 512)]
+    jq_memory_limit_mb: u64,
+
+    #[arg(long, env = "RQ_JQ_BIN", default_value = "jq")]
+    jq_bin: String,
+}
+
+#[derive(Deserialize)]
+struct JqRequest {
+    input: String,
+    filter: String,
+    #[serde(default)]
+    flags: String,
+}
+
+#[derive(Serialize)]
+struct JqResponse {
+    output: String,
+    error: Option<String>,
+}
+
+impl From<JqOutput> for JqResponse {
+    fn from(jq_output: JqOutput) -> Self {
+        let output = jq_output.scroll_view().content().to_owned();
+        let error = jq_output.error().map(str::to_owned);
+
+        Self { output, error }
+    }
+}
+
+type JqResponseResult = Result<Json<JqResponse>, (StatusCode, String)>;
+
+// NOTE: every request is run through the same JqProcessBuilder/JqProcess machinery as the tui, with a semaphore
+// bounding the number of concurrent jq processes, a timeout after which the process is killed, and limits on the
+// output size and memory of each process
+pub struct Server {
+    host: String,
+    port: u16,
+    jq_bin: String,
+    semaphore: Semaphore,
+    timeout: Duration,
+    max_output_bytes: usize,
+    memory_limit_kb: u64,
+    cancellation_token: CancellationToken,
+}
+
+impl Server {
+    const ROUTE: &'static str = "/jq";
+    const KIB: u64 = 1024;
+
+    pub fn new(serve_cli_args: ServeCliArgs) -> Self {
+        let host = serve_cli_args.host;
+        let port = serve_cli_args.port;
+        let jq_bin = serve_cli_args.jq_bin;
+        let semaphore = Semaphore::new(serve_cli_args.max_jq_processes.get());
+        let timeout = Duration::from_millis(serve_cli_args.timeout_ms);
+        let max_output_bytes = serve_cli_args
+            .jq_max_output_mb
+            .saturating_mul(Self::KIB)
+            .saturating_mul(Self::KIB)
+            .cast();
+        let memory_limit_kb = serve_cli_args.jq_memory_limit_mb.saturating_mul(Self::KIB);
+        let cancellation_token = CancellationToken::new();
+
+        Self {
+            host,
+            port,
+            jq_bin,
+            semaphore,
+            timeout,
+            max_output_bytes,
+            memory_limit_kb,
+            cancellation_token,
+        }
+    }
+
+    fn error_response(status_code: StatusCode, error: &impl ToString) -> (StatusCode, String) {
+        (status_code, error.to_string())
+    }
+
+    // NOTE: the flags and filter come from any client that can reach the server, so flags that would let it read
+    // files on this host are refused before anything is run and jq is started without the environment of the server
+    async fn jq_output(&self, jq_request: &JqRequest) -> Result<JqOutput, (StatusCode, String)> {
+        CliFlags::validate_sandboxed(&jq_request.flags)
+            .map_err(|error| Self::error_response(StatusCode::BAD_REQUEST, &error.message()))?;
+
+        let _permit = self
+            .semaphore
+            .acquire()
+            .await
+            .map_err(|error| Self::error_response(StatusCode::SERVICE_UNAVAILABLE, &error))?;
+        let mut jq_outputs = Channel::new();
+        let jq_process = JqProcessBuilder {
+            engine: Engine::Jq,
+            executable: &self.jq_bin,
+            cli_flags: &jq_request.flags,
+            filter: &jq_request.filter,
+            pre_filter: None,
+            json_args: &[],
+            input: jq_request.input.as_bytes(),
+            niceness: None,
+            memory_limit_kb: self.memory_limit_kb.some(),
+            max_output_bytes: self.max_output_bytes.some(),
+            preview_bytes: None,
+            timeout: None,
+            skip_record_errors: false,
+            clear_env: true,
+            jq_outputs_sender: jq_outputs.sender.clone(),
+            cancellation_token: self.cancellation_token.child_token(),
+        }
+        .build()
+        .map_err(|error| Self::error_response(StatusCode::BAD_REQUEST, &error))?;
+
+        // NOTE: dropping the jq_process future on timeout kills the child bc of kill_on_drop
+        tokio::time::timeout(self.timeout, jq_process.run())
+            .await
+            .map_err(|error| Self::error_response(StatusCode::GATEWAY_TIMEOUT, &error))?;
+
+        jq_outputs
+            .receiver
+            .try_recv()
+            .map_err(|error| Self::error_response(StatusCode::INTERNAL_SERVER_ERROR, &error))
+    }
+
+    #[tracing::instrument(skip_all)]
+    async fn handle_jq_request(State(server): State<Arc<Self>>, Json(jq_request): Json<JqRequest>) -> JqResponseResult {
+        Json(JqResponse::from(server.jq_output(&jq_request).await?)).ok()
+    }
+
+    pub async fn serve(self) -> Result<(), Error> {
+        let listener = TcpListener::bind((self.host.as_str(), self.port)).await?;
+        let cancellation_token = self.cancellation_token.clone();
+        let router = Router::new()
+            .route(Self::ROUTE, post(Self::handle_jq_request))
+            .with_state(Arc::new(self));
+
+        tracing::info!(local_addr = ?listener.local_addr()?, "serving");
+
+        axum::serve(listener, router).await?;
+
+        cancellation_token.cancel().ok()
+    }
+}
@@ -0,0 +1,50 @@
+use crate::any::Any;
+use anyhow::Error;
+use std::{future::Future, io::Error as IoError};
+use tokio::signal::unix::{signal, Signal, SignalKind};
+
+// NOTE: a signal would otherwise end the process before anything is dropped, leaving the terminal in raw mode and
+// orphaning every running jq child, so it is received here instead and turned into a regular exit; the children are
+// spawned with kill_on_drop and the tasks owning them are dropped along with the runtime, which also happens when rq
+// exits with an error or its main task panics
+pub struct ShutdownSignal {
+    terminate: Signal,
+    hangup: Signal,
+    interrupt: Signal,
+    quit: Signal,
+}
+
+impl ShutdownSignal {
+    pub fn new() -> Result<Self, IoError> {
+        let terminate = signal(SignalKind::terminate())?;
+        let hangup = signal(SignalKind::hangup())?;
+        let interrupt = signal(SignalKind::interrupt())?;
+        let quit = signal(SignalKind::quit())?;
+
+        Self {
+            terminate,
+            hangup,
+            interrupt,
+            quit,
+        }
+        .ok()
+    }
+
+    pub async fn recv(&mut self) -> &'static str {
+        tokio::select! {
+            _signal = self.terminate.recv() => "SIGTERM",
+            _signal = self.hangup.recv() => "SIGHUP",
+            _signal = self.interrupt.recv() => "SIGINT",
+            _signal = self.quit.recv() => "SIGQUIT",
+        }
+    }
+
+    pub async fn run_until(future: impl Future<Output = Result<(), Error>>) -> Result<(), Error> {
+        let mut shutdown_signal = Self::new()?;
+
+        tokio::select! {
+            res = future => res,
+            signal = shutdown_signal.recv() => anyhow::bail!("received {signal}"),
+        }
+    }
+}
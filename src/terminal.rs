@@ -1,5 +1,6 @@
 use crate::any::Any;
 use anyhow::Error;
+use base64::{engine::general_purpose::STANDARD, Engine};
 use crossterm::{
     cursor::{Hide, Show},
     event::{DisableMouseCapture, EnableMouseCapture},
@@ -13,25 +14,46 @@ type Inner = RatatuiTerminal<CrosstermBackend<StderrLock<'static>>>;
 
 pub struct Terminal {
     inner: Inner,
+    mouse_captured: bool,
 }
 
 impl Terminal {
+    const BELL: &'static [u8] = b"\x07";
+    const TERM_VAR: &'static str = "TERM";
+    const DUMB_TERM: &'static str = "dumb";
+
     pub fn new() -> Result<Self, Error> {
         let backend = CrosstermBackend::new(std::io::stderr().lock());
         let inner = RatatuiTerminal::new(backend)?;
-        let mut terminal = Self { inner };
+        let mut terminal = Self {
+            inner,
+            mouse_captured: false,
+        };
 
         terminal.on_new()?;
 
         terminal.ok()
     }
 
+    // NOTE: terminals don't report whether they support mouse reporting, so it is only ruled out when TERM is unset or
+    // dumb, where the escape sequences would be printed as is
+    fn supports_mouse() -> bool {
+        std::env::var(Self::TERM_VAR).is_ok_and(|term| term != Self::DUMB_TERM)
+    }
+
+    fn enable_mouse_capture(&mut self) -> Result<(), Error> {
+        self.inner.backend_mut().queue(EnableMouseCapture)?.flush()?.ok()
+    }
+
+    // NOTE: the mouse only adds shortcuts for what the keyboard can do as well, so a terminal without mouse support
+    // falls back to keyboard-only mode instead of failing to start
     fn on_new(&mut self) -> Result<(), Error> {
         crossterm::terminal::enable_raw_mode()?;
 
+        self.mouse_captured = Self::supports_mouse() && self.enable_mouse_capture().log_if_error().is_some();
+
         self.inner
             .backend_mut()
-            .queue(EnableMouseCapture)?
             .queue(EnterAlternateScreen)?
             .queue(Hide)?
             .queue(Clear(ClearType::All))?
@@ -39,18 +61,40 @@ impl Terminal {
             .ok()
     }
 
+    pub fn is_mouse_captured(&self) -> bool {
+        self.mouse_captured
+    }
+
     fn on_drop(&mut self) -> Result<(), Error> {
         crossterm::terminal::disable_raw_mode()?;
 
+        if self.mouse_captured {
+            self.inner.backend_mut().queue(DisableMouseCapture)?;
+        }
+
         self.inner
             .backend_mut()
-            .queue(DisableMouseCapture)?
             .queue(LeaveAlternateScreen)?
             .queue(Show)?
             .flush()?
             .ok()
     }
 
+    pub fn bell(&mut self) -> Result<(), Error> {
+        let backend = self.inner.backend_mut();
+
+        backend.write_all(Self::BELL)?;
+        backend.flush()?.ok()
+    }
+
+    // NOTE: OSC 52 lets the terminal emulator set the system clipboard, which also works over ssh
+    pub fn copy(&mut self, text: &str) -> Result<(), Error> {
+        let backend = self.inner.backend_mut();
+
+        write!(backend, "\x1b]52;c;{text}\x07", text = STANDARD.encode(text))?;
+        backend.flush()?.ok()
+    }
+
     pub fn inner(&mut self) -> &mut Inner {
         &mut self.inner
     }
@@ -0,0 +1,225 @@
+use crate::{any::Any, cli_args::ColorPolicy};
+use anyhow::Error;
+use ratatui::{
+    buffer::Buffer,
+    style::{Color, Modifier, Style},
+};
+use std::str::FromStr;
+
+#[derive(Clone, Copy)]
+enum ThemeElement {
+    Border,
+    Success,
+    Error,
+    Warning,
+    Flash,
+    Matched,
+    Scrollbar,
+    Cursor,
+    Gutter,
+    SyntaxKeyword,
+    SyntaxBuiltin,
+    SyntaxString,
+    SyntaxVariable,
+    SyntaxPipe,
+    SyntaxBracket,
+    SyntaxComment,
+}
+
+// NOTE: one `--theme` value, e.g. `error=magenta`, `scrollbar=blue+reversed`, or `border=#808080+dim`, where the style
+// is a `+`-separated list of at most one foreground color and any number of modifiers
+#[derive(Clone)]
+pub struct ThemeEntry {
+    element: ThemeElement,
+    style: Style,
+}
+
+impl ThemeEntry {
+    const ELEMENTS: &'static [(&'static str, ThemeElement)] = &[
+        ("border", ThemeElement::Border),
+        ("success", ThemeElement::Success),
+        ("error", ThemeElement::Error),
+        ("warning", ThemeElement::Warning),
+        ("flash", ThemeElement::Flash),
+        ("matched", ThemeElement::Matched),
+        ("scrollbar", ThemeElement::Scrollbar),
+        ("cursor", ThemeElement::Cursor),
+        ("gutter", ThemeElement::Gutter),
+        ("syntax-keyword", ThemeElement::SyntaxKeyword),
+        ("syntax-builtin", ThemeElement::SyntaxBuiltin),
+        ("syntax-string", ThemeElement::SyntaxString),
+        ("syntax-variable", ThemeElement::SyntaxVariable),
+        ("syntax-pipe", ThemeElement::SyntaxPipe),
+        ("syntax-bracket", ThemeElement::SyntaxBracket),
+        ("syntax-comment", ThemeElement::SyntaxComment),
+    ];
+    const MODIFIERS: &'static [(&'static str, Modifier)] = &[
+        ("bold", Modifier::BOLD),
+        ("dim", Modifier::DIM),
+        ("italic", Modifier::ITALIC),
+        ("underlined", Modifier::UNDERLINED),
+        ("reversed", Modifier::REVERSED),
+        ("crossed-out", Modifier::CROSSED_OUT),
+    ];
+    const SEPARATOR: char = '+';
+    const PLAIN_STYLE: &'static str = "plain";
+
+    fn parse_style(spec: &str) -> Result<Style, Error> {
+        let mut style = Style::new();
+
+        if spec == Self::PLAIN_STYLE {
+            return style.ok();
+        }
+
+        for word in spec.split(Self::SEPARATOR).map(str::trim) {
+            if let Some((_name, modifier)) = Self::MODIFIERS.iter().find(|(name, _modifier)| *name == word) {
+                style = style.add_modifier(*modifier);
+            } else if let Ok(color) = Color::from_str(word) {
+                style = style.fg(color);
+            } else {
+                anyhow::bail!("unknown color or modifier {word:?}");
+            }
+        }
+
+        style.ok()
+    }
+}
+
+impl FromStr for ThemeEntry {
+    type Err = Error;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let (name, spec) = value
+            .split_once('=')
+            .ok_or_error::<(&str, &str)>("expected a theme entry of the form ELEMENT=STYLE")?;
+        let Some((_name, element)) = Self::ELEMENTS
+            .iter()
+            .find(|(element_name, _element)| *element_name == name.trim())
+        else {
+            anyhow::bail!("unknown theme element {name:?}");
+        };
+        let element = *element;
+        let style = Self::parse_style(spec.trim())?;
+
+        Self { element, style }.ok()
+    }
+}
+
+// NOTE: the styles of the parts of the ui that signal state, starting from the defaults below and overridden by the
+// `--theme` entries in order; the color policy is applied to the whole frame once every widget has rendered so that
+// all of them obey it without each needing to know about it, and modifiers like reversed, dim, and underlined are kept
+// so that focus, staleness, and errors remain visible without color
+#[derive(Clone, Copy)]
+pub struct Theme {
+    colored: bool,
+    pub border: Style,
+    pub success: Style,
+    pub error: Style,
+    pub warning: Style,
+    pub flash: Style,
+    pub matched: Style,
+    pub scrollbar: Style,
+    pub cursor: Style,
+    pub gutter: Style,
+    pub syntax_keyword: Style,
+    pub syntax_builtin: Style,
+    pub syntax_string: Style,
+    pub syntax_variable: Style,
+    pub syntax_pipe: Style,
+    pub syntax_bracket: Style,
+    pub syntax_comment: Style,
+}
+
+impl Theme {
+    pub fn new(color_policy: ColorPolicy, theme_entries: &[ThemeEntry]) -> Self {
+        let colored = color_policy.is_colored();
+        let mut theme = Self {
+            colored,
+            border: Style::new(),
+            success: Style::new(),
+            error: Style::new().fg(Color::Red),
+            warning: Style::new().fg(Color::Yellow),
+            flash: Style::new().fg(Color::Yellow),
+            matched: Style::new().fg(Color::Green).add_modifier(Modifier::BOLD),
+            scrollbar: Style::new().add_modifier(Modifier::REVERSED),
+            cursor: Style::new().add_modifier(Modifier::REVERSED),
+            gutter: Style::new().add_modifier(Modifier::DIM),
+            syntax_keyword: Style::new().fg(Color::Magenta),
+            syntax_builtin: Style::new().fg(Color::Blue),
+            syntax_string: Style::new().fg(Color::Green),
+            syntax_variable: Style::new().fg(Color::Cyan),
+            syntax_pipe: Style::new().fg(Color::Magenta).add_modifier(Modifier::BOLD),
+            syntax_bracket: Style::new().fg(Color::Yellow),
+            syntax_comment: Style::new().fg(Color::DarkGray),
+        };
+
+        for theme_entry in theme_entries {
+            let style = match theme_entry.element {
+                ThemeElement::Border => &mut theme.border,
+                ThemeElement::Success => &mut theme.success,
+                ThemeElement::Error => &mut theme.error,
+                ThemeElement::Warning => &mut theme.warning,
+                ThemeElement::Flash => &mut theme.flash,
+                ThemeElement::Matched => &mut theme.matched,
+                ThemeElement::Scrollbar => &mut theme.scrollbar,
+                ThemeElement::Cursor => &mut theme.cursor,
+                ThemeElement::Gutter => &mut theme.gutter,
+                ThemeElement::SyntaxKeyword => &mut theme.syntax_keyword,
+                ThemeElement::SyntaxBuiltin => &mut theme.syntax_builtin,
+                ThemeElement::SyntaxString => &mut theme.syntax_string,
+                ThemeElement::SyntaxVariable => &mut theme.syntax_variable,
+                ThemeElement::SyntaxPipe => &mut theme.syntax_pipe,
+                ThemeElement::SyntaxBracket => &mut theme.syntax_bracket,
+                ThemeElement::SyntaxComment => &mut theme.syntax_comment,
+            };
+
+            *style = theme_entry.style;
+        }
+
+        theme
+    }
+
+    pub fn apply(&self, buffer: &mut Buffer) {
+        if self.colored {
+            return;
+        }
+
+        for cell in &mut buffer.content {
+            cell.set_fg(Color::Reset).set_bg(Color::Reset);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ThemeElement, ThemeEntry};
+    use ratatui::style::{Color, Modifier, Style};
+
+    #[test]
+    fn from_str_reads_a_color_and_modifiers() {
+        let theme_entry = " scrollbar = #808080 + reversed+dim ".parse::<ThemeEntry>().unwrap();
+
+        assert!(matches!(theme_entry.element, ThemeElement::Scrollbar));
+        assert_eq!(
+            theme_entry.style,
+            Style::new()
+                .fg(Color::Rgb(0x80, 0x80, 0x80))
+                .add_modifier(Modifier::REVERSED | Modifier::DIM)
+        );
+    }
+
+    #[test]
+    fn from_str_reads_the_plain_style() {
+        let theme_entry = "error=plain".parse::<ThemeEntry>().unwrap();
+
+        assert!(matches!(theme_entry.element, ThemeElement::Error));
+        assert_eq!(theme_entry.style, Style::new());
+    }
+
+    #[test]
+    fn from_str_rejects_invalid_entries() {
+        for value in ["error", "background=red", "error=red+blinking", "error=", "syntax=red"] {
+            assert!(value.parse::<ThemeEntry>().is_err(), "{value}");
+        }
+    }
+}
@@ -0,0 +1,264 @@
+use crate::{any::Any, theme::Theme};
+use anyhow::Error;
+use crossterm::event::KeyEvent;
+use ratatui::{
+    layout::{Constraint, Flex, Layout, Rect},
+    text::Line,
+    widgets::Clear,
+    Frame,
+};
+use regex::Regex;
+use std::str::FromStr;
+use tui_textarea::TextArea;
+
+enum TimeBound {
+    Ago(u64),
+    At(String),
+}
+
+impl TimeBound {
+    const DATE_PATTERN: &'static str = r"^(\d{4}-\d{2}-\d{2})(?:T(\d{2}:\d{2})(:\d{2})?Z?)?$";
+    const UNITS: &'static [(char, u64)] = &[('s', 1), ('m', 60), ('h', 60 * 60), ('d', 24 * 60 * 60)];
+
+    fn ago(text: &str) -> Option<Self> {
+        Self::UNITS.iter().find_map(|(unit, seconds)| {
+            let count = text.strip_suffix(*unit)?.parse::<u64>().ok()?;
+
+            Self::Ago(count.saturating_mul(*seconds)).some()
+        })
+    }
+
+    // NOTE: normalizes to the only format fromdateiso8601 accepts, i.e. 2024-01-01T00:00:00Z
+    fn at(text: &str) -> Option<Self> {
+        let regex = Regex::new(Self::DATE_PATTERN).log_if_error()?;
+        let captures = regex.captures(text)?;
+        let date = captures.get(1)?.as_str();
+        let time = captures.get(2).map_or("00:00", |time| time.as_str());
+        let seconds = captures.get(3).map_or(":00", |seconds| seconds.as_str());
+
+        Self::At(format!("{date}T{time}{seconds}Z")).some()
+    }
+
+    fn parse(text: &str) -> Result<Option<Self>, Error> {
+        let text = text.trim();
+
+        if text.is_empty() {
+            return None.ok();
+        }
+
+        let Some(time_bound) = Self::ago(text).or_else(|| Self::at(text)) else {
+            anyhow::bail!("{text:?} is neither a duration like 5m nor a time like 2024-01-01T12:00");
+        };
+
+        time_bound.some().ok()
+    }
+
+    fn epoch_expr(&self) -> String {
+        match self {
+            Self::Ago(seconds) => format!("(now - {seconds})"),
+            Self::At(time) => format!("(\"{time}\" | fromdateiso8601)"),
+        }
+    }
+
+    fn iso_expr(&self) -> String {
+        match self {
+            Self::Ago(seconds) => format!("(now - {seconds} | todate)"),
+            Self::At(time) => format!("\"{time}\""),
+        }
+    }
+}
+
+// NOTE: restricts the input to documents whose timestamp field falls within a window, either relative to now (e.g.
+// `5m`) or between two times (e.g. `2024-01-01T10:00..2024-01-01T11:00`, where either side may be omitted or be a
+// duration); numeric timestamps are taken as epoch seconds (or milliseconds when large enough) and string timestamps
+// are compared as utc iso-8601 strings, which order lexicographically
+pub struct TimeWindow {
+    description: String,
+    start: Option<TimeBound>,
+    end: Option<TimeBound>,
+}
+
+impl TimeWindow {
+    const RANGE_SEPARATOR: &'static str = "..";
+    const EPOCH_MILLIS_THRESHOLD: u64 = 100_000_000_000;
+
+    pub fn description(&self) -> &str {
+        &self.description
+    }
+
+    fn condition(bounds: &[(&str, Option<String>)]) -> String {
+        let conditions = bounds
+            .iter()
+            .filter_map(|(operator, bound)| bound.as_ref().map(|bound| format!("$t {operator} {bound}")))
+            .collect::<Vec<_>>();
+
+        if conditions.is_empty() {
+            "true".to_owned()
+        } else {
+            conditions.join(" and ")
+        }
+    }
+
+    pub fn pre_filter(&self, field: &str) -> String {
+        let epoch_condition = Self::condition(&[
+            (">=", self.start.as_ref().map(TimeBound::epoch_expr)),
+            ("<=", self.end.as_ref().map(TimeBound::epoch_expr)),
+        ]);
+        let iso_condition = Self::condition(&[
+            (">=", self.start.as_ref().map(TimeBound::iso_expr)),
+            ("<=", self.end.as_ref().map(TimeBound::iso_expr)),
+        ]);
+        let threshold = Self::EPOCH_MILLIS_THRESHOLD;
+
+        // NOTE: documents other than objects (e.g. the null of --null-input) pass through untouched and slurped
+        // arrays are filtered element-wise
+        format!(
+            "def __rq_time_window: select(type != \"object\" or ({field} as $t | if ($t | type) == \"number\" then \
+             ($t | if . > {threshold} then . / 1000 else . end) as $t | {epoch_condition} elif ($t | type) == \
+             \"string\" then {iso_condition} else false end)); if type == \"array\" then map(__rq_time_window) else \
+             __rq_time_window end"
+        )
+    }
+}
+
+impl FromStr for TimeWindow {
+    type Err = Error;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let description = text.trim().to_owned();
+        let (start, end) = match description.split_once(Self::RANGE_SEPARATOR) {
+            Some((start, end)) => (TimeBound::parse(start)?, TimeBound::parse(end)?),
+            None => (TimeBound::parse(&description)?, None),
+        };
+
+        anyhow::ensure!(start.is_some() || end.is_some(), "expected a duration or a time range");
+
+        Self {
+            description,
+            start,
+            end,
+        }
+        .ok()
+    }
+}
+
+pub struct TimeWindowEditor {
+    text_area: TextArea<'static>,
+    error: Option<String>,
+}
+
+impl TimeWindowEditor {
+    const HEIGHT: u16 = 3;
+    const PERCENTAGE: u16 = 60;
+    const TITLE: &'static str = "TIME WINDOW e.g. 5m, 1h, START..END (enter to apply, empty to clear, esc to cancel)";
+
+    pub fn new(time_window: Option<&TimeWindow>) -> Self {
+        let text_area = time_window
+            .map(|time_window| time_window.description.clone())
+            .convert::<TextArea>();
+        let error = None;
+
+        Self { text_area, error }
+    }
+
+    pub fn handle_key_event(&mut self, key_event: KeyEvent) {
+        if self.text_area.input(key_event) {
+            self.error = None;
+        }
+    }
+
+    pub fn set_error(&mut self, error: &Error) {
+        self.error = error.to_string().some();
+    }
+
+    pub fn time_window(&self) -> Result<Option<TimeWindow>, Error> {
+        let text = self.text_area.lines()[0].trim();
+
+        if text.is_empty() {
+            return None.ok();
+        }
+
+        text.parse::<TimeWindow>()?.some().ok()
+    }
+
+    fn rect(area: Rect) -> Rect {
+        let [rect] = Layout::vertical([Constraint::Length(Self::HEIGHT)])
+            .flex(Flex::Center)
+            .areas(area);
+        let [rect] = Layout::horizontal([Constraint::Percentage(Self::PERCENTAGE)])
+            .flex(Flex::Center)
+            .areas(rect);
+
+        rect
+    }
+
+    pub fn render(&mut self, frame: &mut Frame, theme: &Theme, field: &str) {
+        let rect = Self::rect(frame.area());
+        let block = match &self.error {
+            Some(error) => Self::TITLE
+                .block()
+                .title_bottom(Line::styled(error.clone(), theme.error)),
+            None => Self::TITLE
+                .block()
+                .title_bottom(format!("field: {field} (see --time-field)")),
+        };
+
+        self.text_area.set_block(block);
+
+        Clear.render_to(frame, rect);
+        (&self.text_area).render_to(frame, rect);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{TimeBound, TimeWindow};
+
+    fn bounds(text: &str) -> (Option<String>, Option<String>) {
+        let time_window = text.parse::<TimeWindow>().unwrap();
+
+        (
+            time_window.start.as_ref().map(TimeBound::iso_expr),
+            time_window.end.as_ref().map(TimeBound::iso_expr),
+        )
+    }
+
+    #[test]
+    fn from_str_reads_a_duration_as_the_start() {
+        assert_eq!(bounds(" 5m "), ("(now - 300 | todate)".to_owned().into(), None));
+        assert_eq!(bounds("2d"), ("(now - 172800 | todate)".to_owned().into(), None));
+    }
+
+    #[test]
+    fn from_str_normalizes_times() {
+        assert_eq!(
+            bounds("2024-01-01..2024-01-01T11:30:15Z"),
+            (
+                "\"2024-01-01T00:00:00Z\"".to_owned().into(),
+                "\"2024-01-01T11:30:15Z\"".to_owned().into()
+            )
+        );
+        assert_eq!(
+            bounds("..2024-01-01T10:00"),
+            (None, "\"2024-01-01T10:00:00Z\"".to_owned().into())
+        );
+        assert_eq!(bounds("1h.."), ("(now - 3600 | todate)".to_owned().into(), None));
+    }
+
+    #[test]
+    fn from_str_rejects_invalid_windows() {
+        for text in ["", "..", "5x", "yesterday", "2024-1-1", "5m..soon"] {
+            assert!(text.parse::<TimeWindow>().is_err(), "{text}");
+        }
+    }
+
+    #[test]
+    fn pre_filter_compares_numbers_as_epoch_seconds() {
+        let pre_filter = "10s".parse::<TimeWindow>().unwrap().pre_filter(".ts");
+
+        assert!(pre_filter.contains(".ts as $t"));
+        assert!(pre_filter.contains("$t >= (now - 10)"));
+        assert!(pre_filter.contains("$t >= (now - 10 | todate)"));
+        assert!(!pre_filter.contains("$t <="));
+    }
+}
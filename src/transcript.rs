@@ -0,0 +1,95 @@
+use crate::{any::Any, jq_process::JqOutput};
+use std::fmt::Write;
+
+struct TranscriptEntry {
+    cli_flags: String,
+    filter: String,
+    sample: String,
+    num_lines: usize,
+    error: Option<String>,
+}
+
+impl TranscriptEntry {
+    fn new(jq_output: &JqOutput) -> Self {
+        let cli_flags = jq_output.cli_flags().trim().to_owned();
+        let filter = jq_output.filter().to_owned();
+        let scroll_view = jq_output.scroll_view();
+        let sample = (0..scroll_view.len_lines().min(Transcript::SAMPLE_MAX_LINES))
+            .filter_map(|index| scroll_view.line(index))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let num_lines = scroll_view.len_lines();
+        let error = jq_output.error().map(str::to_owned);
+
+        Self {
+            cli_flags,
+            filter,
+            sample,
+            num_lines,
+            error,
+        }
+    }
+
+    fn is_same_run(&self, other: &Self) -> bool {
+        self.cli_flags == other.cli_flags && self.filter == other.filter
+    }
+}
+
+// NOTE: the transcript records every executed filter once, so a rerun of the same filter and flags (e.g. bc more input
+// arrived) replaces the previous entry instead of adding a duplicate
+pub struct Transcript {
+    entries: Vec<TranscriptEntry>,
+}
+
+impl Transcript {
+    const SAMPLE_MAX_LINES: usize = 20;
+    const TITLE: &'static str = "# rq session transcript";
+
+    pub fn new() -> Self {
+        let entries = Vec::new();
+
+        Self { entries }
+    }
+
+    pub fn record(&mut self, jq_output: &JqOutput) {
+        let entry = TranscriptEntry::new(jq_output);
+
+        match self.entries.last_mut() {
+            Some(last_entry) if last_entry.is_same_run(&entry) => *last_entry = entry,
+            _ => self.entries.push(entry),
+        }
+    }
+
+    // NOTE: writing to a String is infallible, so the fmt results are ignored
+    pub fn to_markdown(&self) -> String {
+        let mut markdown = format!("{title}\n", title = Self::TITLE);
+
+        for (index, entry) in self.entries.iter().enumerate() {
+            write!(markdown, "\n## {number}\n\n", number = index + 1).unit();
+
+            if !entry.cli_flags.is_empty() {
+                write!(markdown, "flags: `{cli_flags}`\n\n", cli_flags = entry.cli_flags).unit();
+            }
+
+            write!(markdown, "```jq\n{filter}\n```\n\n", filter = entry.filter).unit();
+
+            if let Some(error) = &entry.error {
+                write!(markdown, "error: `{error}`\n\n").unit();
+            }
+
+            write!(markdown, "```json\n{sample}\n```\n", sample = entry.sample).unit();
+
+            if entry.num_lines > Self::SAMPLE_MAX_LINES {
+                write!(
+                    markdown,
+                    "\n_showing {max_lines} of {num_lines} lines_\n",
+                    max_lines = Self::SAMPLE_MAX_LINES,
+                    num_lines = entry.num_lines,
+                )
+                .unit();
+            }
+        }
+
+        markdown
+    }
+}